@@ -0,0 +1,200 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, mat3_apply, mat3_inverse, mat3_mul, Channel, Matrix3, Rgb, Xyz, IDENTITY3};
+
+/// The cone response model used to adapt colors between white points.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum AdaptationMethod {
+    /// The Bradford transform, used by ICC profiles.
+    Bradford,
+
+    /// The von Kries transform, using the Hunt-Pointer-Estevez cone
+    /// responses.
+    VonKries,
+
+    /// Scaling of the XYZ components directly. This is the simplest, and
+    /// least accurate, method.
+    XyzScaling,
+
+    /// The transform from the CIECAM02 color appearance model.
+    Cat02,
+
+    /// The transform from the CAM16 color appearance model.
+    Cat16
+}
+
+impl AdaptationMethod {
+    /// The matrix from XYZ to the cone response space of this method.
+    fn cone_response(self) -> Matrix3 {
+        match self {
+            AdaptationMethod::Bradford => [
+                [0.8951, 0.2664, -0.1614],
+                [-0.7502, 1.7135, 0.0367],
+                [0.0389, -0.0685, 1.0296]],
+            AdaptationMethod::VonKries => [
+                [0.40024, 0.70760, -0.08081],
+                [-0.22630, 1.16532, 0.04570],
+                [0.0, 0.0, 0.91822]],
+            AdaptationMethod::XyzScaling => IDENTITY3,
+            AdaptationMethod::Cat02 => [
+                [0.7328, 0.4296, -0.1624],
+                [-0.7036, 1.6975, 0.0061],
+                [0.0030, 0.0136, 0.9834]],
+            AdaptationMethod::Cat16 => [
+                [0.401288, 0.650173, -0.051461],
+                [-0.250268, 1.204414, 0.045854],
+                [-0.002079, 0.048952, 0.953127]]
+        }
+    }
+}
+
+/// A linear transform that maps colors seen under one white point to the
+/// colors that appear the same under another white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChromaticAdaptation {
+    /// The XYZ to XYZ matrix
+    matrix: Matrix3
+}
+
+impl ChromaticAdaptation {
+    /// Build the adaptation from the source white point to the destination
+    /// white point, using the given method.
+    ///
+    /// ```rust
+    /// use simplecolor::{AdaptationMethod, ChromaticAdaptation, Xyz};
+    ///
+    /// let d65_to_d50 = ChromaticAdaptation::new(AdaptationMethod::Bradford,
+    ///                                           &Xyz::<f64>::d65(), &Xyz::d50());
+    /// let white = d65_to_d50.adapt_xyz(&Xyz::<f64>::d65());
+    /// ```
+    pub fn new<F: Float>(method: AdaptationMethod, source: &Xyz<F>,
+                         destination: &Xyz<F>) -> ChromaticAdaptation {
+        let cone = method.cone_response();
+        let source = mat3_apply(&cone, to_vector(source));
+        let destination = mat3_apply(&cone, to_vector(destination));
+        let scale = [
+            [destination[0] / source[0], 0.0, 0.0],
+            [0.0, destination[1] / source[1], 0.0],
+            [0.0, 0.0, destination[2] / source[2]]];
+        ChromaticAdaptation::from_matrix(
+            mat3_mul(&mat3_inverse(&cone), &mat3_mul(&scale, &cone)))
+    }
+
+    /// Create an adaptation from an XYZ to XYZ matrix, stored by rows.
+    pub fn from_matrix(matrix: [[f64; 3]; 3]) -> ChromaticAdaptation {
+        ChromaticAdaptation {
+            matrix: matrix
+        }
+    }
+
+    /// Returns the XYZ to XYZ matrix of the adaptation, stored by rows.
+    #[inline]
+    pub fn matrix(&self) -> [[f64; 3]; 3] { self.matrix }
+
+    /// Returns the adaptation in the opposite direction.
+    pub fn inverse(&self) -> ChromaticAdaptation {
+        ChromaticAdaptation::from_matrix(mat3_inverse(&self.matrix))
+    }
+
+    /// Returns the adaptation that applies this adaptation followed by
+    /// another.
+    pub fn then(&self, next: &ChromaticAdaptation) -> ChromaticAdaptation {
+        ChromaticAdaptation::from_matrix(mat3_mul(&next.matrix, &self.matrix))
+    }
+
+    /// Adapt an XYZ color.
+    pub fn adapt_xyz<F: Float>(&self, xyz: &Xyz<F>) -> Xyz<F> {
+        let v = mat3_apply(&self.matrix, to_vector(xyz));
+        Xyz::with_components(float(v[0]), float(v[1]), float(v[2]))
+    }
+
+    /// Adapt a linear light color, assuming it is in the sRGB space and
+    /// converting through XYZ with the sRGB matrices. Use `RgbSpace::adapt`
+    /// for colors in other RGB spaces.
+    pub fn adapt_linear_rgb<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        self.adapt_xyz(&Xyz::from_linear_rgb(rgb)).to_linear_rgb()
+    }
+
+    /// Adapt an sRGB encoded color, decoding it to linear light first. Like
+    /// `adapt_linear_rgb`, this assumes the sRGB primaries; use
+    /// `RgbSpace::adapt` for colors in other RGB spaces.
+    pub fn adapt_rgb<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        self.adapt_linear_rgb(&rgb.to_linear()).to_srgb()
+    }
+}
+
+/// The components of an XYZ color as a column vector.
+fn to_vector<F: Float>(xyz: &Xyz<F>) -> [f64; 3] {
+    let (x, y, z) = xyz.components();
+    [x.to_f64().unwrap(), y.to_f64().unwrap(), z.to_f64().unwrap()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, Xyz};
+
+    const METHODS: [AdaptationMethod; 5] = [
+        AdaptationMethod::Bradford, AdaptationMethod::VonKries, AdaptationMethod::XyzScaling,
+        AdaptationMethod::Cat02, AdaptationMethod::Cat16];
+
+    #[test]
+    fn bradford_d65_to_d50_matches_reference_matrix() {
+        let expected = [
+            [1.0478112, 0.0228866, -0.0501270],
+            [0.0295424, 0.9904844, -0.0170491],
+            [-0.0092345, 0.0150436, 0.7521316]];
+        let matrix = ChromaticAdaptation::new(AdaptationMethod::Bradford,
+                                              &Xyz::<f64>::d65(), &Xyz::d50()).matrix();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((matrix[i][j] - expected[i][j]).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn source_white_maps_to_destination_white() {
+        for &method in METHODS.iter() {
+            let adaptation = ChromaticAdaptation::new(method, &Xyz::<f64>::d65(), &Xyz::d50());
+            let (x, y, z) = adaptation.adapt_xyz(&Xyz::<f64>::d65()).components();
+            let (wx, wy, wz) = Xyz::<f64>::d50().components();
+            assert!((x - wx).abs() < 1e-9 && (y - wy).abs() < 1e-9 && (z - wz).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn adaptation_round_trips_through_inverse() {
+        let adaptation = ChromaticAdaptation::new(AdaptationMethod::Cat16, &Xyz::<f64>::d50(), &Xyz::d65());
+        let round_trip = adaptation.then(&adaptation.inverse());
+        let col = Rgb::with_components(0.2f64, 0.7, 0.4);
+        let (r, g, b) = round_trip.adapt_rgb(&col).components();
+        assert!((r - 0.2).abs() < 1e-6 && (g - 0.7).abs() < 1e-6 && (b - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn adapting_to_the_same_white_does_nothing() {
+        let adaptation = ChromaticAdaptation::new(AdaptationMethod::Bradford, &Xyz::<f64>::d65(), &Xyz::d65());
+        let (r, g, b) = adaptation.adapt_rgb(&Rgb::with_components(0.9f32, 0.1, 0.3)).components();
+        assert!((r - 0.9).abs() < 1e-5 && (g - 0.1).abs() < 1e-5 && (b - 0.3).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,468 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp::Ordering;
+
+use super::{clamp, Channel, Lut3d, Rgb};
+
+/// A tonal adjustment applied to each channel independently, which can be
+/// baked into a 1D lookup table.
+pub trait Adjustment {
+    /// Adjust the value of one channel, where 0 is red, 1 is green and 2
+    /// is blue.
+    fn adjust_channel(&self, channel: usize, x: f64) -> f64;
+
+    /// Adjust a color.
+    fn apply<T: Channel>(&self, rgb: &Rgb<T>) -> Rgb<T> {
+        Rgb::with_components(T::from_float(self.adjust_channel(0, rgb.r().to_float())),
+                             T::from_float(self.adjust_channel(1, rgb.g().to_float())),
+                             T::from_float(self.adjust_channel(2, rgb.b().to_float())))
+    }
+
+    /// Adjust a buffer of colors in place.
+    fn apply_in_place<T: Channel>(&self, pixels: &mut [Rgb<T>]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+
+    /// Sample the adjustment into a 1D table with the given number of
+    /// entries, covering inputs between zero and one. A table of 256
+    /// entries reproduces the adjustment exactly for `Rgb<u8>`.
+    fn bake(&self, size: usize) -> Lut3d {
+        assert!(size >= 2, "a lookup table needs at least two entries");
+        let table: Vec<Rgb<f64>> = (0..size).map(|i| {
+            let x = i as f64 / (size - 1) as f64;
+            Rgb::with_components(self.adjust_channel(0, x), self.adjust_channel(1, x), self.adjust_channel(2, x))
+        }).collect();
+        Lut3d::from_1d_table(&table).unwrap()
+    }
+}
+
+/// A levels adjustment: the input black and white points are stretched to
+/// the full range, a gamma adjusts the midtones, and the result is
+/// compressed into the output range.
+///
+/// ```rust
+/// use simplecolor::{Adjustment, Levels, Rgb};
+///
+/// let mut levels = Levels::new();
+/// levels.set_input(0.05, 0.9);
+/// levels.set_gamma(1.2);
+/// let col = levels.apply(&Rgb::with_components(30u8, 120, 200));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Levels {
+    /// Input black point
+    input_black: f64,
+
+    /// Input white point
+    input_white: f64,
+
+    /// Midtone gamma
+    gamma: f64,
+
+    /// Output black point
+    output_black: f64,
+
+    /// Output white point
+    output_white: f64
+}
+
+impl Levels {
+    /// Create a levels adjustment which leaves colors unchanged.
+    pub fn new() -> Levels {
+        Levels {
+            input_black: 0.0,
+            input_white: 1.0,
+            gamma: 1.0,
+            output_black: 0.0,
+            output_white: 1.0
+        }
+    }
+
+    /// Returns the input black and white points.
+    #[inline]
+    pub fn input(&self) -> (f64, f64) { (self.input_black, self.input_white) }
+
+    /// Returns the midtone gamma.
+    #[inline]
+    pub fn gamma(&self) -> f64 { self.gamma }
+
+    /// Returns the output black and white points.
+    #[inline]
+    pub fn output(&self) -> (f64, f64) { (self.output_black, self.output_white) }
+
+    /// Set the input values mapped to black and white. Panics unless black
+    /// is below white.
+    pub fn set_input(&mut self, black: f64, white: f64) {
+        assert!(black < white, "the input black point must be below the white point");
+        self.input_black = black;
+        self.input_white = white;
+    }
+
+    /// Set the midtone gamma. Values above one brighten the midtones, as in
+    /// most photo editors. Panics unless the gamma is positive.
+    pub fn set_gamma(&mut self, gamma: f64) {
+        assert!(gamma > 0.0, "the gamma must be positive");
+        self.gamma = gamma;
+    }
+
+    /// Set the output values for black and white. White may be below black
+    /// to invert the image.
+    pub fn set_output(&mut self, black: f64, white: f64) {
+        self.output_black = black;
+        self.output_white = white;
+    }
+}
+
+impl Default for Levels {
+    /// Identical to ```Levels::new()```.
+    fn default() -> Levels {
+        Levels::new()
+    }
+}
+
+impl Adjustment for Levels {
+    fn adjust_channel(&self, _: usize, x: f64) -> f64 {
+        let x = clamp((x - self.input_black) / (self.input_white - self.input_black), 0.0, 1.0);
+        self.output_black + x.powf(1.0 / self.gamma) * (self.output_white - self.output_black)
+    }
+}
+
+/// A tone curve through a set of control points, interpolated with a
+/// monotonic cubic spline (Fritsch-Carlson), so that the curve never
+/// overshoots between points. Inputs outside of the control points take
+/// the value of the nearest end point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToneCurve {
+    /// Control points, sorted by input
+    points: Vec<(f64, f64)>,
+
+    /// Slope of the curve at each control point
+    slopes: Vec<f64>
+}
+
+impl ToneCurve {
+    /// Create the identity curve.
+    pub fn identity() -> ToneCurve {
+        ToneCurve::new(&[(0.0, 0.0), (1.0, 1.0)])
+    }
+
+    /// Create a curve through control points given as (input, output)
+    /// pairs, in any order. Panics if there are fewer than two points or
+    /// two points share an input.
+    pub fn new(points: &[(f64, f64)]) -> ToneCurve {
+        assert!(points.len() >= 2, "a curve needs at least two control points");
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        let n = points.len();
+        let secants: Vec<f64> = points.windows(2).map(|w| {
+            assert!(w[1].0 > w[0].0, "control points must have distinct inputs");
+            (w[1].1 - w[0].1) / (w[1].0 - w[0].0)
+        }).collect();
+
+        let mut slopes = vec![0.0; n];
+        slopes[0] = secants[0];
+        slopes[n - 1] = secants[n - 2];
+        for i in 1..n - 1 {
+            slopes[i] = if secants[i - 1] * secants[i] <= 0.0 {
+                0.0
+            } else {
+                (secants[i - 1] + secants[i]) / 2.0
+            };
+        }
+        // Limit the slopes so that each segment stays monotonic.
+        for i in 0..n - 1 {
+            if secants[i] == 0.0 {
+                slopes[i] = 0.0;
+                slopes[i + 1] = 0.0;
+                continue;
+            }
+            let (a, b) = (slopes[i] / secants[i], slopes[i + 1] / secants[i]);
+            let length = a.hypot(b);
+            if length > 3.0 {
+                slopes[i] = 3.0 / length * a * secants[i];
+                slopes[i + 1] = 3.0 / length * b * secants[i];
+            }
+        }
+
+        ToneCurve {
+            points: points,
+            slopes: slopes
+        }
+    }
+
+    /// Returns the control points, sorted by input.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Evaluate the curve. Inputs outside of the control points, and NaN,
+    /// give the output of the nearest end point.
+    pub fn eval(&self, x: f64) -> f64 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if x <= first.0 || x.is_nan() {
+            return first.1;
+        }
+        if x >= last.0 {
+            return last.1;
+        }
+        let i = self.points.iter().rposition(|point| point.0 <= x).unwrap();
+        let ((x0, y0), (x1, y1)) = (self.points[i], self.points[i + 1]);
+        let h = x1 - x0;
+        let t = (x - x0) / h;
+        let (t2, t3) = (t * t, t * t * t);
+        (2.0 * t3 - 3.0 * t2 + 1.0) * y0 + (t3 - 2.0 * t2 + t) * h * self.slopes[i] +
+        (-2.0 * t3 + 3.0 * t2) * y1 + (t3 - t2) * h * self.slopes[i + 1]
+    }
+}
+
+impl Default for ToneCurve {
+    /// Identical to ```ToneCurve::identity()```.
+    fn default() -> ToneCurve {
+        ToneCurve::identity()
+    }
+}
+
+/// A curves adjustment, with a curve for each channel followed by a master
+/// curve applied to all three.
+///
+/// ```rust
+/// use simplecolor::{Adjustment, Curves, ToneCurve};
+///
+/// let mut curves = Curves::new();
+/// curves.set_master(ToneCurve::new(&[(0.0, 0.0), (0.25, 0.2), (0.75, 0.8), (1.0, 1.0)]));
+/// let baked = curves.bake(256);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Curves {
+    /// Curve applied to every channel
+    master: ToneCurve,
+
+    /// Red, green and blue curves
+    channels: [ToneCurve; 3]
+}
+
+impl Curves {
+    /// Create a curves adjustment which leaves colors unchanged.
+    pub fn new() -> Curves {
+        Curves::default()
+    }
+
+    /// Returns the master curve.
+    #[inline]
+    pub fn master(&self) -> &ToneCurve { &self.master }
+
+    /// Returns the curve of a channel, where 0 is red, 1 is green and 2 is
+    /// blue.
+    #[inline]
+    pub fn channel(&self, channel: usize) -> &ToneCurve { &self.channels[channel] }
+
+    /// Set the curve applied to every channel.
+    pub fn set_master(&mut self, curve: ToneCurve) { self.master = curve; }
+
+    /// Set the red curve.
+    pub fn set_red(&mut self, curve: ToneCurve) { self.channels[0] = curve; }
+
+    /// Set the green curve.
+    pub fn set_green(&mut self, curve: ToneCurve) { self.channels[1] = curve; }
+
+    /// Set the blue curve.
+    pub fn set_blue(&mut self, curve: ToneCurve) { self.channels[2] = curve; }
+}
+
+impl Adjustment for Curves {
+    fn adjust_channel(&self, channel: usize, x: f64) -> f64 {
+        self.master.eval(self.channels[channel].eval(x))
+    }
+}
+
+/// An exposure adjustment: colors are scaled by a number of stops, an
+/// offset is added, and a gamma correction is applied. Like the equivalent
+/// photo editor tools it is meant for linear light values, and results may
+/// exceed one for floating point channels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exposure {
+    /// Exposure in stops
+    exposure: f64,
+
+    /// Offset added after scaling
+    offset: f64,
+
+    /// Gamma correction
+    gamma: f64
+}
+
+impl Exposure {
+    /// Create an exposure adjustment which leaves colors unchanged.
+    pub fn new() -> Exposure {
+        Exposure {
+            exposure: 0.0,
+            offset: 0.0,
+            gamma: 1.0
+        }
+    }
+
+    /// Returns the exposure, in stops.
+    #[inline]
+    pub fn exposure(&self) -> f64 { self.exposure }
+
+    /// Returns the offset.
+    #[inline]
+    pub fn offset(&self) -> f64 { self.offset }
+
+    /// Returns the gamma correction.
+    #[inline]
+    pub fn gamma(&self) -> f64 { self.gamma }
+
+    /// Set the exposure in stops. Colors are multiplied by two to the power
+    /// of the exposure.
+    #[inline]
+    pub fn set_exposure(&mut self, exposure: f64) { self.exposure = exposure; }
+
+    /// Set the offset added to every channel after exposure, which darkens
+    /// or lightens the shadows.
+    #[inline]
+    pub fn set_offset(&mut self, offset: f64) { self.offset = offset; }
+
+    /// Set the gamma correction. Values above one brighten the midtones.
+    /// Panics unless the gamma is positive.
+    pub fn set_gamma(&mut self, gamma: f64) {
+        assert!(gamma > 0.0, "the gamma must be positive");
+        self.gamma = gamma;
+    }
+}
+
+impl Default for Exposure {
+    /// Identical to ```Exposure::new()```.
+    fn default() -> Exposure {
+        Exposure::new()
+    }
+}
+
+impl Adjustment for Exposure {
+    fn adjust_channel(&self, _: usize, x: f64) -> f64 {
+        (x * self.exposure.exp2() + self.offset).max(0.0).powf(1.0 / self.gamma)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn defaults_leave_colors_unchanged() {
+        let col = Rgb::with_components(12u8, 130, 250);
+        assert_eq!(Levels::new().apply(&col).components(), (12, 130, 250));
+        assert_eq!(Curves::new().apply(&col).components(), (12, 130, 250));
+        assert_eq!(Exposure::new().apply(&col).components(), (12, 130, 250));
+    }
+
+    #[test]
+    fn levels_stretch_and_compress() {
+        let mut levels = Levels::new();
+        levels.set_input(0.2, 0.6);
+        assert!((levels.adjust_channel(0, 0.4) - 0.5).abs() < 1e-12);
+        assert_eq!(levels.adjust_channel(0, 0.1), 0.0);
+        assert_eq!(levels.adjust_channel(0, 0.9), 1.0);
+
+        levels.set_gamma(2.0);
+        assert!((levels.adjust_channel(0, 0.4) - 0.5f64.sqrt()).abs() < 1e-12);
+
+        levels.set_output(0.1, 0.9);
+        assert!((levels.adjust_channel(0, 0.6) - 0.9).abs() < 1e-12);
+        assert!((levels.adjust_channel(0, 0.2) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn curves_do_not_panic_on_nan() {
+        let curve = ToneCurve::new(&[(0.1, 0.2), (0.9, 0.7)]);
+        assert_eq!(curve.eval(::std::f64::NAN), 0.2);
+        let out = Curves::new().apply(&Rgb::with_components(::std::f32::NAN, 0.5, 0.5));
+        assert_eq!(out.g(), 0.5);
+    }
+
+    #[test]
+    fn curve_passes_through_control_points() {
+        let points = [(0.0, 0.1), (0.3, 0.2), (0.6, 0.8), (1.0, 0.9)];
+        let curve = ToneCurve::new(&[points[2], points[0], points[3], points[1]]);
+        assert_eq!(curve.points(), &points);
+        for &(x, y) in points.iter() {
+            assert!((curve.eval(x) - y).abs() < 1e-12);
+        }
+        assert_eq!(curve.eval(-1.0), 0.1);
+        assert_eq!(curve.eval(2.0), 0.9);
+    }
+
+    #[test]
+    fn curve_is_monotonic_between_points() {
+        let curve = ToneCurve::new(&[(0.0, 0.0), (0.1, 0.6), (0.2, 0.62), (0.9, 0.65), (1.0, 1.0)]);
+        let mut previous = 0.0;
+        for i in 0..1001 {
+            let y = curve.eval(i as f64 / 1000.0);
+            assert!(y >= previous - 1e-12);
+            previous = y;
+        }
+
+        let flat = ToneCurve::new(&[(0.0, 0.0), (0.4, 0.5), (0.6, 0.5), (1.0, 1.0)]);
+        assert!((flat.eval(0.5) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn channel_curves_apply_before_master() {
+        let mut curves = Curves::new();
+        curves.set_red(ToneCurve::new(&[(0.0, 0.0), (1.0, 0.5)]));
+        curves.set_master(ToneCurve::new(&[(0.0, 1.0), (1.0, 0.0)]));
+        let (r, g, b) = curves.apply(&Rgb::with_components(1.0f64, 1.0, 0.25)).components();
+        assert!((r - 0.5).abs() < 1e-12);
+        assert!((g - 0.0).abs() < 1e-12);
+        assert!((b - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn exposure_offset_and_gamma() {
+        let mut exposure = Exposure::new();
+        exposure.set_exposure(1.0);
+        assert_eq!(exposure.apply(&Rgb::with_components(0.25f64, 0.5, 1.0)).components(), (0.5, 1.0, 2.0));
+        exposure.set_offset(-0.5);
+        assert_eq!(exposure.adjust_channel(0, 0.1), 0.0);
+        exposure.set_gamma(2.0);
+        assert!((exposure.adjust_channel(0, 0.5) - 0.5f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn baked_tables_match_adjustments() {
+        let mut levels = Levels::new();
+        levels.set_input(0.1, 0.8);
+        levels.set_gamma(1.4);
+        let lut = levels.bake(256);
+        assert!(lut.is_1d());
+        for v in 0..256 {
+            let col = Rgb::with_components(v as u8, 255 - v as u8, 0);
+            assert_eq!(lut.apply(&col).components(), levels.apply(&col).components());
+        }
+
+        let curves = Curves::new();
+        let lut = curves.bake(17);
+        let (r, g, b) = lut.apply(&Rgb::with_components(0.3f64, 0.6, 0.9)).components();
+        assert!((r - 0.3).abs() < 1e-12 && (g - 0.6).abs() < 1e-12 && (b - 0.9).abs() < 1e-12);
+    }
+}
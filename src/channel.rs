@@ -17,8 +17,9 @@
 // AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
-use num::{Num, One};
-use super::clamp_to_zero_one;
+use num::{Float, Num, One};
+use num::traits::cast;
+use super::{clamp_to_zero_one, float_to_integral, integral_to_float};
 
 /// A trait which represents the numerical value
 /// of a single channel of a color.
@@ -29,6 +30,16 @@ pub trait Channel: Copy + Num + PartialOrd {
     /// Normalise the channel. For unsigned integrals, this does nothing.
     /// For floating point channels, this clamps it between 1 and 0.
     fn normalised(self) -> Self;
+
+    /// Convert the channel to a floating point value. Unsigned integral
+    /// channels are scaled from [0, max_value()] to [0.0, 1.0], and floating
+    /// point channels are converted unchanged.
+    fn to_float<F: Float>(self) -> F;
+
+    /// Create a channel from a floating point value. Unsigned integral
+    /// channels are scaled from [0.0, 1.0] to [0, max_value()] and rounded,
+    /// and floating point channels are converted unchanged.
+    fn from_float<F: Float>(x: F) -> Self;
 }
 
 /*
@@ -47,29 +58,41 @@ impl<T: Float + !PrimInt + !Unsigned> Channel for T {
 impl Channel for u8 {
     fn inverted(self) -> u8 { u8::max_value() - self }
     fn normalised(self) -> u8 { self }
+    fn to_float<F: Float>(self) -> F { integral_to_float(self) }
+    fn from_float<F: Float>(x: F) -> u8 { float_to_integral(x) }
 }
 
 impl Channel for u16 {
     fn inverted(self) -> u16 { u16::max_value() - self }
     fn normalised(self) -> u16 { self }
+    fn to_float<F: Float>(self) -> F { integral_to_float(self) }
+    fn from_float<F: Float>(x: F) -> u16 { float_to_integral(x) }
 }
 
 impl Channel for u32 {
     fn inverted(self) -> u32 { u32::max_value() - self }
     fn normalised(self) -> u32 { self }
+    fn to_float<F: Float>(self) -> F { integral_to_float(self) }
+    fn from_float<F: Float>(x: F) -> u32 { float_to_integral(x) }
 }
 
 impl Channel for u64 {
     fn inverted(self) -> u64 { u64::max_value() - self }
     fn normalised(self) -> u64 { self }
+    fn to_float<F: Float>(self) -> F { integral_to_float(self) }
+    fn from_float<F: Float>(x: F) -> u64 { float_to_integral(x) }
 }
 
 impl Channel for f32 {
     fn inverted(self) -> f32 { f32::one() - self.normalised() }
     fn normalised(self) -> f32 { clamp_to_zero_one(self) }
+    fn to_float<F: Float>(self) -> F { cast(self).unwrap() }
+    fn from_float<F: Float>(x: F) -> f32 { cast(x).unwrap() }
 }
 
 impl Channel for f64 {
     fn inverted(self) -> f64 { f64::one() - self.normalised() }
     fn normalised(self) -> f64 { clamp_to_zero_one(self) }
+    fn to_float<F: Float>(self) -> F { cast(self).unwrap() }
+    fn from_float<F: Float>(x: F) -> f64 { cast(x).unwrap() }
 }
\ No newline at end of file
@@ -0,0 +1,371 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+use num::traits::cast;
+use std::default::Default;
+
+use super::{clamp, lerp_channel, linear_to_srgb, Channel, Color, Rgb};
+
+/// A Cmyk color with 4 channels: cyan, magenta, yellow and black (key) ink
+/// coverage. A color with no ink is white.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd)]
+pub struct Cmyk<T: Channel> {
+    /// Cyan component
+    c: T,
+
+    /// Magenta component
+    m: T,
+
+    /// Yellow component
+    y: T,
+
+    /// Black component
+    k: T
+}
+
+impl<T: Channel> Cmyk<T> {
+    /// Creates a new color, with every component set to zero (white paper).
+    pub fn new() -> Cmyk<T> {
+        Cmyk::with_components(T::zero(), T::zero(), T::zero(), T::zero())
+    }
+
+    /// Construct a Cmyk color piecewise from individual components.
+    pub const fn with_components(c: T, m: T, y: T, k: T) -> Cmyk<T> {
+        Cmyk {
+            c: c,
+            m: m,
+            y: y,
+            k: k
+        }
+    }
+
+    /// Returns the cyan channel value.
+    #[inline]
+    pub const fn c(&self) -> T { self.c }
+
+    /// Returns the magenta channel value.
+    #[inline]
+    pub const fn m(&self) -> T { self.m }
+
+    /// Returns the yellow channel value.
+    #[inline]
+    pub const fn y(&self) -> T { self.y }
+
+    /// Returns the black channel value.
+    #[inline]
+    pub const fn k(&self) -> T { self.k }
+
+    /// Return each component in a 4-element tuple. Useful for destructuring.
+    pub const fn components(&self) -> (T, T, T, T) {
+        (self.c, self.m, self.y, self.k)
+    }
+
+    /// Convert this color to a color with a different channel type.
+    pub fn convert<U: Channel>(&self) -> Cmyk<U> {
+        Cmyk::from_floats(self.floats())
+    }
+
+    /// Convert an Rgb color to Cmyk, using as much black as possible. This
+    /// is the same as `CmykConverter::new().to_cmyk(rgb)`.
+    pub fn from_rgb(rgb: &Rgb<T>) -> Cmyk<T> {
+        CmykConverter::new().to_cmyk(rgb)
+    }
+
+    /// Convert this color to Rgb, assuming ideal inks that each absorb one
+    /// primary. This is the inverse of every `CmykConverter`, ignoring the
+    /// total ink limit.
+    pub fn to_rgb(&self) -> Rgb<T> {
+        let c = self.floats();
+        Rgb::with_components(
+            T::from_float((1.0 - c[0]) * (1.0 - c[3])),
+            T::from_float((1.0 - c[1]) * (1.0 - c[3])),
+            T::from_float((1.0 - c[2]) * (1.0 - c[3])))
+    }
+
+    /// Returns the total ink coverage, from 0 to 4 (400%).
+    pub fn total_ink(&self) -> f64 {
+        self.floats().iter().fold(0.0, |sum, x| sum + x)
+    }
+
+    /// Returns the components as floats between 0 and 1.
+    fn floats(&self) -> [f64; 4] {
+        [self.c.to_float(), self.m.to_float(), self.y.to_float(), self.k.to_float()]
+    }
+
+    /// Construct a color from floats between 0 and 1.
+    fn from_floats(c: [f64; 4]) -> Cmyk<T> {
+        Cmyk::with_components(T::from_float(c[0]), T::from_float(c[1]),
+                              T::from_float(c[2]), T::from_float(c[3]))
+    }
+}
+
+impl<T: Channel> Default for Cmyk<T> {
+    /// Identical to ```Cmyk::new()```.
+    fn default() -> Cmyk<T> { Cmyk::new() }
+}
+
+impl<T: Channel> Color<T> for Cmyk<T> {
+    /// Clamp each component between two scalar values.
+    fn clamp_scalar(&self, min: T, max: T) -> Cmyk<T> {
+        Cmyk::with_components(
+            clamp(self.c, min, max),
+            clamp(self.m, min, max),
+            clamp(self.y, min, max),
+            clamp(self.k, min, max))
+    }
+
+    /// Clamp each component piecewise between zero, and the
+    /// corresponding channel for the other color.
+    fn clamp_color(&self, min: &Cmyk<T>, max: &Cmyk<T>) -> Cmyk<T> {
+        Cmyk::with_components(
+            clamp(self.c, min.c, max.c),
+            clamp(self.m, min.m, max.m),
+            clamp(self.y, min.y, max.y),
+            clamp(self.k, min.k, max.k))
+    }
+
+    /// Normalise each component.
+    fn normalise(&self) -> Cmyk<T> {
+        Cmyk::with_components(
+            self.c.normalised(),
+            self.m.normalised(),
+            self.y.normalised(),
+            self.k.normalised())
+    }
+
+    /// Invert the color, by inverting its Rgb equivalent.
+    fn invert(&self) -> Cmyk<T> {
+        Cmyk::from_rgb(&self.to_rgb().invert())
+    }
+
+    /// Get the relative brightness of the color's Rgb equivalent.
+    fn luminance(&self) -> T {
+        self.to_rgb().luminance()
+    }
+
+    /// Mix two colors together equally.
+    fn mix(&self, other: &Cmyk<T>) -> Cmyk<T> {
+        self.lerp(other, 0.5f64)
+    }
+
+    /// Convert a color to greyscale, printed with black ink only.
+    fn to_greyscale(&self) -> Cmyk<T> {
+        let grey = linear_to_srgb(self.to_rgb().relative_luminance());
+        Cmyk::with_components(T::zero(), T::zero(), T::zero(), T::from_float(1.0 - grey))
+    }
+
+    /// Linearly interpolate between the ink coverages of this color and
+    /// another color.
+    fn lerp<F: Float>(&self, other: &Cmyk<T>, t: F) -> Cmyk<T> {
+        let t = cast(t).unwrap();
+        Cmyk::with_components(
+            lerp_channel(self.c, other.c, t),
+            lerp_channel(self.m, other.m, t),
+            lerp_channel(self.y, other.y, t),
+            lerp_channel(self.k, other.k, t))
+    }
+
+    /// Mix a list of colors together, weighting each color by its
+    /// corresponding weight.
+    fn mix_weighted<F: Float>(colors: &[(Cmyk<T>, F)]) -> Cmyk<T> {
+        let (mut sum, mut total) = ([0.0f64; 4], 0.0f64);
+        for &(ref col, weight) in colors {
+            let weight: f64 = cast(weight).unwrap();
+            let c = col.floats();
+            for i in 0..4 {
+                sum[i] += weight * c[i];
+            }
+            total += weight;
+        }
+        if total == 0.0 {
+            return Cmyk::default();
+        }
+        Cmyk::from_floats([sum[0] / total, sum[1] / total, sum[2] / total, sum[3] / total])
+    }
+}
+
+/// Converts Rgb colors to Cmyk for print without a color profile, by
+/// replacing some of the grey component of each color (the ink shared by
+/// cyan, magenta and yellow) with black ink.
+///
+/// Full gray component replacement (GCR) uses as much black as possible,
+/// saving colored ink and keeping neutrals stable. Under color removal
+/// (UCR) only generates black in the shadows, which is set with a high
+/// black start. A total ink limit prevents the paper being oversaturated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CmykConverter {
+    /// Fraction of the grey component replaced by black
+    black_amount: f64,
+
+    /// Grey component below which no black is generated
+    black_start: f64,
+
+    /// Maximum total coverage of the four inks
+    ink_limit: f64
+}
+
+impl CmykConverter {
+    /// Create a converter with full gray component replacement and no ink
+    /// limit, which gives the naive conversion.
+    pub fn new() -> CmykConverter {
+        CmykConverter {
+            black_amount: 1.0,
+            black_start: 0.0,
+            ink_limit: 4.0
+        }
+    }
+
+    /// Create a converter for under color removal, which only replaces the
+    /// grey component with black where it exceeds `black_start`.
+    pub fn ucr(black_start: f64, ink_limit: f64) -> CmykConverter {
+        let mut converter = CmykConverter::new();
+        converter.set_black_generation(1.0, black_start);
+        converter.set_ink_limit(ink_limit);
+        converter
+    }
+
+    /// Create a converter for gray component replacement, which replaces
+    /// the given fraction of the grey component with black across all
+    /// tones.
+    pub fn gcr(amount: f64, ink_limit: f64) -> CmykConverter {
+        let mut converter = CmykConverter::new();
+        converter.set_black_generation(amount, 0.0);
+        converter.set_ink_limit(ink_limit);
+        converter
+    }
+
+    /// Returns the fraction of the grey component replaced by black.
+    #[inline]
+    pub fn black_amount(&self) -> f64 { self.black_amount }
+
+    /// Returns the grey component below which no black is generated.
+    #[inline]
+    pub fn black_start(&self) -> f64 { self.black_start }
+
+    /// Set how black is generated. Above the start, the black ink rises
+    /// linearly with the grey component, from zero at the start to `amount`
+    /// at a grey component of one. Both values are
+    /// clamped between zero and one, and the start must be less than one.
+    pub fn set_black_generation(&mut self, amount: f64, start: f64) {
+        self.black_amount = clamp(amount, 0.0, 1.0);
+        self.black_start = clamp(start, 0.0, 0.999);
+    }
+
+    /// Returns the total ink limit, from 0 to 4 (400%).
+    #[inline]
+    pub fn ink_limit(&self) -> f64 { self.ink_limit }
+
+    /// Set the total ink limit, from 0 to 4 (400%). Typical limits are
+    /// around 3 (300%) for coated paper.
+    #[inline]
+    pub fn set_ink_limit(&mut self, limit: f64) { self.ink_limit = clamp(limit, 0.0, 4.0); }
+
+    /// Convert an Rgb color to Cmyk.
+    pub fn to_cmyk<T: Channel>(&self, rgb: &Rgb<T>) -> Cmyk<T> {
+        let c = 1.0 - rgb.r().to_float::<f64>();
+        let m = 1.0 - rgb.g().to_float::<f64>();
+        let y = 1.0 - rgb.b().to_float::<f64>();
+        let grey = c.min(m).min(y);
+        let k = if grey <= self.black_start {
+            0.0
+        } else {
+            self.black_amount * (grey - self.black_start) / (1.0 - self.black_start)
+        };
+
+        // Removing black from each ink keeps the color the same, since the
+        // inks combine multiplicatively.
+        let remove = |x: f64| if k >= 1.0 { 0.0 } else { clamp((x - k) / (1.0 - k), 0.0, 1.0) };
+        let (mut c, mut m, mut y) = (remove(c), remove(m), remove(y));
+        let k = k.min(self.ink_limit);
+        let colored = c + m + y;
+        if colored + k > self.ink_limit && colored > 0.0 {
+            let scale = (self.ink_limit - k) / colored;
+            c *= scale;
+            m *= scale;
+            y *= scale;
+        }
+        Cmyk::from_floats([c, m, y, k])
+    }
+}
+
+impl Default for CmykConverter {
+    /// Identical to ```CmykConverter::new()```.
+    fn default() -> CmykConverter { CmykConverter::new() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Color, Rgb};
+
+    #[test]
+    fn naive_conversion() {
+        let red = Cmyk::from_rgb(&Rgb::with_components(255u8, 0, 0));
+        assert_eq!(red.components(), (0, 255, 255, 0));
+        let grey = Cmyk::from_rgb(&Rgb::with_components(0.25f64, 0.25, 0.25));
+        assert_eq!(grey.components(), (0.0, 0.0, 0.0, 0.75));
+        let black = Cmyk::from_rgb(&Rgb::with_components(0u8, 0, 0));
+        assert_eq!(black.components(), (0, 0, 0, 255));
+        assert_eq!(black.to_rgb(), Rgb::with_components(0, 0, 0));
+    }
+
+    #[test]
+    fn black_generation_preserves_the_color() {
+        let col = Rgb::with_components(0.3f64, 0.2, 0.6);
+        let converters = [CmykConverter::new(), CmykConverter::ucr(0.5, 4.0), CmykConverter::gcr(0.4, 4.0)];
+        for converter in converters.iter() {
+            let (r, g, b) = converter.to_cmyk(&col).to_rgb().components();
+            assert!((r - 0.3).abs() < 1e-12 && (g - 0.2).abs() < 1e-12 && (b - 0.6).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn ucr_only_adds_black_in_shadows() {
+        let ucr = CmykConverter::ucr(0.5, 4.0);
+        assert_eq!(ucr.to_cmyk(&Rgb::with_components(0.7f64, 0.8, 0.9)).k(), 0.0);
+        assert!(ucr.to_cmyk(&Rgb::with_components(0.1f64, 0.2, 0.3)).k() > 0.0);
+
+        let gcr = CmykConverter::gcr(0.5, 4.0);
+        let (c, m, y, k) = gcr.to_cmyk(&Rgb::with_components(0.5f64, 0.5, 0.5)).components();
+        assert!((k - 0.25).abs() < 1e-12);
+        assert!((c - 1.0 / 3.0).abs() < 1e-12 && c == m && m == y);
+    }
+
+    #[test]
+    fn ink_limit_caps_total_coverage() {
+        let rich_black = Rgb::with_components(0.0f64, 0.0, 0.02);
+        let converter = CmykConverter::gcr(0.2, 3.0);
+        assert!(converter.to_cmyk(&rich_black).total_ink() <= 3.0 + 1e-12);
+        let unlimited = CmykConverter::gcr(0.2, 4.0);
+        assert!(unlimited.to_cmyk(&rich_black).total_ink() > 3.0);
+    }
+
+    #[test]
+    fn color_trait() {
+        let a = Cmyk::with_components(0u8, 100, 200, 50);
+        let b = Cmyk::with_components(200u8, 100, 0, 150);
+        assert_eq!(a.lerp(&b, 0.0f64), a);
+        assert_eq!(a.mix(&b).components(), (100, 100, 100, 100));
+        let grey = Cmyk::with_components(0.0f64, 0.0, 0.0, 0.4).to_greyscale();
+        assert!((grey.k() - 0.4).abs() < 1e-9);
+        let white = Cmyk::<f64>::new().invert();
+        assert_eq!(white.k(), 1.0);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use colormap_data;
+use super::{clamp, Channel, Gradient, Rgb};
+
+/// A mapping from scalar values to colors.
+pub trait Colormap {
+    /// Returns the color at a position between 0 and 1. This is the only
+    /// method which needs to be implemented.
+    fn lookup(&self, t: f64) -> Rgb<f64>;
+
+    /// Map a value between 0 and 1 to a color. Values outside of this
+    /// range are clamped.
+    fn map<T: Channel>(&self, value: f64) -> Rgb<T> {
+        let t = clamp(value, 0.0, 1.0);
+        self.lookup(t).convert()
+    }
+
+    /// Map a value between `min` and `max` to a color. Values outside of
+    /// this range are clamped. If `min` and `max` are equal, the lowest
+    /// color of the map is returned.
+    fn map_range<T: Channel>(&self, value: f64, min: f64, max: f64) -> Rgb<T> {
+        if min == max {
+            return self.map(0.0);
+        }
+        self.map((value - min) / (max - min))
+    }
+
+    /// Returns `n` colors, sampled at evenly spaced positions from the
+    /// first to the last color of the map.
+    fn colors<T: Channel>(&self, n: usize) -> Vec<Rgb<T>> {
+        let last = if n > 1 { (n - 1) as f64 } else { 1.0 };
+        (0..n).map(|i| self.map(i as f64 / last)).collect()
+    }
+
+    /// Returns this colormap running in the opposite direction.
+    fn reversed(self) -> Reversed<Self> where Self: Sized {
+        Reversed(self)
+    }
+
+    /// Returns this colormap reduced to `steps` evenly spaced colors, which
+    /// are sampled from the first to the last color of the map.
+    fn discrete(self, steps: usize) -> Discrete<Self> where Self: Sized {
+        Discrete {
+            colormap: self,
+            steps: steps
+        }
+    }
+}
+
+/// A colormap defined by a table of evenly spaced colors, which are linearly
+/// interpolated. Constructors are provided for the standard perceptually
+/// uniform colormaps, which are stored as 256 8-bit sRGB colors.
+#[derive(Clone, Copy)]
+pub struct ListedColormap {
+    /// Evenly spaced 8-bit colors
+    table: &'static [[u8; 3]]
+}
+
+impl ListedColormap {
+    /// Create a colormap from a table of evenly spaced 8-bit colors. Panics
+    /// if the table is empty.
+    pub fn new(table: &'static [[u8; 3]]) -> ListedColormap {
+        assert!(!table.is_empty());
+        ListedColormap {
+            table: table
+        }
+    }
+
+    /// The viridis colormap, running from dark blue to yellow.
+    pub fn viridis() -> ListedColormap { ListedColormap::new(&colormap_data::VIRIDIS) }
+
+    /// The magma colormap, running from black through purple to pale yellow.
+    pub fn magma() -> ListedColormap { ListedColormap::new(&colormap_data::MAGMA) }
+
+    /// The inferno colormap, running from black through red to pale yellow.
+    pub fn inferno() -> ListedColormap { ListedColormap::new(&colormap_data::INFERNO) }
+
+    /// The plasma colormap, running from dark blue through pink to yellow.
+    pub fn plasma() -> ListedColormap { ListedColormap::new(&colormap_data::PLASMA) }
+
+    /// The cividis colormap, running from blue to yellow, which is designed
+    /// to be readable with color vision deficiencies.
+    pub fn cividis() -> ListedColormap { ListedColormap::new(&colormap_data::CIVIDIS) }
+
+    /// The turbo colormap, an improved rainbow colormap.
+    pub fn turbo() -> ListedColormap { ListedColormap::new(&colormap_data::TURBO) }
+
+    /// Returns the table of colors.
+    #[inline]
+    pub fn table(&self) -> &'static [[u8; 3]] { self.table }
+}
+
+impl Colormap for ListedColormap {
+    fn lookup(&self, t: f64) -> Rgb<f64> {
+        let last = self.table.len() - 1;
+        let position = clamp(t, 0.0, 1.0) * last as f64;
+        let index = (position.floor() as usize).min(last);
+        let next = (index + 1).min(last);
+        let frac = position - index as f64;
+        let (a, b) = (self.table[index], self.table[next]);
+        let channel = |i: usize| (a[i] as f64 * (1.0 - frac) + b[i] as f64 * frac) / 255.0;
+        Rgb::with_components(channel(0), channel(1), channel(2))
+    }
+}
+
+impl<T: Channel> Colormap for Gradient<Rgb<T>> {
+    fn lookup(&self, t: f64) -> Rgb<f64> {
+        let stops = self.stops();
+        let (start, end) = (stops[0].0, stops[stops.len() - 1].0);
+        self.sample(start + (end - start) * t).convert()
+    }
+}
+
+/// A colormap running in the opposite direction to another colormap.
+/// Created by `Colormap::reversed`.
+#[derive(Clone, Copy)]
+pub struct Reversed<C>(C);
+
+impl<C: Colormap> Colormap for Reversed<C> {
+    fn lookup(&self, t: f64) -> Rgb<f64> {
+        self.0.lookup(1.0 - t)
+    }
+}
+
+/// A colormap reduced to a fixed number of colors. Created by
+/// `Colormap::discrete`.
+#[derive(Clone, Copy)]
+pub struct Discrete<C> {
+    /// The underlying colormap
+    colormap: C,
+
+    /// The number of colors
+    steps: usize
+}
+
+impl<C: Colormap> Colormap for Discrete<C> {
+    fn lookup(&self, t: f64) -> Rgb<f64> {
+        if self.steps < 2 {
+            return self.colormap.lookup(0.5);
+        }
+        let last = self.steps - 1;
+        let step = ((clamp(t, 0.0, 1.0) * self.steps as f64) as usize).min(last);
+        self.colormap.lookup(step as f64 / last as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn endpoints_match_the_tables() {
+        let viridis = ListedColormap::viridis();
+        assert_eq!(viridis.map::<u8>(0.0), Rgb::with_components(0x44, 0x01, 0x54));
+        assert_eq!(viridis.map::<u8>(1.0), Rgb::with_components(0xfd, 0xe7, 0x25));
+        assert_eq!(viridis.map::<u8>(-3.0), viridis.map::<u8>(0.0));
+        assert_eq!(ListedColormap::magma().map::<u8>(1.0), Rgb::with_components(0xfc, 0xfd, 0xbf));
+    }
+
+    #[test]
+    fn reversed_runs_backwards() {
+        let plasma = ListedColormap::plasma();
+        assert_eq!(plasma.reversed().map::<u16>(0.2), plasma.map::<u16>(0.8));
+    }
+
+    #[test]
+    fn map_range_rescales_values() {
+        let inferno = ListedColormap::inferno();
+        assert_eq!(inferno.map_range::<f32>(15.0, 10.0, 20.0), inferno.map::<f32>(0.5));
+    }
+
+    #[test]
+    fn discrete_has_fixed_number_of_colors() {
+        let turbo = ListedColormap::turbo();
+        let discrete = turbo.discrete(4);
+        assert_eq!(discrete.map::<u8>(0.0), turbo.map::<u8>(0.0));
+        assert_eq!(discrete.map::<u8>(0.2), turbo.map::<u8>(0.0));
+        assert_eq!(discrete.map::<u8>(0.3), turbo.map::<u8>(1.0 / 3.0));
+        assert_eq!(discrete.map::<u8>(1.0), turbo.map::<u8>(1.0));
+    }
+}
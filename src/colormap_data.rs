@@ -0,0 +1,302 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Lookup tables for the built in colormaps, as 256 evenly spaced 8-bit
+//! sRGB colors.
+//!
+//! Viridis, magma, inferno and plasma are the tables from matplotlib,
+//! by Stéfan van der Walt and Nathaniel Smith. Cividis (Nuñez, Anderton
+//! and Renslow) and turbo (Mikhailov) are sampled from Mike Bostock's
+//! polynomial fits of the original tables, as used by d3-scale-chromatic.
+
+pub const VIRIDIS: [[u8; 3]; 256] = [
+    [0x44, 0x01, 0x54], [0x44, 0x02, 0x56], [0x45, 0x04, 0x57], [0x45, 0x05, 0x59], [0x46, 0x07, 0x5a], [0x46, 0x08, 0x5c],
+    [0x46, 0x0a, 0x5d], [0x46, 0x0b, 0x5e], [0x47, 0x0d, 0x60], [0x47, 0x0e, 0x61], [0x47, 0x10, 0x63], [0x47, 0x11, 0x64],
+    [0x47, 0x13, 0x65], [0x48, 0x14, 0x67], [0x48, 0x16, 0x68], [0x48, 0x17, 0x69], [0x48, 0x18, 0x6a], [0x48, 0x1a, 0x6c],
+    [0x48, 0x1b, 0x6d], [0x48, 0x1c, 0x6e], [0x48, 0x1d, 0x6f], [0x48, 0x1f, 0x70], [0x48, 0x20, 0x71], [0x48, 0x21, 0x73],
+    [0x48, 0x23, 0x74], [0x48, 0x24, 0x75], [0x48, 0x25, 0x76], [0x48, 0x26, 0x77], [0x48, 0x28, 0x78], [0x48, 0x29, 0x79],
+    [0x47, 0x2a, 0x7a], [0x47, 0x2c, 0x7a], [0x47, 0x2d, 0x7b], [0x47, 0x2e, 0x7c], [0x47, 0x2f, 0x7d], [0x46, 0x30, 0x7e],
+    [0x46, 0x32, 0x7e], [0x46, 0x33, 0x7f], [0x46, 0x34, 0x80], [0x45, 0x35, 0x81], [0x45, 0x37, 0x81], [0x45, 0x38, 0x82],
+    [0x44, 0x39, 0x83], [0x44, 0x3a, 0x83], [0x44, 0x3b, 0x84], [0x43, 0x3d, 0x84], [0x43, 0x3e, 0x85], [0x42, 0x3f, 0x85],
+    [0x42, 0x40, 0x86], [0x42, 0x41, 0x86], [0x41, 0x42, 0x87], [0x41, 0x44, 0x87], [0x40, 0x45, 0x88], [0x40, 0x46, 0x88],
+    [0x3f, 0x47, 0x88], [0x3f, 0x48, 0x89], [0x3e, 0x49, 0x89], [0x3e, 0x4a, 0x89], [0x3e, 0x4c, 0x8a], [0x3d, 0x4d, 0x8a],
+    [0x3d, 0x4e, 0x8a], [0x3c, 0x4f, 0x8a], [0x3c, 0x50, 0x8b], [0x3b, 0x51, 0x8b], [0x3b, 0x52, 0x8b], [0x3a, 0x53, 0x8b],
+    [0x3a, 0x54, 0x8c], [0x39, 0x55, 0x8c], [0x39, 0x56, 0x8c], [0x38, 0x58, 0x8c], [0x38, 0x59, 0x8c], [0x37, 0x5a, 0x8c],
+    [0x37, 0x5b, 0x8d], [0x36, 0x5c, 0x8d], [0x36, 0x5d, 0x8d], [0x35, 0x5e, 0x8d], [0x35, 0x5f, 0x8d], [0x34, 0x60, 0x8d],
+    [0x34, 0x61, 0x8d], [0x33, 0x62, 0x8d], [0x33, 0x63, 0x8d], [0x32, 0x64, 0x8e], [0x32, 0x65, 0x8e], [0x31, 0x66, 0x8e],
+    [0x31, 0x67, 0x8e], [0x31, 0x68, 0x8e], [0x30, 0x69, 0x8e], [0x30, 0x6a, 0x8e], [0x2f, 0x6b, 0x8e], [0x2f, 0x6c, 0x8e],
+    [0x2e, 0x6d, 0x8e], [0x2e, 0x6e, 0x8e], [0x2e, 0x6f, 0x8e], [0x2d, 0x70, 0x8e], [0x2d, 0x71, 0x8e], [0x2c, 0x71, 0x8e],
+    [0x2c, 0x72, 0x8e], [0x2c, 0x73, 0x8e], [0x2b, 0x74, 0x8e], [0x2b, 0x75, 0x8e], [0x2a, 0x76, 0x8e], [0x2a, 0x77, 0x8e],
+    [0x2a, 0x78, 0x8e], [0x29, 0x79, 0x8e], [0x29, 0x7a, 0x8e], [0x29, 0x7b, 0x8e], [0x28, 0x7c, 0x8e], [0x28, 0x7d, 0x8e],
+    [0x27, 0x7e, 0x8e], [0x27, 0x7f, 0x8e], [0x27, 0x80, 0x8e], [0x26, 0x81, 0x8e], [0x26, 0x82, 0x8e], [0x26, 0x82, 0x8e],
+    [0x25, 0x83, 0x8e], [0x25, 0x84, 0x8e], [0x25, 0x85, 0x8e], [0x24, 0x86, 0x8e], [0x24, 0x87, 0x8e], [0x23, 0x88, 0x8e],
+    [0x23, 0x89, 0x8e], [0x23, 0x8a, 0x8d], [0x22, 0x8b, 0x8d], [0x22, 0x8c, 0x8d], [0x22, 0x8d, 0x8d], [0x21, 0x8e, 0x8d],
+    [0x21, 0x8f, 0x8d], [0x21, 0x90, 0x8d], [0x21, 0x91, 0x8c], [0x20, 0x92, 0x8c], [0x20, 0x92, 0x8c], [0x20, 0x93, 0x8c],
+    [0x1f, 0x94, 0x8c], [0x1f, 0x95, 0x8b], [0x1f, 0x96, 0x8b], [0x1f, 0x97, 0x8b], [0x1f, 0x98, 0x8b], [0x1f, 0x99, 0x8a],
+    [0x1f, 0x9a, 0x8a], [0x1e, 0x9b, 0x8a], [0x1e, 0x9c, 0x89], [0x1e, 0x9d, 0x89], [0x1f, 0x9e, 0x89], [0x1f, 0x9f, 0x88],
+    [0x1f, 0xa0, 0x88], [0x1f, 0xa1, 0x88], [0x1f, 0xa1, 0x87], [0x1f, 0xa2, 0x87], [0x20, 0xa3, 0x86], [0x20, 0xa4, 0x86],
+    [0x21, 0xa5, 0x85], [0x21, 0xa6, 0x85], [0x22, 0xa7, 0x85], [0x22, 0xa8, 0x84], [0x23, 0xa9, 0x83], [0x24, 0xaa, 0x83],
+    [0x25, 0xab, 0x82], [0x25, 0xac, 0x82], [0x26, 0xad, 0x81], [0x27, 0xad, 0x81], [0x28, 0xae, 0x80], [0x29, 0xaf, 0x7f],
+    [0x2a, 0xb0, 0x7f], [0x2c, 0xb1, 0x7e], [0x2d, 0xb2, 0x7d], [0x2e, 0xb3, 0x7c], [0x2f, 0xb4, 0x7c], [0x31, 0xb5, 0x7b],
+    [0x32, 0xb6, 0x7a], [0x34, 0xb6, 0x79], [0x35, 0xb7, 0x79], [0x37, 0xb8, 0x78], [0x38, 0xb9, 0x77], [0x3a, 0xba, 0x76],
+    [0x3b, 0xbb, 0x75], [0x3d, 0xbc, 0x74], [0x3f, 0xbc, 0x73], [0x40, 0xbd, 0x72], [0x42, 0xbe, 0x71], [0x44, 0xbf, 0x70],
+    [0x46, 0xc0, 0x6f], [0x48, 0xc1, 0x6e], [0x4a, 0xc1, 0x6d], [0x4c, 0xc2, 0x6c], [0x4e, 0xc3, 0x6b], [0x50, 0xc4, 0x6a],
+    [0x52, 0xc5, 0x69], [0x54, 0xc5, 0x68], [0x56, 0xc6, 0x67], [0x58, 0xc7, 0x65], [0x5a, 0xc8, 0x64], [0x5c, 0xc8, 0x63],
+    [0x5e, 0xc9, 0x62], [0x60, 0xca, 0x60], [0x63, 0xcb, 0x5f], [0x65, 0xcb, 0x5e], [0x67, 0xcc, 0x5c], [0x69, 0xcd, 0x5b],
+    [0x6c, 0xcd, 0x5a], [0x6e, 0xce, 0x58], [0x70, 0xcf, 0x57], [0x73, 0xd0, 0x56], [0x75, 0xd0, 0x54], [0x77, 0xd1, 0x53],
+    [0x7a, 0xd1, 0x51], [0x7c, 0xd2, 0x50], [0x7f, 0xd3, 0x4e], [0x81, 0xd3, 0x4d], [0x84, 0xd4, 0x4b], [0x86, 0xd5, 0x49],
+    [0x89, 0xd5, 0x48], [0x8b, 0xd6, 0x46], [0x8e, 0xd6, 0x45], [0x90, 0xd7, 0x43], [0x93, 0xd7, 0x41], [0x95, 0xd8, 0x40],
+    [0x98, 0xd8, 0x3e], [0x9b, 0xd9, 0x3c], [0x9d, 0xd9, 0x3b], [0xa0, 0xda, 0x39], [0xa2, 0xda, 0x37], [0xa5, 0xdb, 0x36],
+    [0xa8, 0xdb, 0x34], [0xaa, 0xdc, 0x32], [0xad, 0xdc, 0x30], [0xb0, 0xdd, 0x2f], [0xb2, 0xdd, 0x2d], [0xb5, 0xde, 0x2b],
+    [0xb8, 0xde, 0x29], [0xba, 0xde, 0x28], [0xbd, 0xdf, 0x26], [0xc0, 0xdf, 0x25], [0xc2, 0xdf, 0x23], [0xc5, 0xe0, 0x21],
+    [0xc8, 0xe0, 0x20], [0xca, 0xe1, 0x1f], [0xcd, 0xe1, 0x1d], [0xd0, 0xe1, 0x1c], [0xd2, 0xe2, 0x1b], [0xd5, 0xe2, 0x1a],
+    [0xd8, 0xe2, 0x19], [0xda, 0xe3, 0x19], [0xdd, 0xe3, 0x18], [0xdf, 0xe3, 0x18], [0xe2, 0xe4, 0x18], [0xe5, 0xe4, 0x19],
+    [0xe7, 0xe4, 0x19], [0xea, 0xe5, 0x1a], [0xec, 0xe5, 0x1b], [0xef, 0xe5, 0x1c], [0xf1, 0xe5, 0x1d], [0xf4, 0xe6, 0x1e],
+    [0xf6, 0xe6, 0x20], [0xf8, 0xe6, 0x21], [0xfb, 0xe7, 0x23], [0xfd, 0xe7, 0x25],
+];
+
+pub const MAGMA: [[u8; 3]; 256] = [
+    [0x00, 0x00, 0x04], [0x01, 0x00, 0x05], [0x01, 0x01, 0x06], [0x01, 0x01, 0x08], [0x02, 0x01, 0x09], [0x02, 0x02, 0x0b],
+    [0x02, 0x02, 0x0d], [0x03, 0x03, 0x0f], [0x03, 0x03, 0x12], [0x04, 0x04, 0x14], [0x05, 0x04, 0x16], [0x06, 0x05, 0x18],
+    [0x06, 0x05, 0x1a], [0x07, 0x06, 0x1c], [0x08, 0x07, 0x1e], [0x09, 0x07, 0x20], [0x0a, 0x08, 0x22], [0x0b, 0x09, 0x24],
+    [0x0c, 0x09, 0x26], [0x0d, 0x0a, 0x29], [0x0e, 0x0b, 0x2b], [0x10, 0x0b, 0x2d], [0x11, 0x0c, 0x2f], [0x12, 0x0d, 0x31],
+    [0x13, 0x0d, 0x34], [0x14, 0x0e, 0x36], [0x15, 0x0e, 0x38], [0x16, 0x0f, 0x3b], [0x18, 0x0f, 0x3d], [0x19, 0x10, 0x3f],
+    [0x1a, 0x10, 0x42], [0x1c, 0x10, 0x44], [0x1d, 0x11, 0x47], [0x1e, 0x11, 0x49], [0x20, 0x11, 0x4b], [0x21, 0x11, 0x4e],
+    [0x22, 0x11, 0x50], [0x24, 0x12, 0x53], [0x25, 0x12, 0x55], [0x27, 0x12, 0x58], [0x29, 0x11, 0x5a], [0x2a, 0x11, 0x5c],
+    [0x2c, 0x11, 0x5f], [0x2d, 0x11, 0x61], [0x2f, 0x11, 0x63], [0x31, 0x11, 0x65], [0x33, 0x10, 0x67], [0x34, 0x10, 0x69],
+    [0x36, 0x10, 0x6b], [0x38, 0x10, 0x6c], [0x39, 0x0f, 0x6e], [0x3b, 0x0f, 0x70], [0x3d, 0x0f, 0x71], [0x3f, 0x0f, 0x72],
+    [0x40, 0x0f, 0x74], [0x42, 0x0f, 0x75], [0x44, 0x0f, 0x76], [0x45, 0x10, 0x77], [0x47, 0x10, 0x78], [0x49, 0x10, 0x78],
+    [0x4a, 0x10, 0x79], [0x4c, 0x11, 0x7a], [0x4e, 0x11, 0x7b], [0x4f, 0x12, 0x7b], [0x51, 0x12, 0x7c], [0x52, 0x13, 0x7c],
+    [0x54, 0x13, 0x7d], [0x56, 0x14, 0x7d], [0x57, 0x15, 0x7e], [0x59, 0x15, 0x7e], [0x5a, 0x16, 0x7e], [0x5c, 0x16, 0x7f],
+    [0x5d, 0x17, 0x7f], [0x5f, 0x18, 0x7f], [0x60, 0x18, 0x80], [0x62, 0x19, 0x80], [0x64, 0x1a, 0x80], [0x65, 0x1a, 0x80],
+    [0x67, 0x1b, 0x80], [0x68, 0x1c, 0x81], [0x6a, 0x1c, 0x81], [0x6b, 0x1d, 0x81], [0x6d, 0x1d, 0x81], [0x6e, 0x1e, 0x81],
+    [0x70, 0x1f, 0x81], [0x72, 0x1f, 0x81], [0x73, 0x20, 0x81], [0x75, 0x21, 0x81], [0x76, 0x21, 0x81], [0x78, 0x22, 0x81],
+    [0x79, 0x22, 0x82], [0x7b, 0x23, 0x82], [0x7c, 0x23, 0x82], [0x7e, 0x24, 0x82], [0x80, 0x25, 0x82], [0x81, 0x25, 0x81],
+    [0x83, 0x26, 0x81], [0x84, 0x26, 0x81], [0x86, 0x27, 0x81], [0x88, 0x27, 0x81], [0x89, 0x28, 0x81], [0x8b, 0x29, 0x81],
+    [0x8c, 0x29, 0x81], [0x8e, 0x2a, 0x81], [0x90, 0x2a, 0x81], [0x91, 0x2b, 0x81], [0x93, 0x2b, 0x80], [0x94, 0x2c, 0x80],
+    [0x96, 0x2c, 0x80], [0x98, 0x2d, 0x80], [0x99, 0x2d, 0x80], [0x9b, 0x2e, 0x7f], [0x9c, 0x2e, 0x7f], [0x9e, 0x2f, 0x7f],
+    [0xa0, 0x2f, 0x7f], [0xa1, 0x30, 0x7e], [0xa3, 0x30, 0x7e], [0xa5, 0x31, 0x7e], [0xa6, 0x31, 0x7d], [0xa8, 0x32, 0x7d],
+    [0xaa, 0x33, 0x7d], [0xab, 0x33, 0x7c], [0xad, 0x34, 0x7c], [0xae, 0x34, 0x7b], [0xb0, 0x35, 0x7b], [0xb2, 0x35, 0x7b],
+    [0xb3, 0x36, 0x7a], [0xb5, 0x36, 0x7a], [0xb7, 0x37, 0x79], [0xb8, 0x37, 0x79], [0xba, 0x38, 0x78], [0xbc, 0x39, 0x78],
+    [0xbd, 0x39, 0x77], [0xbf, 0x3a, 0x77], [0xc0, 0x3a, 0x76], [0xc2, 0x3b, 0x75], [0xc4, 0x3c, 0x75], [0xc5, 0x3c, 0x74],
+    [0xc7, 0x3d, 0x73], [0xc8, 0x3e, 0x73], [0xca, 0x3e, 0x72], [0xcc, 0x3f, 0x71], [0xcd, 0x40, 0x71], [0xcf, 0x40, 0x70],
+    [0xd0, 0x41, 0x6f], [0xd2, 0x42, 0x6f], [0xd3, 0x43, 0x6e], [0xd5, 0x44, 0x6d], [0xd6, 0x45, 0x6c], [0xd8, 0x45, 0x6c],
+    [0xd9, 0x46, 0x6b], [0xdb, 0x47, 0x6a], [0xdc, 0x48, 0x69], [0xde, 0x49, 0x68], [0xdf, 0x4a, 0x68], [0xe0, 0x4c, 0x67],
+    [0xe2, 0x4d, 0x66], [0xe3, 0x4e, 0x65], [0xe4, 0x4f, 0x64], [0xe5, 0x50, 0x64], [0xe7, 0x52, 0x63], [0xe8, 0x53, 0x62],
+    [0xe9, 0x54, 0x62], [0xea, 0x56, 0x61], [0xeb, 0x57, 0x60], [0xec, 0x58, 0x60], [0xed, 0x5a, 0x5f], [0xee, 0x5b, 0x5e],
+    [0xef, 0x5d, 0x5e], [0xf0, 0x5f, 0x5e], [0xf1, 0x60, 0x5d], [0xf2, 0x62, 0x5d], [0xf2, 0x64, 0x5c], [0xf3, 0x65, 0x5c],
+    [0xf4, 0x67, 0x5c], [0xf4, 0x69, 0x5c], [0xf5, 0x6b, 0x5c], [0xf6, 0x6c, 0x5c], [0xf6, 0x6e, 0x5c], [0xf7, 0x70, 0x5c],
+    [0xf7, 0x72, 0x5c], [0xf8, 0x74, 0x5c], [0xf8, 0x76, 0x5c], [0xf9, 0x78, 0x5d], [0xf9, 0x79, 0x5d], [0xf9, 0x7b, 0x5d],
+    [0xfa, 0x7d, 0x5e], [0xfa, 0x7f, 0x5e], [0xfa, 0x81, 0x5f], [0xfb, 0x83, 0x5f], [0xfb, 0x85, 0x60], [0xfb, 0x87, 0x61],
+    [0xfc, 0x89, 0x61], [0xfc, 0x8a, 0x62], [0xfc, 0x8c, 0x63], [0xfc, 0x8e, 0x64], [0xfc, 0x90, 0x65], [0xfd, 0x92, 0x66],
+    [0xfd, 0x94, 0x67], [0xfd, 0x96, 0x68], [0xfd, 0x98, 0x69], [0xfd, 0x9a, 0x6a], [0xfd, 0x9b, 0x6b], [0xfe, 0x9d, 0x6c],
+    [0xfe, 0x9f, 0x6d], [0xfe, 0xa1, 0x6e], [0xfe, 0xa3, 0x6f], [0xfe, 0xa5, 0x71], [0xfe, 0xa7, 0x72], [0xfe, 0xa9, 0x73],
+    [0xfe, 0xaa, 0x74], [0xfe, 0xac, 0x76], [0xfe, 0xae, 0x77], [0xfe, 0xb0, 0x78], [0xfe, 0xb2, 0x7a], [0xfe, 0xb4, 0x7b],
+    [0xfe, 0xb6, 0x7c], [0xfe, 0xb7, 0x7e], [0xfe, 0xb9, 0x7f], [0xfe, 0xbb, 0x81], [0xfe, 0xbd, 0x82], [0xfe, 0xbf, 0x84],
+    [0xfe, 0xc1, 0x85], [0xfe, 0xc2, 0x87], [0xfe, 0xc4, 0x88], [0xfe, 0xc6, 0x8a], [0xfe, 0xc8, 0x8c], [0xfe, 0xca, 0x8d],
+    [0xfe, 0xcc, 0x8f], [0xfe, 0xcd, 0x90], [0xfe, 0xcf, 0x92], [0xfe, 0xd1, 0x94], [0xfe, 0xd3, 0x95], [0xfe, 0xd5, 0x97],
+    [0xfe, 0xd7, 0x99], [0xfe, 0xd8, 0x9a], [0xfd, 0xda, 0x9c], [0xfd, 0xdc, 0x9e], [0xfd, 0xde, 0xa0], [0xfd, 0xe0, 0xa1],
+    [0xfd, 0xe2, 0xa3], [0xfd, 0xe3, 0xa5], [0xfd, 0xe5, 0xa7], [0xfd, 0xe7, 0xa9], [0xfd, 0xe9, 0xaa], [0xfd, 0xeb, 0xac],
+    [0xfc, 0xec, 0xae], [0xfc, 0xee, 0xb0], [0xfc, 0xf0, 0xb2], [0xfc, 0xf2, 0xb4], [0xfc, 0xf4, 0xb6], [0xfc, 0xf6, 0xb8],
+    [0xfc, 0xf7, 0xb9], [0xfc, 0xf9, 0xbb], [0xfc, 0xfb, 0xbd], [0xfc, 0xfd, 0xbf],
+];
+
+pub const INFERNO: [[u8; 3]; 256] = [
+    [0x00, 0x00, 0x04], [0x01, 0x00, 0x05], [0x01, 0x01, 0x06], [0x01, 0x01, 0x08], [0x02, 0x01, 0x0a], [0x02, 0x02, 0x0c],
+    [0x02, 0x02, 0x0e], [0x03, 0x02, 0x10], [0x04, 0x03, 0x12], [0x04, 0x03, 0x14], [0x05, 0x04, 0x17], [0x06, 0x04, 0x19],
+    [0x07, 0x05, 0x1b], [0x08, 0x05, 0x1d], [0x09, 0x06, 0x1f], [0x0a, 0x07, 0x22], [0x0b, 0x07, 0x24], [0x0c, 0x08, 0x26],
+    [0x0d, 0x08, 0x29], [0x0e, 0x09, 0x2b], [0x10, 0x09, 0x2d], [0x11, 0x0a, 0x30], [0x12, 0x0a, 0x32], [0x14, 0x0b, 0x34],
+    [0x15, 0x0b, 0x37], [0x16, 0x0b, 0x39], [0x18, 0x0c, 0x3c], [0x19, 0x0c, 0x3e], [0x1b, 0x0c, 0x41], [0x1c, 0x0c, 0x43],
+    [0x1e, 0x0c, 0x45], [0x1f, 0x0c, 0x48], [0x21, 0x0c, 0x4a], [0x23, 0x0c, 0x4c], [0x24, 0x0c, 0x4f], [0x26, 0x0c, 0x51],
+    [0x28, 0x0b, 0x53], [0x29, 0x0b, 0x55], [0x2b, 0x0b, 0x57], [0x2d, 0x0b, 0x59], [0x2f, 0x0a, 0x5b], [0x31, 0x0a, 0x5c],
+    [0x32, 0x0a, 0x5e], [0x34, 0x0a, 0x5f], [0x36, 0x09, 0x61], [0x38, 0x09, 0x62], [0x39, 0x09, 0x63], [0x3b, 0x09, 0x64],
+    [0x3d, 0x09, 0x65], [0x3e, 0x09, 0x66], [0x40, 0x0a, 0x67], [0x42, 0x0a, 0x68], [0x44, 0x0a, 0x68], [0x45, 0x0a, 0x69],
+    [0x47, 0x0b, 0x6a], [0x49, 0x0b, 0x6a], [0x4a, 0x0c, 0x6b], [0x4c, 0x0c, 0x6b], [0x4d, 0x0d, 0x6c], [0x4f, 0x0d, 0x6c],
+    [0x51, 0x0e, 0x6c], [0x52, 0x0e, 0x6d], [0x54, 0x0f, 0x6d], [0x55, 0x0f, 0x6d], [0x57, 0x10, 0x6e], [0x59, 0x10, 0x6e],
+    [0x5a, 0x11, 0x6e], [0x5c, 0x12, 0x6e], [0x5d, 0x12, 0x6e], [0x5f, 0x13, 0x6e], [0x61, 0x13, 0x6e], [0x62, 0x14, 0x6e],
+    [0x64, 0x15, 0x6e], [0x65, 0x15, 0x6e], [0x67, 0x16, 0x6e], [0x69, 0x16, 0x6e], [0x6a, 0x17, 0x6e], [0x6c, 0x18, 0x6e],
+    [0x6d, 0x18, 0x6e], [0x6f, 0x19, 0x6e], [0x71, 0x19, 0x6e], [0x72, 0x1a, 0x6e], [0x74, 0x1a, 0x6e], [0x75, 0x1b, 0x6e],
+    [0x77, 0x1c, 0x6d], [0x78, 0x1c, 0x6d], [0x7a, 0x1d, 0x6d], [0x7c, 0x1d, 0x6d], [0x7d, 0x1e, 0x6d], [0x7f, 0x1e, 0x6c],
+    [0x80, 0x1f, 0x6c], [0x82, 0x20, 0x6c], [0x84, 0x20, 0x6b], [0x85, 0x21, 0x6b], [0x87, 0x21, 0x6b], [0x88, 0x22, 0x6a],
+    [0x8a, 0x22, 0x6a], [0x8c, 0x23, 0x69], [0x8d, 0x23, 0x69], [0x8f, 0x24, 0x69], [0x90, 0x25, 0x68], [0x92, 0x25, 0x68],
+    [0x93, 0x26, 0x67], [0x95, 0x26, 0x67], [0x97, 0x27, 0x66], [0x98, 0x27, 0x66], [0x9a, 0x28, 0x65], [0x9b, 0x29, 0x64],
+    [0x9d, 0x29, 0x64], [0x9f, 0x2a, 0x63], [0xa0, 0x2a, 0x63], [0xa2, 0x2b, 0x62], [0xa3, 0x2c, 0x61], [0xa5, 0x2c, 0x60],
+    [0xa6, 0x2d, 0x60], [0xa8, 0x2e, 0x5f], [0xa9, 0x2e, 0x5e], [0xab, 0x2f, 0x5e], [0xad, 0x30, 0x5d], [0xae, 0x30, 0x5c],
+    [0xb0, 0x31, 0x5b], [0xb1, 0x32, 0x5a], [0xb3, 0x32, 0x5a], [0xb4, 0x33, 0x59], [0xb6, 0x34, 0x58], [0xb7, 0x35, 0x57],
+    [0xb9, 0x35, 0x56], [0xba, 0x36, 0x55], [0xbc, 0x37, 0x54], [0xbd, 0x38, 0x53], [0xbf, 0x39, 0x52], [0xc0, 0x3a, 0x51],
+    [0xc1, 0x3a, 0x50], [0xc3, 0x3b, 0x4f], [0xc4, 0x3c, 0x4e], [0xc6, 0x3d, 0x4d], [0xc7, 0x3e, 0x4c], [0xc8, 0x3f, 0x4b],
+    [0xca, 0x40, 0x4a], [0xcb, 0x41, 0x49], [0xcc, 0x42, 0x48], [0xce, 0x43, 0x47], [0xcf, 0x44, 0x46], [0xd0, 0x45, 0x45],
+    [0xd2, 0x46, 0x44], [0xd3, 0x47, 0x43], [0xd4, 0x48, 0x42], [0xd5, 0x4a, 0x41], [0xd7, 0x4b, 0x3f], [0xd8, 0x4c, 0x3e],
+    [0xd9, 0x4d, 0x3d], [0xda, 0x4e, 0x3c], [0xdb, 0x50, 0x3b], [0xdd, 0x51, 0x3a], [0xde, 0x52, 0x38], [0xdf, 0x53, 0x37],
+    [0xe0, 0x55, 0x36], [0xe1, 0x56, 0x35], [0xe2, 0x57, 0x34], [0xe3, 0x59, 0x33], [0xe4, 0x5a, 0x31], [0xe5, 0x5c, 0x30],
+    [0xe6, 0x5d, 0x2f], [0xe7, 0x5e, 0x2e], [0xe8, 0x60, 0x2d], [0xe9, 0x61, 0x2b], [0xea, 0x63, 0x2a], [0xeb, 0x64, 0x29],
+    [0xeb, 0x66, 0x28], [0xec, 0x67, 0x26], [0xed, 0x69, 0x25], [0xee, 0x6a, 0x24], [0xef, 0x6c, 0x23], [0xef, 0x6e, 0x21],
+    [0xf0, 0x6f, 0x20], [0xf1, 0x71, 0x1f], [0xf1, 0x73, 0x1d], [0xf2, 0x74, 0x1c], [0xf3, 0x76, 0x1b], [0xf3, 0x78, 0x19],
+    [0xf4, 0x79, 0x18], [0xf5, 0x7b, 0x17], [0xf5, 0x7d, 0x15], [0xf6, 0x7e, 0x14], [0xf6, 0x80, 0x13], [0xf7, 0x82, 0x12],
+    [0xf7, 0x84, 0x10], [0xf8, 0x85, 0x0f], [0xf8, 0x87, 0x0e], [0xf8, 0x89, 0x0c], [0xf9, 0x8b, 0x0b], [0xf9, 0x8c, 0x0a],
+    [0xf9, 0x8e, 0x09], [0xfa, 0x90, 0x08], [0xfa, 0x92, 0x07], [0xfa, 0x94, 0x07], [0xfb, 0x96, 0x06], [0xfb, 0x97, 0x06],
+    [0xfb, 0x99, 0x06], [0xfb, 0x9b, 0x06], [0xfb, 0x9d, 0x07], [0xfc, 0x9f, 0x07], [0xfc, 0xa1, 0x08], [0xfc, 0xa3, 0x09],
+    [0xfc, 0xa5, 0x0a], [0xfc, 0xa6, 0x0c], [0xfc, 0xa8, 0x0d], [0xfc, 0xaa, 0x0f], [0xfc, 0xac, 0x11], [0xfc, 0xae, 0x12],
+    [0xfc, 0xb0, 0x14], [0xfc, 0xb2, 0x16], [0xfc, 0xb4, 0x18], [0xfb, 0xb6, 0x1a], [0xfb, 0xb8, 0x1d], [0xfb, 0xba, 0x1f],
+    [0xfb, 0xbc, 0x21], [0xfb, 0xbe, 0x23], [0xfa, 0xc0, 0x26], [0xfa, 0xc2, 0x28], [0xfa, 0xc4, 0x2a], [0xfa, 0xc6, 0x2d],
+    [0xf9, 0xc7, 0x2f], [0xf9, 0xc9, 0x32], [0xf9, 0xcb, 0x35], [0xf8, 0xcd, 0x37], [0xf8, 0xcf, 0x3a], [0xf7, 0xd1, 0x3d],
+    [0xf7, 0xd3, 0x40], [0xf6, 0xd5, 0x43], [0xf6, 0xd7, 0x46], [0xf5, 0xd9, 0x49], [0xf5, 0xdb, 0x4c], [0xf4, 0xdd, 0x4f],
+    [0xf4, 0xdf, 0x53], [0xf4, 0xe1, 0x56], [0xf3, 0xe3, 0x5a], [0xf3, 0xe5, 0x5d], [0xf2, 0xe6, 0x61], [0xf2, 0xe8, 0x65],
+    [0xf2, 0xea, 0x69], [0xf1, 0xec, 0x6d], [0xf1, 0xed, 0x71], [0xf1, 0xef, 0x75], [0xf1, 0xf1, 0x79], [0xf2, 0xf2, 0x7d],
+    [0xf2, 0xf4, 0x82], [0xf3, 0xf5, 0x86], [0xf3, 0xf6, 0x8a], [0xf4, 0xf8, 0x8e], [0xf5, 0xf9, 0x92], [0xf6, 0xfa, 0x96],
+    [0xf8, 0xfb, 0x9a], [0xf9, 0xfc, 0x9d], [0xfa, 0xfd, 0xa1], [0xfc, 0xff, 0xa4],
+];
+
+pub const PLASMA: [[u8; 3]; 256] = [
+    [0x0d, 0x08, 0x87], [0x10, 0x07, 0x88], [0x13, 0x07, 0x89], [0x16, 0x07, 0x8a], [0x19, 0x06, 0x8c], [0x1b, 0x06, 0x8d],
+    [0x1d, 0x06, 0x8e], [0x20, 0x06, 0x8f], [0x22, 0x06, 0x90], [0x24, 0x06, 0x91], [0x26, 0x05, 0x91], [0x28, 0x05, 0x92],
+    [0x2a, 0x05, 0x93], [0x2c, 0x05, 0x94], [0x2e, 0x05, 0x95], [0x2f, 0x05, 0x96], [0x31, 0x05, 0x97], [0x33, 0x05, 0x97],
+    [0x35, 0x04, 0x98], [0x37, 0x04, 0x99], [0x38, 0x04, 0x9a], [0x3a, 0x04, 0x9a], [0x3c, 0x04, 0x9b], [0x3e, 0x04, 0x9c],
+    [0x3f, 0x04, 0x9c], [0x41, 0x04, 0x9d], [0x43, 0x03, 0x9e], [0x44, 0x03, 0x9e], [0x46, 0x03, 0x9f], [0x48, 0x03, 0x9f],
+    [0x49, 0x03, 0xa0], [0x4b, 0x03, 0xa1], [0x4c, 0x02, 0xa1], [0x4e, 0x02, 0xa2], [0x50, 0x02, 0xa2], [0x51, 0x02, 0xa3],
+    [0x53, 0x02, 0xa3], [0x55, 0x02, 0xa4], [0x56, 0x01, 0xa4], [0x58, 0x01, 0xa4], [0x59, 0x01, 0xa5], [0x5b, 0x01, 0xa5],
+    [0x5c, 0x01, 0xa6], [0x5e, 0x01, 0xa6], [0x60, 0x01, 0xa6], [0x61, 0x00, 0xa7], [0x63, 0x00, 0xa7], [0x64, 0x00, 0xa7],
+    [0x66, 0x00, 0xa7], [0x67, 0x00, 0xa8], [0x69, 0x00, 0xa8], [0x6a, 0x00, 0xa8], [0x6c, 0x00, 0xa8], [0x6e, 0x00, 0xa8],
+    [0x6f, 0x00, 0xa8], [0x71, 0x00, 0xa8], [0x72, 0x01, 0xa8], [0x74, 0x01, 0xa8], [0x75, 0x01, 0xa8], [0x77, 0x01, 0xa8],
+    [0x78, 0x01, 0xa8], [0x7a, 0x02, 0xa8], [0x7b, 0x02, 0xa8], [0x7d, 0x03, 0xa8], [0x7e, 0x03, 0xa8], [0x80, 0x04, 0xa8],
+    [0x81, 0x04, 0xa7], [0x83, 0x05, 0xa7], [0x84, 0x05, 0xa7], [0x86, 0x06, 0xa6], [0x87, 0x07, 0xa6], [0x88, 0x08, 0xa6],
+    [0x8a, 0x09, 0xa5], [0x8b, 0x0a, 0xa5], [0x8d, 0x0b, 0xa5], [0x8e, 0x0c, 0xa4], [0x8f, 0x0d, 0xa4], [0x91, 0x0e, 0xa3],
+    [0x92, 0x0f, 0xa3], [0x94, 0x10, 0xa2], [0x95, 0x11, 0xa1], [0x96, 0x13, 0xa1], [0x98, 0x14, 0xa0], [0x99, 0x15, 0x9f],
+    [0x9a, 0x16, 0x9f], [0x9c, 0x17, 0x9e], [0x9d, 0x18, 0x9d], [0x9e, 0x19, 0x9d], [0xa0, 0x1a, 0x9c], [0xa1, 0x1b, 0x9b],
+    [0xa2, 0x1d, 0x9a], [0xa3, 0x1e, 0x9a], [0xa5, 0x1f, 0x99], [0xa6, 0x20, 0x98], [0xa7, 0x21, 0x97], [0xa8, 0x22, 0x96],
+    [0xaa, 0x23, 0x95], [0xab, 0x24, 0x94], [0xac, 0x26, 0x94], [0xad, 0x27, 0x93], [0xae, 0x28, 0x92], [0xb0, 0x29, 0x91],
+    [0xb1, 0x2a, 0x90], [0xb2, 0x2b, 0x8f], [0xb3, 0x2c, 0x8e], [0xb4, 0x2e, 0x8d], [0xb5, 0x2f, 0x8c], [0xb6, 0x30, 0x8b],
+    [0xb7, 0x31, 0x8a], [0xb8, 0x32, 0x89], [0xba, 0x33, 0x88], [0xbb, 0x34, 0x88], [0xbc, 0x35, 0x87], [0xbd, 0x37, 0x86],
+    [0xbe, 0x38, 0x85], [0xbf, 0x39, 0x84], [0xc0, 0x3a, 0x83], [0xc1, 0x3b, 0x82], [0xc2, 0x3c, 0x81], [0xc3, 0x3d, 0x80],
+    [0xc4, 0x3e, 0x7f], [0xc5, 0x40, 0x7e], [0xc6, 0x41, 0x7d], [0xc7, 0x42, 0x7c], [0xc8, 0x43, 0x7b], [0xc9, 0x44, 0x7a],
+    [0xca, 0x45, 0x7a], [0xcb, 0x46, 0x79], [0xcc, 0x47, 0x78], [0xcc, 0x49, 0x77], [0xcd, 0x4a, 0x76], [0xce, 0x4b, 0x75],
+    [0xcf, 0x4c, 0x74], [0xd0, 0x4d, 0x73], [0xd1, 0x4e, 0x72], [0xd2, 0x4f, 0x71], [0xd3, 0x51, 0x71], [0xd4, 0x52, 0x70],
+    [0xd5, 0x53, 0x6f], [0xd5, 0x54, 0x6e], [0xd6, 0x55, 0x6d], [0xd7, 0x56, 0x6c], [0xd8, 0x57, 0x6b], [0xd9, 0x58, 0x6a],
+    [0xda, 0x5a, 0x6a], [0xda, 0x5b, 0x69], [0xdb, 0x5c, 0x68], [0xdc, 0x5d, 0x67], [0xdd, 0x5e, 0x66], [0xde, 0x5f, 0x65],
+    [0xde, 0x61, 0x64], [0xdf, 0x62, 0x63], [0xe0, 0x63, 0x63], [0xe1, 0x64, 0x62], [0xe2, 0x65, 0x61], [0xe2, 0x66, 0x60],
+    [0xe3, 0x68, 0x5f], [0xe4, 0x69, 0x5e], [0xe5, 0x6a, 0x5d], [0xe5, 0x6b, 0x5d], [0xe6, 0x6c, 0x5c], [0xe7, 0x6e, 0x5b],
+    [0xe7, 0x6f, 0x5a], [0xe8, 0x70, 0x59], [0xe9, 0x71, 0x58], [0xe9, 0x72, 0x57], [0xea, 0x74, 0x57], [0xeb, 0x75, 0x56],
+    [0xeb, 0x76, 0x55], [0xec, 0x77, 0x54], [0xed, 0x79, 0x53], [0xed, 0x7a, 0x52], [0xee, 0x7b, 0x51], [0xef, 0x7c, 0x51],
+    [0xef, 0x7e, 0x50], [0xf0, 0x7f, 0x4f], [0xf0, 0x80, 0x4e], [0xf1, 0x81, 0x4d], [0xf1, 0x83, 0x4c], [0xf2, 0x84, 0x4b],
+    [0xf3, 0x85, 0x4b], [0xf3, 0x87, 0x4a], [0xf4, 0x88, 0x49], [0xf4, 0x89, 0x48], [0xf5, 0x8b, 0x47], [0xf5, 0x8c, 0x46],
+    [0xf6, 0x8d, 0x45], [0xf6, 0x8f, 0x44], [0xf7, 0x90, 0x44], [0xf7, 0x91, 0x43], [0xf7, 0x93, 0x42], [0xf8, 0x94, 0x41],
+    [0xf8, 0x95, 0x40], [0xf9, 0x97, 0x3f], [0xf9, 0x98, 0x3e], [0xf9, 0x9a, 0x3e], [0xfa, 0x9b, 0x3d], [0xfa, 0x9c, 0x3c],
+    [0xfa, 0x9e, 0x3b], [0xfb, 0x9f, 0x3a], [0xfb, 0xa1, 0x39], [0xfb, 0xa2, 0x38], [0xfc, 0xa3, 0x38], [0xfc, 0xa5, 0x37],
+    [0xfc, 0xa6, 0x36], [0xfc, 0xa8, 0x35], [0xfc, 0xa9, 0x34], [0xfd, 0xab, 0x33], [0xfd, 0xac, 0x33], [0xfd, 0xae, 0x32],
+    [0xfd, 0xaf, 0x31], [0xfd, 0xb1, 0x30], [0xfd, 0xb2, 0x2f], [0xfd, 0xb4, 0x2f], [0xfd, 0xb5, 0x2e], [0xfe, 0xb7, 0x2d],
+    [0xfe, 0xb8, 0x2c], [0xfe, 0xba, 0x2c], [0xfe, 0xbb, 0x2b], [0xfe, 0xbd, 0x2a], [0xfe, 0xbe, 0x2a], [0xfe, 0xc0, 0x29],
+    [0xfd, 0xc2, 0x29], [0xfd, 0xc3, 0x28], [0xfd, 0xc5, 0x27], [0xfd, 0xc6, 0x27], [0xfd, 0xc8, 0x27], [0xfd, 0xca, 0x26],
+    [0xfd, 0xcb, 0x26], [0xfc, 0xcd, 0x25], [0xfc, 0xce, 0x25], [0xfc, 0xd0, 0x25], [0xfc, 0xd2, 0x25], [0xfb, 0xd3, 0x24],
+    [0xfb, 0xd5, 0x24], [0xfb, 0xd7, 0x24], [0xfa, 0xd8, 0x24], [0xfa, 0xda, 0x24], [0xf9, 0xdc, 0x24], [0xf9, 0xdd, 0x25],
+    [0xf8, 0xdf, 0x25], [0xf8, 0xe1, 0x25], [0xf7, 0xe2, 0x25], [0xf7, 0xe4, 0x25], [0xf6, 0xe6, 0x26], [0xf6, 0xe8, 0x26],
+    [0xf5, 0xe9, 0x26], [0xf5, 0xeb, 0x27], [0xf4, 0xed, 0x27], [0xf3, 0xee, 0x27], [0xf3, 0xf0, 0x27], [0xf2, 0xf2, 0x27],
+    [0xf1, 0xf4, 0x26], [0xf1, 0xf5, 0x25], [0xf0, 0xf7, 0x24], [0xf0, 0xf9, 0x21],
+];
+
+pub const CIVIDIS: [[u8; 3]; 256] = [
+    [0x00, 0x20, 0x51], [0x00, 0x21, 0x53], [0x00, 0x22, 0x55], [0x00, 0x23, 0x56], [0x00, 0x23, 0x58], [0x00, 0x24, 0x59],
+    [0x00, 0x25, 0x5a], [0x00, 0x25, 0x5c], [0x00, 0x26, 0x5d], [0x00, 0x27, 0x5e], [0x00, 0x27, 0x5f], [0x00, 0x28, 0x60],
+    [0x00, 0x29, 0x61], [0x00, 0x29, 0x62], [0x00, 0x2a, 0x63], [0x00, 0x2b, 0x64], [0x01, 0x2b, 0x65], [0x02, 0x2c, 0x65],
+    [0x03, 0x2d, 0x66], [0x04, 0x2d, 0x67], [0x05, 0x2e, 0x67], [0x05, 0x2f, 0x68], [0x06, 0x30, 0x69], [0x07, 0x30, 0x69],
+    [0x08, 0x31, 0x6a], [0x09, 0x32, 0x6a], [0x0b, 0x32, 0x6a], [0x0c, 0x33, 0x6b], [0x0d, 0x34, 0x6b], [0x0e, 0x34, 0x6b],
+    [0x0f, 0x35, 0x6c], [0x10, 0x36, 0x6c], [0x12, 0x37, 0x6c], [0x13, 0x37, 0x6d], [0x14, 0x38, 0x6d], [0x15, 0x39, 0x6d],
+    [0x17, 0x39, 0x6d], [0x18, 0x3a, 0x6d], [0x19, 0x3b, 0x6d], [0x1a, 0x3b, 0x6d], [0x1c, 0x3c, 0x6e], [0x1d, 0x3d, 0x6e],
+    [0x1e, 0x3e, 0x6e], [0x20, 0x3e, 0x6e], [0x21, 0x3f, 0x6e], [0x23, 0x40, 0x6e], [0x24, 0x40, 0x6e], [0x25, 0x41, 0x6e],
+    [0x27, 0x42, 0x6e], [0x28, 0x43, 0x6e], [0x29, 0x43, 0x6e], [0x2b, 0x44, 0x6e], [0x2c, 0x45, 0x6e], [0x2e, 0x45, 0x6e],
+    [0x2f, 0x46, 0x6e], [0x30, 0x47, 0x6e], [0x32, 0x48, 0x6e], [0x33, 0x48, 0x6e], [0x34, 0x49, 0x6e], [0x36, 0x4a, 0x6e],
+    [0x37, 0x4a, 0x6e], [0x39, 0x4b, 0x6e], [0x3a, 0x4c, 0x6e], [0x3b, 0x4d, 0x6e], [0x3d, 0x4d, 0x6e], [0x3e, 0x4e, 0x6e],
+    [0x3f, 0x4f, 0x6e], [0x41, 0x4f, 0x6e], [0x42, 0x50, 0x6e], [0x43, 0x51, 0x6d], [0x44, 0x52, 0x6d], [0x46, 0x52, 0x6d],
+    [0x47, 0x53, 0x6d], [0x48, 0x54, 0x6d], [0x4a, 0x54, 0x6d], [0x4b, 0x55, 0x6d], [0x4c, 0x56, 0x6d], [0x4d, 0x57, 0x6d],
+    [0x4e, 0x57, 0x6e], [0x50, 0x58, 0x6e], [0x51, 0x59, 0x6e], [0x52, 0x59, 0x6e], [0x53, 0x5a, 0x6e], [0x54, 0x5b, 0x6e],
+    [0x56, 0x5c, 0x6e], [0x57, 0x5c, 0x6e], [0x58, 0x5d, 0x6e], [0x59, 0x5e, 0x6e], [0x5a, 0x5e, 0x6e], [0x5b, 0x5f, 0x6e],
+    [0x5c, 0x60, 0x6e], [0x5d, 0x61, 0x6e], [0x5e, 0x61, 0x6e], [0x60, 0x62, 0x6e], [0x61, 0x63, 0x6f], [0x62, 0x64, 0x6f],
+    [0x63, 0x64, 0x6f], [0x64, 0x65, 0x6f], [0x65, 0x66, 0x6f], [0x66, 0x66, 0x6f], [0x67, 0x67, 0x6f], [0x68, 0x68, 0x70],
+    [0x69, 0x69, 0x70], [0x6a, 0x69, 0x70], [0x6b, 0x6a, 0x70], [0x6c, 0x6b, 0x70], [0x6d, 0x6c, 0x70], [0x6d, 0x6c, 0x71],
+    [0x6e, 0x6d, 0x71], [0x6f, 0x6e, 0x71], [0x70, 0x6f, 0x71], [0x71, 0x6f, 0x71], [0x72, 0x70, 0x71], [0x73, 0x71, 0x72],
+    [0x74, 0x71, 0x72], [0x75, 0x72, 0x72], [0x76, 0x73, 0x72], [0x76, 0x74, 0x72], [0x77, 0x74, 0x73], [0x78, 0x75, 0x73],
+    [0x79, 0x76, 0x73], [0x7a, 0x77, 0x73], [0x7b, 0x77, 0x74], [0x7b, 0x78, 0x74], [0x7c, 0x79, 0x74], [0x7d, 0x7a, 0x74],
+    [0x7e, 0x7a, 0x74], [0x7f, 0x7b, 0x75], [0x80, 0x7c, 0x75], [0x80, 0x7d, 0x75], [0x81, 0x7d, 0x75], [0x82, 0x7e, 0x75],
+    [0x83, 0x7f, 0x76], [0x84, 0x80, 0x76], [0x85, 0x80, 0x76], [0x85, 0x81, 0x76], [0x86, 0x82, 0x76], [0x87, 0x83, 0x76],
+    [0x88, 0x84, 0x77], [0x89, 0x84, 0x77], [0x89, 0x85, 0x77], [0x8a, 0x86, 0x77], [0x8b, 0x87, 0x77], [0x8c, 0x87, 0x77],
+    [0x8d, 0x88, 0x77], [0x8e, 0x89, 0x78], [0x8e, 0x8a, 0x78], [0x8f, 0x8a, 0x78], [0x90, 0x8b, 0x78], [0x91, 0x8c, 0x78],
+    [0x92, 0x8d, 0x78], [0x93, 0x8e, 0x78], [0x93, 0x8e, 0x78], [0x94, 0x8f, 0x78], [0x95, 0x90, 0x78], [0x96, 0x91, 0x78],
+    [0x97, 0x92, 0x78], [0x98, 0x92, 0x78], [0x99, 0x93, 0x78], [0x9a, 0x94, 0x78], [0x9b, 0x95, 0x78], [0x9b, 0x96, 0x78],
+    [0x9c, 0x96, 0x78], [0x9d, 0x97, 0x78], [0x9e, 0x98, 0x78], [0x9f, 0x99, 0x78], [0xa0, 0x9a, 0x78], [0xa1, 0x9a, 0x78],
+    [0xa2, 0x9b, 0x78], [0xa3, 0x9c, 0x78], [0xa4, 0x9d, 0x78], [0xa5, 0x9e, 0x77], [0xa6, 0x9e, 0x77], [0xa7, 0x9f, 0x77],
+    [0xa8, 0xa0, 0x77], [0xa9, 0xa1, 0x77], [0xaa, 0xa2, 0x76], [0xab, 0xa3, 0x76], [0xac, 0xa3, 0x76], [0xad, 0xa4, 0x76],
+    [0xae, 0xa5, 0x75], [0xaf, 0xa6, 0x75], [0xb0, 0xa7, 0x75], [0xb2, 0xa8, 0x74], [0xb3, 0xa8, 0x74], [0xb4, 0xa9, 0x74],
+    [0xb5, 0xaa, 0x73], [0xb6, 0xab, 0x73], [0xb7, 0xac, 0x72], [0xb8, 0xad, 0x72], [0xba, 0xae, 0x72], [0xbb, 0xae, 0x71],
+    [0xbc, 0xaf, 0x71], [0xbd, 0xb0, 0x70], [0xbe, 0xb1, 0x70], [0xbf, 0xb2, 0x6f], [0xc1, 0xb3, 0x6f], [0xc2, 0xb4, 0x6e],
+    [0xc3, 0xb5, 0x6d], [0xc4, 0xb5, 0x6d], [0xc5, 0xb6, 0x6c], [0xc7, 0xb7, 0x6c], [0xc8, 0xb8, 0x6b], [0xc9, 0xb9, 0x6a],
+    [0xca, 0xba, 0x6a], [0xcc, 0xbb, 0x69], [0xcd, 0xbc, 0x68], [0xce, 0xbc, 0x68], [0xcf, 0xbd, 0x67], [0xd1, 0xbe, 0x66],
+    [0xd2, 0xbf, 0x66], [0xd3, 0xc0, 0x65], [0xd4, 0xc1, 0x64], [0xd6, 0xc2, 0x63], [0xd7, 0xc3, 0x63], [0xd8, 0xc4, 0x62],
+    [0xd9, 0xc5, 0x61], [0xdb, 0xc6, 0x60], [0xdc, 0xc6, 0x60], [0xdd, 0xc7, 0x5f], [0xde, 0xc8, 0x5e], [0xe0, 0xc9, 0x5d],
+    [0xe1, 0xca, 0x5c], [0xe2, 0xcb, 0x5c], [0xe3, 0xcc, 0x5b], [0xe4, 0xcd, 0x5a], [0xe6, 0xce, 0x59], [0xe7, 0xcf, 0x58],
+    [0xe8, 0xd0, 0x58], [0xe9, 0xd1, 0x57], [0xea, 0xd2, 0x56], [0xeb, 0xd3, 0x55], [0xec, 0xd4, 0x54], [0xed, 0xd4, 0x53],
+    [0xee, 0xd5, 0x53], [0xf0, 0xd6, 0x52], [0xf1, 0xd7, 0x51], [0xf1, 0xd8, 0x50], [0xf2, 0xd9, 0x50], [0xf3, 0xda, 0x4f],
+    [0xf4, 0xdb, 0x4e], [0xf5, 0xdc, 0x4d], [0xf6, 0xdd, 0x4d], [0xf7, 0xde, 0x4c], [0xf8, 0xdf, 0x4b], [0xf8, 0xe0, 0x4b],
+    [0xf9, 0xe1, 0x4a], [0xfa, 0xe2, 0x49], [0xfa, 0xe3, 0x49], [0xfb, 0xe4, 0x48], [0xfb, 0xe5, 0x48], [0xfc, 0xe6, 0x47],
+    [0xfc, 0xe7, 0x46], [0xfd, 0xe8, 0x46], [0xfd, 0xe9, 0x46], [0xfd, 0xea, 0x45],
+];
+
+pub const TURBO: [[u8; 3]; 256] = [
+    [0x23, 0x17, 0x1b], [0x27, 0x1a, 0x28], [0x2b, 0x1c, 0x33], [0x2f, 0x1e, 0x3f], [0x32, 0x20, 0x4a], [0x36, 0x23, 0x54],
+    [0x39, 0x25, 0x5f], [0x3b, 0x27, 0x68], [0x3e, 0x2a, 0x72], [0x40, 0x2c, 0x7b], [0x42, 0x2f, 0x83], [0x44, 0x31, 0x8b],
+    [0x45, 0x34, 0x93], [0x46, 0x36, 0x9b], [0x48, 0x39, 0xa2], [0x49, 0x3c, 0xa8], [0x49, 0x3e, 0xaf], [0x4a, 0x41, 0xb5],
+    [0x4a, 0x44, 0xbb], [0x4b, 0x46, 0xc0], [0x4b, 0x49, 0xc5], [0x4b, 0x4c, 0xca], [0x4b, 0x4e, 0xcf], [0x4b, 0x51, 0xd3],
+    [0x4a, 0x54, 0xd7], [0x4a, 0x56, 0xdb], [0x49, 0x59, 0xde], [0x49, 0x5c, 0xe2], [0x48, 0x5f, 0xe5], [0x47, 0x61, 0xe7],
+    [0x46, 0x64, 0xea], [0x45, 0x67, 0xec], [0x44, 0x6a, 0xee], [0x44, 0x6d, 0xf0], [0x42, 0x6f, 0xf2], [0x41, 0x72, 0xf3],
+    [0x40, 0x75, 0xf5], [0x3f, 0x78, 0xf6], [0x3e, 0x7a, 0xf7], [0x3d, 0x7d, 0xf7], [0x3c, 0x80, 0xf8], [0x3a, 0x83, 0xf9],
+    [0x39, 0x85, 0xf9], [0x38, 0x88, 0xf9], [0x37, 0x8b, 0xf9], [0x36, 0x8d, 0xf9], [0x35, 0x90, 0xf8], [0x33, 0x93, 0xf8],
+    [0x32, 0x95, 0xf7], [0x31, 0x98, 0xf7], [0x30, 0x9b, 0xf6], [0x2f, 0x9d, 0xf5], [0x2e, 0xa0, 0xf4], [0x2d, 0xa2, 0xf3],
+    [0x2c, 0xa5, 0xf1], [0x2b, 0xa7, 0xf0], [0x2a, 0xaa, 0xef], [0x2a, 0xac, 0xed], [0x29, 0xaf, 0xec], [0x28, 0xb1, 0xea],
+    [0x28, 0xb4, 0xe8], [0x27, 0xb6, 0xe6], [0x27, 0xb8, 0xe5], [0x26, 0xbb, 0xe3], [0x26, 0xbd, 0xe1], [0x26, 0xbf, 0xdf],
+    [0x25, 0xc1, 0xdc], [0x25, 0xc3, 0xda], [0x25, 0xc6, 0xd8], [0x25, 0xc8, 0xd6], [0x25, 0xca, 0xd3], [0x25, 0xcc, 0xd1],
+    [0x25, 0xce, 0xcf], [0x26, 0xd0, 0xcc], [0x26, 0xd2, 0xca], [0x26, 0xd4, 0xc8], [0x27, 0xd6, 0xc5], [0x27, 0xd8, 0xc3],
+    [0x28, 0xd9, 0xc0], [0x29, 0xdb, 0xbe], [0x29, 0xdd, 0xbb], [0x2a, 0xdf, 0xb8], [0x2b, 0xe0, 0xb6], [0x2c, 0xe2, 0xb3],
+    [0x2d, 0xe3, 0xb1], [0x2e, 0xe5, 0xae], [0x30, 0xe6, 0xac], [0x31, 0xe8, 0xa9], [0x32, 0xe9, 0xa6], [0x34, 0xeb, 0xa4],
+    [0x35, 0xec, 0xa1], [0x37, 0xed, 0x9f], [0x39, 0xef, 0x9c], [0x3a, 0xf0, 0x9a], [0x3c, 0xf1, 0x97], [0x3e, 0xf2, 0x95],
+    [0x40, 0xf3, 0x92], [0x42, 0xf4, 0x90], [0x44, 0xf5, 0x8d], [0x46, 0xf6, 0x8b], [0x48, 0xf7, 0x88], [0x4a, 0xf7, 0x86],
+    [0x4d, 0xf8, 0x84], [0x4f, 0xf9, 0x81], [0x51, 0xfa, 0x7f], [0x54, 0xfa, 0x7d], [0x56, 0xfb, 0x7a], [0x59, 0xfb, 0x78],
+    [0x5c, 0xfc, 0x76], [0x5e, 0xfc, 0x74], [0x61, 0xfd, 0x71], [0x64, 0xfd, 0x6f], [0x66, 0xfd, 0x6d], [0x69, 0xfd, 0x6b],
+    [0x6c, 0xfd, 0x69], [0x6f, 0xfe, 0x67], [0x72, 0xfe, 0x65], [0x75, 0xfe, 0x63], [0x78, 0xfe, 0x61], [0x7b, 0xfe, 0x5f],
+    [0x7e, 0xfd, 0x5d], [0x81, 0xfd, 0x5c], [0x84, 0xfd, 0x5a], [0x87, 0xfd, 0x58], [0x8a, 0xfc, 0x56], [0x8d, 0xfc, 0x55],
+    [0x90, 0xfb, 0x53], [0x93, 0xfb, 0x51], [0x96, 0xfa, 0x50], [0x99, 0xfa, 0x4e], [0x9c, 0xf9, 0x4d], [0x9f, 0xf8, 0x4b],
+    [0xa2, 0xf8, 0x4a], [0xa6, 0xf7, 0x48], [0xa9, 0xf6, 0x47], [0xac, 0xf5, 0x46], [0xaf, 0xf4, 0x44], [0xb2, 0xf3, 0x43],
+    [0xb5, 0xf2, 0x42], [0xb8, 0xf1, 0x41], [0xbb, 0xf0, 0x3f], [0xbe, 0xef, 0x3e], [0xc1, 0xed, 0x3d], [0xc3, 0xec, 0x3c],
+    [0xc6, 0xeb, 0x3b], [0xc9, 0xe9, 0x3a], [0xcc, 0xe8, 0x39], [0xcf, 0xe7, 0x38], [0xd1, 0xe5, 0x37], [0xd4, 0xe3, 0x36],
+    [0xd7, 0xe2, 0x35], [0xd9, 0xe0, 0x34], [0xdc, 0xdf, 0x33], [0xde, 0xdd, 0x32], [0xe0, 0xdb, 0x32], [0xe3, 0xd9, 0x31],
+    [0xe5, 0xd7, 0x30], [0xe7, 0xd5, 0x2f], [0xe9, 0xd4, 0x2f], [0xec, 0xd2, 0x2e], [0xee, 0xd0, 0x2d], [0xf0, 0xce, 0x2c],
+    [0xf1, 0xcb, 0x2c], [0xf3, 0xc9, 0x2b], [0xf5, 0xc7, 0x2b], [0xf7, 0xc5, 0x2a], [0xf8, 0xc3, 0x29], [0xfa, 0xc0, 0x29],
+    [0xfb, 0xbe, 0x28], [0xfd, 0xbc, 0x28], [0xfe, 0xb9, 0x27], [0xff, 0xb7, 0x27], [0xff, 0xb5, 0x26], [0xff, 0xb2, 0x26],
+    [0xff, 0xb0, 0x25], [0xff, 0xad, 0x25], [0xff, 0xab, 0x24], [0xff, 0xa8, 0x24], [0xff, 0xa6, 0x23], [0xff, 0xa3, 0x23],
+    [0xff, 0xa0, 0x22], [0xff, 0x9e, 0x22], [0xff, 0x9b, 0x21], [0xff, 0x99, 0x21], [0xff, 0x96, 0x21], [0xff, 0x93, 0x20],
+    [0xff, 0x90, 0x20], [0xff, 0x8e, 0x1f], [0xff, 0x8b, 0x1f], [0xff, 0x88, 0x1e], [0xff, 0x85, 0x1e], [0xff, 0x83, 0x1d],
+    [0xff, 0x80, 0x1d], [0xff, 0x7d, 0x1d], [0xff, 0x7a, 0x1c], [0xff, 0x78, 0x1c], [0xff, 0x75, 0x1b], [0xff, 0x72, 0x1b],
+    [0xff, 0x6f, 0x1a], [0xfd, 0x6c, 0x1a], [0xfc, 0x6a, 0x19], [0xfa, 0x67, 0x19], [0xf9, 0x64, 0x18], [0xf7, 0x61, 0x18],
+    [0xf6, 0x5f, 0x18], [0xf4, 0x5c, 0x17], [0xf2, 0x59, 0x16], [0xf0, 0x57, 0x16], [0xee, 0x54, 0x15], [0xec, 0x51, 0x15],
+    [0xea, 0x4f, 0x14], [0xe8, 0x4c, 0x14], [0xe6, 0x49, 0x13], [0xe4, 0x47, 0x13], [0xe2, 0x44, 0x12], [0xdf, 0x42, 0x12],
+    [0xdd, 0x3f, 0x11], [0xda, 0x3d, 0x10], [0xd8, 0x3a, 0x10], [0xd5, 0x38, 0x0f], [0xd3, 0x36, 0x0f], [0xd0, 0x33, 0x0e],
+    [0xce, 0x31, 0x0d], [0xcb, 0x2f, 0x0d], [0xc9, 0x2d, 0x0c], [0xc6, 0x2a, 0x0b], [0xc3, 0x28, 0x0b], [0xc1, 0x26, 0x0a],
+    [0xbe, 0x24, 0x09], [0xbb, 0x23, 0x09], [0xb9, 0x21, 0x08], [0xb6, 0x1f, 0x07], [0xb4, 0x1d, 0x07], [0xb1, 0x1b, 0x06],
+    [0xaf, 0x1a, 0x05], [0xac, 0x18, 0x05], [0xaa, 0x17, 0x04], [0xa8, 0x16, 0x04], [0xa5, 0x14, 0x03], [0xa3, 0x13, 0x02],
+    [0xa1, 0x12, 0x02], [0x9f, 0x11, 0x01], [0x9d, 0x10, 0x00], [0x9b, 0x0f, 0x00], [0x9a, 0x0e, 0x00], [0x98, 0x0e, 0x00],
+    [0x96, 0x0d, 0x00], [0x95, 0x0c, 0x00], [0x94, 0x0c, 0x00], [0x93, 0x0c, 0x00], [0x92, 0x0c, 0x00], [0x91, 0x0b, 0x00],
+    [0x91, 0x0c, 0x00], [0x90, 0x0c, 0x00], [0x90, 0x0c, 0x00], [0x90, 0x0c, 0x00],
+];
@@ -0,0 +1,308 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::ops::Mul;
+
+use super::{clamp, Channel, Rgb, Rgba};
+
+/// The luminance coefficients used by the SVG `feColorMatrix` filter.
+const SVG_LUMINANCE: [f64; 3] = [0.2125, 0.7154, 0.0721];
+
+/// An affine transform of red, green, blue and alpha, in the style of the
+/// SVG `feColorMatrix` filter. Each row gives one output channel as a
+/// weighted sum of the input red, green, blue and alpha, plus an offset in
+/// the last column. Channels are treated as values between zero and one,
+/// and results are clamped to that range.
+///
+/// ```rust
+/// use simplecolor::{ColorMatrix, Rgb};
+///
+/// let muted_warm = ColorMatrix::saturate(0.6).then(&ColorMatrix::sepia(0.2));
+/// let col = muted_warm.apply(&Rgb::with_components(40u8, 120, 220));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorMatrix {
+    /// Rows for red, green, blue and alpha
+    rows: [[f64; 5]; 4]
+}
+
+impl ColorMatrix {
+    /// The matrix which leaves colors unchanged.
+    pub fn identity() -> ColorMatrix {
+        ColorMatrix::from_3x3([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Create a matrix from a 4x5 affine matrix by rows, in the order of
+    /// the `values` attribute of `feColorMatrix type="matrix"`.
+    pub fn from_4x5(rows: [[f64; 5]; 4]) -> ColorMatrix {
+        ColorMatrix { rows: rows }
+    }
+
+    /// Create a matrix transforming red, green and blue from a 3x3 matrix
+    /// by rows, leaving alpha unchanged.
+    pub fn from_3x3(m: [[f64; 3]; 3]) -> ColorMatrix {
+        let mut rows = [[0.0; 5]; 4];
+        for i in 0..3 {
+            rows[i][..3].copy_from_slice(&m[i]);
+        }
+        rows[3][3] = 1.0;
+        ColorMatrix { rows: rows }
+    }
+
+    /// Create a matrix scaling each channel and adding an offset, like a
+    /// linear `feComponentTransfer` function for each channel.
+    pub fn scale(slope: [f64; 4], intercept: [f64; 4]) -> ColorMatrix {
+        let mut rows = [[0.0; 5]; 4];
+        for i in 0..4 {
+            rows[i][i] = slope[i];
+            rows[i][4] = intercept[i];
+        }
+        ColorMatrix { rows: rows }
+    }
+
+    /// The `saturate` matrix of `feColorMatrix`. Zero gives grey, one
+    /// leaves colors unchanged, and larger values oversaturate.
+    pub fn saturate(s: f64) -> ColorMatrix {
+        ColorMatrix::from_3x3([
+            [0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s]])
+    }
+
+    /// The `hueRotate` matrix of `feColorMatrix`, rotating hues by an angle
+    /// in degrees while approximately preserving luminance.
+    pub fn hue_rotate(degrees: f64) -> ColorMatrix {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        ColorMatrix::from_3x3([
+            [0.213 + cos * 0.787 - sin * 0.213,
+             0.715 - cos * 0.715 - sin * 0.715,
+             0.072 - cos * 0.072 + sin * 0.928],
+            [0.213 - cos * 0.213 + sin * 0.143,
+             0.715 + cos * 0.285 + sin * 0.140,
+             0.072 - cos * 0.072 - sin * 0.283],
+            [0.213 - cos * 0.213 - sin * 0.787,
+             0.715 - cos * 0.715 + sin * 0.715,
+             0.072 + cos * 0.928 + sin * 0.072]])
+    }
+
+    /// The `luminanceToAlpha` matrix of `feColorMatrix`, which sets alpha to
+    /// the luminance of the color and red, green and blue to zero.
+    pub fn luminance_to_alpha() -> ColorMatrix {
+        let l = SVG_LUMINANCE;
+        ColorMatrix::from_4x5([[0.0; 5], [0.0; 5], [0.0; 5], [l[0], l[1], l[2], 0.0, 0.0]])
+    }
+
+    /// The matrix of the CSS `grayscale()` filter. The amount is clamped
+    /// between zero and one, where one is fully grey.
+    pub fn grayscale(amount: f64) -> ColorMatrix {
+        let s = 1.0 - clamp(amount, 0.0, 1.0);
+        ColorMatrix::from_3x3([
+            [0.2126 + 0.7874 * s, 0.7152 - 0.7152 * s, 0.0722 - 0.0722 * s],
+            [0.2126 - 0.2126 * s, 0.7152 + 0.2848 * s, 0.0722 - 0.0722 * s],
+            [0.2126 - 0.2126 * s, 0.7152 - 0.7152 * s, 0.0722 + 0.9278 * s]])
+    }
+
+    /// The matrix of the CSS `sepia()` filter. The amount is clamped between
+    /// zero and one, where one is fully sepia.
+    pub fn sepia(amount: f64) -> ColorMatrix {
+        let s = 1.0 - clamp(amount, 0.0, 1.0);
+        ColorMatrix::from_3x3([
+            [0.393 + 0.607 * s, 0.769 - 0.769 * s, 0.189 - 0.189 * s],
+            [0.349 - 0.349 * s, 0.686 + 0.314 * s, 0.168 - 0.168 * s],
+            [0.272 - 0.272 * s, 0.534 - 0.534 * s, 0.131 + 0.869 * s]])
+    }
+
+    /// The matrix of the CSS `saturate()` filter, which is the
+    /// `feColorMatrix` saturate matrix with negative amounts clamped to
+    /// zero.
+    pub fn css_saturate(amount: f64) -> ColorMatrix {
+        ColorMatrix::saturate(amount.max(0.0))
+    }
+
+    /// The matrix of the CSS `brightness()` filter, which scales red, green
+    /// and blue by a non-negative amount.
+    pub fn brightness(amount: f64) -> ColorMatrix {
+        let a = amount.max(0.0);
+        ColorMatrix::scale([a, a, a, 1.0], [0.0; 4])
+    }
+
+    /// The matrix of the CSS `contrast()` filter, which scales red, green
+    /// and blue around one half by a non-negative amount.
+    pub fn contrast(amount: f64) -> ColorMatrix {
+        let a = amount.max(0.0);
+        let offset = 0.5 - 0.5 * a;
+        ColorMatrix::scale([a, a, a, 1.0], [offset, offset, offset, 0.0])
+    }
+
+    /// The matrix of the CSS `invert()` filter. The amount is clamped
+    /// between zero and one, where one fully inverts red, green and blue.
+    pub fn invert(amount: f64) -> ColorMatrix {
+        let a = clamp(amount, 0.0, 1.0);
+        let slope = 1.0 - 2.0 * a;
+        ColorMatrix::scale([slope, slope, slope, 1.0], [a, a, a, 0.0])
+    }
+
+    /// The matrix of the CSS `opacity()` filter, which scales alpha by an
+    /// amount clamped between zero and one.
+    pub fn opacity(amount: f64) -> ColorMatrix {
+        ColorMatrix::scale([1.0, 1.0, 1.0, clamp(amount, 0.0, 1.0)], [0.0; 4])
+    }
+
+    /// Returns the rows of the 4x5 affine matrix.
+    #[inline]
+    pub fn rows(&self) -> [[f64; 5]; 4] { self.rows }
+
+    /// Returns a matrix which applies this matrix and then another. The
+    /// result is the product `next * self`. Unlike applying the matrices in
+    /// turn, intermediate values are not clamped.
+    pub fn then(&self, next: &ColorMatrix) -> ColorMatrix {
+        *next * *self
+    }
+
+    /// Transform red, green, blue and alpha values.
+    fn transform(&self, c: [f64; 4]) -> [f64; 4] {
+        let mut out = [0.0; 4];
+        for (o, row) in out.iter_mut().zip(&self.rows) {
+            let value = row[0] * c[0] + row[1] * c[1] + row[2] * c[2] + row[3] * c[3] + row[4];
+            *o = clamp(value, 0.0, 1.0);
+        }
+        out
+    }
+
+    /// Transform a color, treating it as opaque.
+    pub fn apply<T: Channel>(&self, rgb: &Rgb<T>) -> Rgb<T> {
+        let c = self.transform([rgb.r().to_float(), rgb.g().to_float(), rgb.b().to_float(), 1.0]);
+        Rgb::with_components(T::from_float(c[0]), T::from_float(c[1]), T::from_float(c[2]))
+    }
+
+    /// Transform a color with alpha. As with `feColorMatrix`, the color is
+    /// not premultiplied by alpha.
+    pub fn apply_rgba<T: Channel>(&self, rgba: &Rgba<T>) -> Rgba<T> {
+        let c = self.transform([rgba.r().to_float(), rgba.g().to_float(),
+                                rgba.b().to_float(), rgba.a().to_float()]);
+        Rgba::with_components(T::from_float(c[0]), T::from_float(c[1]),
+                              T::from_float(c[2]), T::from_float(c[3]))
+    }
+
+    /// Transform a buffer of colors in place.
+    pub fn apply_in_place<T: Channel>(&self, pixels: &mut [Rgb<T>]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+}
+
+impl Default for ColorMatrix {
+    /// Identical to ```ColorMatrix::identity()```.
+    fn default() -> ColorMatrix {
+        ColorMatrix::identity()
+    }
+}
+
+impl Mul for ColorMatrix {
+    type Output = ColorMatrix;
+
+    /// Multiply two affine matrices. The product applies the right hand
+    /// matrix first.
+    fn mul(self, rhs: ColorMatrix) -> ColorMatrix {
+        let (a, b) = (self.rows, rhs.rows);
+        let mut rows = [[0.0; 5]; 4];
+        for i in 0..4 {
+            for j in 0..5 {
+                let mut sum = (0..4).fold(0.0, |sum, k| sum + a[i][k] * b[k][j]);
+                if j == 4 {
+                    sum += a[i][4];
+                }
+                rows[i][j] = sum;
+            }
+        }
+        ColorMatrix { rows: rows }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, Rgba};
+    use ::test::assert_near;
+
+    #[test]
+    fn identity_leaves_colors_unchanged() {
+        let col = Rgba::with_components(10u8, 20, 30, 40);
+        assert_eq!(ColorMatrix::identity().apply_rgba(&col).components(), (10, 20, 30, 40));
+        assert_eq!(ColorMatrix::default(), ColorMatrix::identity());
+    }
+
+    #[test]
+    fn saturate_zero_gives_grey() {
+        let (r, g, b) = ColorMatrix::saturate(0.0).apply(&Rgb::with_components(0.9f64, 0.2, 0.4)).components();
+        assert!((r - g).abs() < 1e-12 && (g - b).abs() < 1e-12);
+        assert!((r - (0.213 * 0.9 + 0.715 * 0.2 + 0.072 * 0.4)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn full_hue_rotation_is_identity() {
+        let col = Rgb::with_components(0.8f64, 0.3, 0.1);
+        assert_near(ColorMatrix::hue_rotate(0.0).apply(&col), (0.8, 0.3, 0.1), 1e-9);
+        assert_near(ColorMatrix::hue_rotate(360.0).apply(&col), (0.8, 0.3, 0.1), 1e-9);
+        let grey = Rgb::with_components(0.5f64, 0.5, 0.5);
+        assert_near(ColorMatrix::hue_rotate(123.0).apply(&grey), (0.5, 0.5, 0.5), 1e-9);
+    }
+
+    #[test]
+    fn luminance_to_alpha() {
+        let out = ColorMatrix::luminance_to_alpha().apply_rgba(&Rgba::with_components(1.0f64, 1.0, 0.0, 0.3));
+        assert_eq!((out.r(), out.g(), out.b()), (0.0, 0.0, 0.0));
+        assert!((out.a() - (0.2125 + 0.7154)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn css_filter_matrices() {
+        let grey = Rgb::with_components(0.5f64, 0.5, 0.5);
+        assert_near(ColorMatrix::sepia(1.0).apply(&grey), (0.6755, 0.6015, 0.4685), 1e-9);
+        assert_near(ColorMatrix::grayscale(1.0).apply(&Rgb::with_components(1.0f64, 0.0, 0.0)),
+                    (0.2126, 0.2126, 0.2126), 1e-9);
+        assert_near(ColorMatrix::invert(0.25).apply(&Rgb::with_components(1.0f64, 0.0, 0.5)), (0.75, 0.25, 0.5), 1e-9);
+        assert_near(ColorMatrix::contrast(2.0).apply(&Rgb::with_components(0.3f64, 0.5, 0.6)), (0.1, 0.5, 0.7), 1e-9);
+        assert_near(ColorMatrix::brightness(0.5).apply(&Rgb::with_components(0.3f64, 0.5, 0.6)), (0.15, 0.25, 0.3), 1e-9);
+        let faded = ColorMatrix::opacity(0.5).apply_rgba(&Rgba::with_components(0.1f64, 0.2, 0.3, 0.8));
+        assert!((faded.a() - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn results_are_clamped() {
+        let out = ColorMatrix::brightness(3.0).apply(&Rgb::with_components(0.5f32, 0.1, 0.0));
+        assert_eq!(out.components(), (1.0, 0.3, 0.0));
+    }
+
+    #[test]
+    fn swaps_channels() {
+        let swap = ColorMatrix::from_3x3([[0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]]);
+        assert_eq!(swap.apply(&Rgb::with_components(1u8, 2, 3)).components(), (3, 2, 1));
+    }
+
+    #[test]
+    fn composition_matches_sequential_application() {
+        let (a, b) = (ColorMatrix::hue_rotate(40.0), ColorMatrix::contrast(0.8));
+        let col = Rgb::with_components(0.4f64, 0.5, 0.6);
+        let expected = b.apply(&a.apply(&col)).components();
+        assert_near(a.then(&b).apply(&col), expected, 1e-9);
+        assert_near((b * a).apply(&col), expected, 1e-9);
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use super::{Channel, Lab, Oklab, Rgb};
+
+/// A WCAG 2.x conformance level for contrast.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WcagLevel {
+    /// Level AA (success criterion 1.4.3, minimum contrast).
+    AA,
+
+    /// Level AAA (success criterion 1.4.6, enhanced contrast).
+    AAA
+}
+
+/// The size of text, which determines the contrast WCAG requires.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum TextSize {
+    /// Text smaller than large text.
+    Normal,
+
+    /// Text of at least 18 point, or at least 14 point and bold.
+    Large
+}
+
+impl WcagLevel {
+    /// Returns the minimum contrast ratio required at this level for text
+    /// of the given size.
+    pub fn minimum_ratio(self, size: TextSize) -> f64 {
+        match (self, size) {
+            (WcagLevel::AA, TextSize::Normal) => 4.5,
+            (WcagLevel::AA, TextSize::Large) => 3.0,
+            (WcagLevel::AAA, TextSize::Normal) => 7.0,
+            (WcagLevel::AAA, TextSize::Large) => 4.5
+        }
+    }
+}
+
+/// A contrast requirement between a foreground and background color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContrastTarget {
+    /// A minimum WCAG 2.1 contrast ratio, such as 4.5.
+    Wcag(f64),
+
+    /// A minimum magnitude of APCA Lc, such as 60. Either polarity passes.
+    Apca(f64)
+}
+
+impl ContrastTarget {
+    /// Returns true if the foreground color on the background color meets
+    /// this target.
+    pub fn is_met_by<T: Channel>(self, fg: &Rgb<T>, bg: &Rgb<T>) -> bool {
+        match self {
+            ContrastTarget::Wcag(ratio) => contrast_ratio(fg, bg) >= ratio,
+            ContrastTarget::Apca(lc) => apca_contrast(fg, bg).abs() >= lc
+        }
+    }
+}
+
+/// The perceptual space in which `adjust_contrast` changes lightness.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ContrastSpace {
+    /// CIE LCh, the cylindrical form of `Lab`.
+    Lch,
+
+    /// Oklch, the cylindrical form of `Oklab`.
+    Oklch
+}
+
+impl ContrastSpace {
+    /// Returns the lightness, chroma and hue of a color, with lightness
+    /// scaled between 0 and 1.
+    fn to_lch(self, col: &Rgb<f64>) -> (f64, f64, f64) {
+        match self {
+            ContrastSpace::Lch => {
+                let lab = Lab::from_rgb(col);
+                (lab.l() / 100.0, lab.chroma(), lab.hue())
+            }
+            ContrastSpace::Oklch => {
+                let lab = Oklab::from_rgb(col);
+                (lab.l(), lab.chroma(), lab.hue())
+            }
+        }
+    }
+
+    /// Returns the sRGB color for a lightness between 0 and 1, chroma and
+    /// hue, reducing the chroma as needed to fit the sRGB gamut.
+    fn to_rgb(self, l: f64, c: f64, h: f64) -> Rgb<f64> {
+        match self {
+            ContrastSpace::Lch => Lab::from_lch(l * 100.0, c, h).to_rgb_in_gamut(),
+            ContrastSpace::Oklch => Oklab::from_lch(l, c, h).to_rgb_in_gamut()
+        }
+    }
+}
+
+/// Find the foreground color closest to `fg` that meets a contrast target
+/// against `bg`, changing only its lightness in the given space. Hue is
+/// preserved, and chroma is reduced only where the new lightness would
+/// otherwise leave the sRGB gamut. Both lighter and darker colors are
+/// considered, and the one needing the smaller change is chosen.
+///
+/// Returns `fg` unchanged if it already meets the target, or `None` if no
+/// lightness of its hue meets the target.
+pub fn adjust_contrast<T: Channel>(fg: &Rgb<T>, bg: &Rgb<T>, target: ContrastTarget,
+                                   space: ContrastSpace) -> Option<Rgb<T>> {
+    if target.is_met_by(fg, bg) {
+        return Some(*fg);
+    }
+
+    let (l, c, h) = space.to_lch(&fg.convert());
+    let candidate = |l: f64| space.to_rgb(l, c, h).convert::<T>();
+    let search = |limit: f64| {
+        if !target.is_met_by(&candidate(limit), bg) {
+            return None;
+        }
+        let (mut near, mut far) = (l, limit);
+        for _ in 0..40 {
+            let mid = (near + far) / 2.0;
+            if target.is_met_by(&candidate(mid), bg) { far = mid; } else { near = mid; }
+        }
+        Some(far)
+    };
+
+    match (search(0.0), search(1.0)) {
+        (Some(darker), Some(lighter)) => {
+            Some(candidate(if l - darker <= lighter - l { darker } else { lighter }))
+        }
+        (Some(darker), None) => Some(candidate(darker)),
+        (None, Some(lighter)) => Some(candidate(lighter)),
+        (None, None) => None
+    }
+}
+
+/// Calculate the WCAG 2.1 contrast ratio between two colors, from 1 for
+/// identical luminance to 21 for black on white. The ratio is symmetric, so
+/// it does not matter which color is the foreground.
+pub fn contrast_ratio<T: Channel>(fg: &Rgb<T>, bg: &Rgb<T>) -> f64 {
+    let (a, b) = (fg.relative_luminance(), bg.relative_luminance());
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Returns true if text in the foreground color on the background color
+/// meets the given WCAG level.
+pub fn meets_wcag<T: Channel>(fg: &Rgb<T>, bg: &Rgb<T>, level: WcagLevel, size: TextSize) -> bool {
+    contrast_ratio(fg, bg) >= level.minimum_ratio(size)
+}
+
+/// Returns true if the colors meet WCAG level AA for text of the given size.
+pub fn meets_aa<T: Channel>(fg: &Rgb<T>, bg: &Rgb<T>, size: TextSize) -> bool {
+    meets_wcag(fg, bg, WcagLevel::AA, size)
+}
+
+/// Returns true if the colors meet WCAG level AAA for text of the given size.
+pub fn meets_aaa<T: Channel>(fg: &Rgb<T>, bg: &Rgb<T>, size: TextSize) -> bool {
+    meets_wcag(fg, bg, WcagLevel::AAA, size)
+}
+
+/// Calculate the APCA (0.0.98G-4g) lightness contrast Lc of text on a
+/// background. Unlike the WCAG ratio this depends on polarity: dark text on
+/// a light background gives a positive value, up to about 106, and light
+/// text on a dark background a negative value, down to about -108. Values
+/// too small to be meaningful are returned as zero.
+pub fn apca_contrast<T: Channel>(text: &Rgb<T>, background: &Rgb<T>) -> f64 {
+    let text = apca_luminance(text);
+    let background = apca_luminance(background);
+    if (background - text).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let contrast = if background > text {
+        let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 { 0.0 } else { sapc - 0.027 }
+    } else {
+        let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 { 0.0 } else { sapc + 0.027 }
+    };
+    contrast * 100.0
+}
+
+/// The screen luminance estimate used by APCA, with its soft clamp applied
+/// to near-black colors.
+fn apca_luminance<T: Channel>(col: &Rgb<T>) -> f64 {
+    let y = 0.2126729 * col.r().to_float::<f64>().powf(2.4) +
+            0.7151522 * col.g().to_float::<f64>().powf(2.4) +
+            0.0721750 * col.b().to_float::<f64>().powf(2.4);
+    if y < 0.022 { y + (0.022 - y).powf(1.414) } else { y }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    fn grey(x: u8) -> Rgb<u8> {
+        Rgb::with_components(x, x, x)
+    }
+
+    #[test]
+    fn black_on_white_has_maximum_contrast() {
+        assert!((contrast_ratio(&grey(0), &grey(255)) - 21.0).abs() < 1e-12);
+        assert!((contrast_ratio(&grey(255), &grey(0)) - 21.0).abs() < 1e-12);
+        assert_eq!(contrast_ratio(&grey(120), &grey(120)), 1.0);
+    }
+
+    #[test]
+    fn wcag_thresholds() {
+        // #777 on white is a well known near miss for AA, at about 4.48:1.
+        assert!((contrast_ratio(&grey(0x77), &grey(255)) - 4.48).abs() < 0.005);
+        assert!(!meets_aa(&grey(0x77), &grey(255), TextSize::Normal));
+        assert!(meets_aa(&grey(0x77), &grey(255), TextSize::Large));
+        assert!(!meets_aaa(&grey(0x77), &grey(255), TextSize::Large));
+        assert!(meets_aa(&grey(0x76), &grey(255), TextSize::Normal));
+        assert!(meets_wcag(&grey(0), &grey(255), WcagLevel::AAA, TextSize::Normal));
+    }
+
+    #[test]
+    fn apca_matches_reference_values() {
+        let cases = [(0x88, 0xff, 63.056469930209424),
+                     (0xff, 0x88, -68.54146436644962),
+                     (0x00, 0xaa, 58.146262578561334),
+                     (0xaa, 0x00, -56.24113336839742),
+                     (0x00, 0xff, 106.04067321268862),
+                     (0xff, 0x00, -107.88473318309848)];
+        for &(text, background, lc) in cases.iter() {
+            assert!((apca_contrast(&grey(text), &grey(background)) - lc).abs() < 1e-9,
+                    "{:x} on {:x}", text, background);
+        }
+        assert_eq!(apca_contrast(&grey(0x80), &grey(0x81)), 0.0);
+    }
+
+    #[test]
+    fn adjusted_colors_meet_the_target() {
+        let bg = Rgb::with_components(255u8, 255, 255);
+        let fg = Rgb::with_components(120u8, 160, 255);
+        let targets = [ContrastTarget::Wcag(4.5), ContrastTarget::Wcag(7.0), ContrastTarget::Apca(75.0)];
+        for &target in targets.iter() {
+            for &space in [ContrastSpace::Lch, ContrastSpace::Oklch].iter() {
+                let adjusted = adjust_contrast(&fg, &bg, target, space).unwrap();
+                assert!(target.is_met_by(&adjusted, &bg));
+                // The adjustment is minimal: a slightly lighter color fails.
+                let (l, c, h) = space.to_lch(&adjusted.convert());
+                let lighter = space.to_rgb(l + 0.01, c, h).convert::<u8>();
+                assert!(!target.is_met_by(&lighter, &bg));
+            }
+        }
+    }
+
+    #[test]
+    fn adjustment_preserves_hue() {
+        let bg = Rgb::with_components(0.1f64, 0.1, 0.1);
+        let fg = Rgb::with_components(0.3f64, 0.1, 0.1);
+        let adjusted = adjust_contrast(&fg, &bg, ContrastTarget::Wcag(4.5), ContrastSpace::Oklch).unwrap();
+        assert!(adjusted.r() > fg.r());
+        assert!((Oklab::from_rgb(&adjusted).hue() - Oklab::from_rgb(&fg).hue()).abs() < 0.1);
+    }
+
+    #[test]
+    fn passing_colors_are_unchanged_and_impossible_targets_fail() {
+        let (black, grey) = (Rgb::with_components(0u8, 0, 0), Rgb::with_components(128u8, 128, 128));
+        assert_eq!(adjust_contrast(&black, &grey, ContrastTarget::Wcag(4.5), ContrastSpace::Lch), Some(black));
+        assert_eq!(adjust_contrast(&black, &grey, ContrastTarget::Wcag(21.0), ContrastSpace::Lch), None);
+    }
+}
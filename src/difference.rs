@@ -0,0 +1,278 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, wrap_hue, Channel, Lab, Rgb};
+
+/// The application weights used by the CIE94 color difference.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Cie94Application {
+    /// Weights for graphic arts: kL = 1, K1 = 0.045 and K2 = 0.015.
+    GraphicArts,
+
+    /// Weights for textiles: kL = 2, K1 = 0.048 and K2 = 0.014.
+    Textiles
+}
+
+/// Measures of the perceived difference between two colors, calculated
+/// in CIE `Lab`. A difference of around 1 is the smallest difference most
+/// observers can see.
+pub trait ColorDifference<F: Float> {
+    /// The CIE76 color difference ΔE\*ab, the Euclidean distance in Lab.
+    fn delta_e76(&self, other: &Self) -> F;
+
+    /// The CIE94 color difference ΔE\*94, with this color as the reference.
+    fn delta_e94(&self, other: &Self, application: Cie94Application) -> F;
+
+    /// The CIEDE2000 color difference ΔE00.
+    fn delta_e2000(&self, other: &Self) -> F;
+
+    /// The CMC l:c color difference, with this color as the reference. A
+    /// ratio of 2:1 is commonly used for acceptability, and 1:1 for
+    /// perceptibility.
+    fn delta_e_cmc(&self, other: &Self, lightness: F, chroma: F) -> F;
+}
+
+impl<F: Float> ColorDifference<F> for Lab<F> {
+    fn delta_e76(&self, other: &Lab<F>) -> F {
+        let (dl, da, db) = (self.l() - other.l(), self.a() - other.a(), self.b() - other.b());
+        (dl * dl + da * da + db * db).sqrt()
+    }
+
+    fn delta_e94(&self, other: &Lab<F>, application: Cie94Application) -> F {
+        let (k_l, k_1, k_2): (F, F, F) = match application {
+            Cie94Application::GraphicArts => (F::one(), float(0.045), float(0.015)),
+            Cie94Application::Textiles => (float(2.0), float(0.048), float(0.014))
+        };
+        let (c1, c2) = (self.chroma(), other.chroma());
+        let dl = self.l() - other.l();
+        let dc = c1 - c2;
+        let dh_squared = hue_difference_squared(self, other, dc);
+        let s_c = F::one() + k_1 * c1;
+        let s_h = F::one() + k_2 * c1;
+        let (l, c) = (dl / k_l, dc / s_c);
+        (l * l + c * c + dh_squared / (s_h * s_h)).sqrt()
+    }
+
+    fn delta_e2000(&self, other: &Lab<F>) -> F {
+        let (l1, a1, b1) = self.components();
+        let (l2, a2, b2) = other.components();
+        let (one, two): (F, F) = (F::one(), float(2.0));
+        let pow25_7: F = float(6103515625.0);
+
+        let c_mean = (self.chroma() + other.chroma()) / two;
+        let c_mean_7 = c_mean.powi(7);
+        let g = (one - (c_mean_7 / (c_mean_7 + pow25_7)).sqrt()) / two;
+        let (a1, a2) = (a1 * (one + g), a2 * (one + g));
+        let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+        let hue = |a: F, b: F| if a == F::zero() && b == F::zero() {
+            F::zero()
+        } else {
+            wrap_hue(b.atan2(a).to_degrees())
+        };
+        let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+        let dl = l2 - l1;
+        let dc = c2 - c1;
+        let chromatic = c1 * c2 != F::zero();
+        let mut dh = h2 - h1;
+        if !chromatic {
+            dh = F::zero();
+        } else if dh > float(180.0) {
+            dh = dh - float(360.0);
+        } else if dh < float(-180.0) {
+            dh = dh + float(360.0);
+        }
+        let dh = two * (c1 * c2).sqrt() * (dh / two).to_radians().sin();
+
+        let l_mean = (l1 + l2) / two;
+        let c_mean = (c1 + c2) / two;
+        let h_mean = if !chromatic {
+            h1 + h2
+        } else if (h1 - h2).abs() <= float(180.0) {
+            (h1 + h2) / two
+        } else if h1 + h2 < float(360.0) {
+            (h1 + h2 + float(360.0)) / two
+        } else {
+            (h1 + h2 - float(360.0)) / two
+        };
+
+        let cos_deg = |x: F| x.to_radians().cos();
+        let t = one - float::<F>(0.17) * cos_deg(h_mean - float(30.0))
+                    + float::<F>(0.24) * cos_deg(two * h_mean)
+                    + float::<F>(0.32) * cos_deg(float::<F>(3.0) * h_mean + float(6.0))
+                    - float::<F>(0.20) * cos_deg(float::<F>(4.0) * h_mean - float(63.0));
+        let rotation = ((h_mean - float(275.0)) / float(25.0)).powi(2);
+        let d_theta = float::<F>(30.0) * (-rotation).exp();
+        let c_mean_7 = c_mean.powi(7);
+        let r_c = two * (c_mean_7 / (c_mean_7 + pow25_7)).sqrt();
+        let l_offset = (l_mean - float(50.0)).powi(2);
+        let s_l = one + float::<F>(0.015) * l_offset / (float::<F>(20.0) + l_offset).sqrt();
+        let s_c = one + float::<F>(0.045) * c_mean;
+        let s_h = one + float::<F>(0.015) * c_mean * t;
+        let r_t = -(two * d_theta).to_radians().sin() * r_c;
+
+        let (l, c, h) = (dl / s_l, dc / s_c, dh / s_h);
+        (l * l + c * c + h * h + r_t * c * h).sqrt()
+    }
+
+    fn delta_e_cmc(&self, other: &Lab<F>, lightness: F, chroma: F) -> F {
+        let (l1, c1, h1) = (self.l(), self.chroma(), self.hue());
+        let dl = l1 - other.l();
+        let dc = c1 - other.chroma();
+        let dh_squared = hue_difference_squared(self, other, dc);
+
+        let s_l = if l1 < float(16.0) {
+            float(0.511)
+        } else {
+            float::<F>(0.040975) * l1 / (F::one() + float::<F>(0.01765) * l1)
+        };
+        let s_c = float::<F>(0.0638) * c1 / (F::one() + float::<F>(0.0131) * c1) + float(0.638);
+        let c1_4 = c1.powi(4);
+        let f = (c1_4 / (c1_4 + float(1900.0))).sqrt();
+        let t = if h1 >= float(164.0) && h1 <= float(345.0) {
+            float::<F>(0.56) + (float::<F>(0.2) * (h1 + float(168.0)).to_radians().cos()).abs()
+        } else {
+            float::<F>(0.36) + (float::<F>(0.4) * (h1 + float(35.0)).to_radians().cos()).abs()
+        };
+        let s_h = s_c * (f * t + F::one() - f);
+
+        let (l, c) = (dl / (lightness * s_l), dc / (chroma * s_c));
+        (l * l + c * c + dh_squared / (s_h * s_h)).sqrt()
+    }
+}
+
+impl<F: Channel + Float> ColorDifference<F> for Rgb<F> {
+    fn delta_e76(&self, other: &Rgb<F>) -> F {
+        Lab::from_rgb(self).delta_e76(&Lab::from_rgb(other))
+    }
+
+    fn delta_e94(&self, other: &Rgb<F>, application: Cie94Application) -> F {
+        Lab::from_rgb(self).delta_e94(&Lab::from_rgb(other), application)
+    }
+
+    fn delta_e2000(&self, other: &Rgb<F>) -> F {
+        Lab::from_rgb(self).delta_e2000(&Lab::from_rgb(other))
+    }
+
+    fn delta_e_cmc(&self, other: &Rgb<F>, lightness: F, chroma: F) -> F {
+        Lab::from_rgb(self).delta_e_cmc(&Lab::from_rgb(other), lightness, chroma)
+    }
+}
+
+/// The squared hue difference ΔH², given the chroma difference ΔC. Rounding
+/// can make this slightly negative, so it is clamped at zero.
+fn hue_difference_squared<F: Float>(a: &Lab<F>, b: &Lab<F>, dc: F) -> F {
+    let (da, db) = (a.a() - b.a(), a.b() - b.b());
+    (da * da + db * db - dc * dc).max(F::zero())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Lab, Rgb};
+
+    /// Test data from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference
+    /// Formula: Implementation Notes, Supplementary Test Data, and
+    /// Mathematical Observations" (2005).
+    const SHARMA_PAIRS: [[f64; 7]; 34] = [
+        [50.0000, 2.6772, -79.7751, 50.0000, 0.0000, -82.7485, 2.0425],
+        [50.0000, 3.1571, -77.2803, 50.0000, 0.0000, -82.7485, 2.8615],
+        [50.0000, 2.8361, -74.0200, 50.0000, 0.0000, -82.7485, 3.4412],
+        [50.0000, -1.3802, -84.2814, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -1.1848, -84.8006, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -0.9009, -85.5211, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, 0.0000, 0.0000, 50.0000, -1.0000, 2.0000, 2.3669],
+        [50.0000, -1.0000, 2.0000, 50.0000, 0.0000, 0.0000, 2.3669],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0009, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0010, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0011, 7.2195],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0012, 7.2195],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0009, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0010, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0011, -2.4900, 4.7461],
+        [50.0000, 2.5000, 0.0000, 50.0000, 0.0000, -2.5000, 4.3065],
+        [50.0000, 2.5000, 0.0000, 73.0000, 25.0000, -18.0000, 27.1492],
+        [50.0000, 2.5000, 0.0000, 61.0000, -5.0000, 29.0000, 22.8977],
+        [50.0000, 2.5000, 0.0000, 56.0000, -27.0000, -3.0000, 31.9030],
+        [50.0000, 2.5000, 0.0000, 58.0000, 24.0000, 15.0000, 19.4535],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.1736, 0.5854, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2972, 0.0000, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 1.8634, 0.5757, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2592, 0.3350, 1.0000],
+        [60.2574, -34.0099, 36.2677, 60.4626, -34.1751, 39.4387, 1.2644],
+        [63.0109, -31.0961, -5.8663, 62.8187, -29.7946, -4.0864, 1.2630],
+        [61.2901, 3.7196, -5.3901, 61.4292, 2.2480, -4.9620, 1.8731],
+        [35.0831, -44.1164, 3.7933, 35.0232, -40.0716, 1.5901, 1.8645],
+        [22.7233, 20.0904, -46.6940, 23.0331, 14.9730, -42.5619, 2.0373],
+        [36.4612, 47.8580, 18.3852, 36.2715, 50.5065, 21.2231, 1.4146],
+        [90.8027, -2.0831, 1.4410, 91.1528, -1.6435, 0.0447, 1.4441],
+        [90.9257, -0.5406, -0.9208, 88.6381, -0.8985, -0.7239, 1.5381],
+        [6.7747, -0.2908, -2.4247, 5.8714, -0.0985, -2.2286, 0.6377],
+        [2.0776, 0.0795, -1.1350, 0.9033, -0.0636, -0.5514, 0.9082]
+    ];
+
+    #[test]
+    fn delta_e2000_matches_sharma_test_data() {
+        for pair in SHARMA_PAIRS.iter() {
+            let a = Lab::with_components(pair[0], pair[1], pair[2]);
+            let b = Lab::with_components(pair[3], pair[4], pair[5]);
+            assert!((a.delta_e2000(&b) - pair[6]).abs() < 1e-4, "{:?}", pair);
+            assert!((b.delta_e2000(&a) - pair[6]).abs() < 1e-4, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn delta_e76_is_euclidean_distance() {
+        let a = Lab::with_components(50.0f64, 10.0, -10.0);
+        let b = Lab::with_components(53.0f64, 14.0, -10.0);
+        assert_eq!(a.delta_e76(&b), 5.0);
+    }
+
+    #[test]
+    fn delta_e94_weights_lightness_by_application() {
+        let a = Lab::with_components(50.0f64, 20.0, 0.0);
+        let b = Lab::with_components(54.0f64, 20.0, 0.0);
+        assert!((a.delta_e94(&b, Cie94Application::GraphicArts) - 4.0).abs() < 1e-12);
+        assert!((a.delta_e94(&b, Cie94Application::Textiles) - 2.0).abs() < 1e-12);
+
+        let c = Lab::with_components(50.0f64, 30.0, 0.0);
+        assert!((a.delta_e94(&c, Cie94Application::GraphicArts) - 10.0 / 1.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn delta_e_cmc_weights_lightness() {
+        let a = Lab::with_components(50.0f64, 20.0, 30.0);
+        let b = Lab::with_components(52.0f64, 20.0, 30.0);
+        let perceptible = a.delta_e_cmc(&b, 1.0, 1.0);
+        let acceptable = a.delta_e_cmc(&b, 2.0, 1.0);
+        assert!((perceptible - 2.0 * acceptable).abs() < 1e-12);
+        assert!((perceptible - 2.0 / (0.040975 * 50.0 / (1.0 + 0.01765 * 50.0))).abs() < 1e-12);
+    }
+
+    #[test]
+    fn identical_colors_have_no_difference() {
+        let col = Rgb::with_components(0.3f64, 0.6, 0.2);
+        assert_eq!(col.delta_e76(&col), 0.0);
+        assert_eq!(col.delta_e94(&col, Cie94Application::Textiles), 0.0);
+        assert_eq!(col.delta_e2000(&col), 0.0);
+        assert_eq!(col.delta_e_cmc(&col, 2.0, 1.0), 0.0);
+    }
+}
@@ -0,0 +1,774 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use super::{clamp, linear_to_srgb, srgb_to_linear, Channel, DistanceMetric, Palette, QuantizeColor, Rgb};
+#[cfg(test)]
+use super::XorShift;
+use palette::NearestSearch;
+
+/// An error diffusion kernel, as `(dx, dy, weight)` offsets, and the
+/// divisor of the weights.
+type Kernel = (&'static [(isize, usize, f64)], f64);
+
+const FLOYD_STEINBERG: Kernel = (&[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0);
+
+const JARVIS_JUDICE_NINKE: Kernel = (&[(1, 0, 7.0), (2, 0, 5.0),
+                                       (-2, 1, 3.0), (-1, 1, 5.0), (0, 1, 7.0), (1, 1, 5.0), (2, 1, 3.0),
+                                       (-2, 2, 1.0), (-1, 2, 3.0), (0, 2, 5.0), (1, 2, 3.0), (2, 2, 1.0)], 48.0);
+
+const STUCKI: Kernel = (&[(1, 0, 8.0), (2, 0, 4.0),
+                          (-2, 1, 2.0), (-1, 1, 4.0), (0, 1, 8.0), (1, 1, 4.0), (2, 1, 2.0),
+                          (-2, 2, 1.0), (-1, 2, 2.0), (0, 2, 4.0), (1, 2, 2.0), (2, 2, 1.0)], 42.0);
+
+// Atkinson only diffuses three quarters of the error.
+const ATKINSON: Kernel = (&[(1, 0, 1.0), (2, 0, 1.0), (-1, 1, 1.0), (0, 1, 1.0), (1, 1, 1.0), (0, 2, 1.0)], 8.0);
+
+const SIERRA: Kernel = (&[(1, 0, 5.0), (2, 0, 3.0),
+                          (-2, 1, 2.0), (-1, 1, 4.0), (0, 1, 5.0), (1, 1, 4.0), (2, 1, 2.0),
+                          (-1, 2, 2.0), (0, 2, 3.0), (1, 2, 2.0)], 32.0);
+
+const TWO_ROW_SIERRA: Kernel = (&[(1, 0, 4.0), (2, 0, 3.0),
+                                  (-2, 1, 1.0), (-1, 1, 2.0), (0, 1, 3.0), (1, 1, 2.0), (2, 1, 1.0)], 16.0);
+
+const SIERRA_LITE: Kernel = (&[(1, 0, 2.0), (-1, 1, 1.0), (0, 1, 1.0)], 4.0);
+
+/// The width and height of the blue noise threshold matrix.
+const BLUE_NOISE_SIZE: usize = 64;
+
+/// A dithering algorithm.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Dither {
+    /// No dithering: each pixel is replaced by its nearest color.
+    None,
+
+    /// Floyd-Steinberg error diffusion.
+    FloydSteinberg,
+
+    /// Jarvis, Judice and Ninke error diffusion.
+    JarvisJudiceNinke,
+
+    /// Stucki error diffusion.
+    Stucki,
+
+    /// Atkinson error diffusion, which only diffuses part of the error and
+    /// so preserves contrast at the cost of detail in shadows and highlights.
+    Atkinson,
+
+    /// Three-row Sierra error diffusion.
+    Sierra,
+
+    /// Two-row Sierra error diffusion.
+    TwoRowSierra,
+
+    /// Sierra Lite error diffusion.
+    SierraLite,
+
+    /// Ordered dithering with a Bayer matrix of the given size, which must
+    /// be a power of two (2, 4, 8, 16...).
+    Bayer(usize),
+
+    /// Ordered dithering with a blue noise threshold matrix, generated by
+    /// Ulichney's void-and-cluster method.
+    BlueNoise
+}
+
+impl Dither {
+    fn kernel(self) -> Option<Kernel> {
+        match self {
+            Dither::FloydSteinberg => Some(FLOYD_STEINBERG),
+            Dither::JarvisJudiceNinke => Some(JARVIS_JUDICE_NINKE),
+            Dither::Stucki => Some(STUCKI),
+            Dither::Atkinson => Some(ATKINSON),
+            Dither::Sierra => Some(SIERRA),
+            Dither::TwoRowSierra => Some(TWO_ROW_SIERRA),
+            Dither::SierraLite => Some(SIERRA_LITE),
+            _ => None
+        }
+    }
+
+    /// Returns the threshold matrix and its size, for ordered dithering.
+    fn threshold_matrix(self) -> Option<(Vec<f64>, usize)> {
+        match self {
+            Dither::Bayer(size) => Some((bayer_matrix(size), size)),
+            Dither::BlueNoise => {
+                let ranks: Vec<usize> = BLUE_NOISE_RANKS.iter().map(|&r| r as usize).collect();
+                Some((ranks_to_thresholds(&ranks), BLUE_NOISE_SIZE))
+            }
+            _ => None
+        }
+    }
+}
+
+/// The number of bits kept for each channel when dithering to a lower bit
+/// depth. For example, RGB565 keeps 5 bits of red, 6 bits of green and 5
+/// bits of blue.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct BitDepth {
+    /// Bits of red
+    red: u32,
+
+    /// Bits of green
+    green: u32,
+
+    /// Bits of blue
+    blue: u32
+}
+
+impl BitDepth {
+    /// Create a bit depth from the number of bits for each channel. Panics
+    /// unless each channel has between 1 and 32 bits.
+    pub fn new(red: u32, green: u32, blue: u32) -> BitDepth {
+        assert!(red >= 1 && green >= 1 && blue >= 1);
+        assert!(red <= 32 && green <= 32 && blue <= 32);
+        BitDepth {
+            red: red,
+            green: green,
+            blue: blue
+        }
+    }
+
+    /// Create a bit depth with the same number of bits for every channel.
+    pub fn uniform(bits: u32) -> BitDepth {
+        BitDepth::new(bits, bits, bits)
+    }
+
+    /// Returns the bits of each channel in a 3-element tuple.
+    pub fn components(&self) -> (u32, u32, u32) {
+        (self.red, self.green, self.blue)
+    }
+
+    /// Returns the largest level of each channel.
+    fn max_levels(&self) -> [f64; 3] {
+        let max = |bits: u32| ((1u64 << bits) - 1) as f64;
+        [max(self.red), max(self.green), max(self.blue)]
+    }
+}
+
+/// Dithers images to a lower bit depth or to a palette. Images are slices
+/// of pixels in row-major order.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Ditherer {
+    /// The algorithm used
+    method: Dither,
+
+    /// Whether error diffusion alternates direction on each row
+    serpentine: bool,
+
+    /// Whether errors are measured in linear light
+    linear_light: bool
+}
+
+impl Ditherer {
+    /// Create a ditherer using an algorithm, with serpentine scanning and
+    /// linear light dithering turned off.
+    pub fn new(method: Dither) -> Ditherer {
+        Ditherer {
+            method: method,
+            serpentine: false,
+            linear_light: false
+        }
+    }
+
+    /// Returns the dithering algorithm.
+    #[inline]
+    pub fn method(&self) -> Dither { self.method }
+
+    /// Returns true if error diffusion alternates direction on each row.
+    #[inline]
+    pub fn serpentine(&self) -> bool { self.serpentine }
+
+    /// Set whether error diffusion alternates direction on each row, which
+    /// avoids the diagonal artifacts of scanning in a single direction.
+    #[inline]
+    pub fn set_serpentine(&mut self, serpentine: bool) { self.serpentine = serpentine; }
+
+    /// Returns true if dithering is performed in linear light.
+    #[inline]
+    pub fn linear_light(&self) -> bool { self.linear_light }
+
+    /// Set whether dithering is performed in linear light rather than on
+    /// the sRGB encoded values, which preserves the average brightness of
+    /// dithered areas.
+    #[inline]
+    pub fn set_linear_light(&mut self, linear_light: bool) { self.linear_light = linear_light; }
+
+    /// Dither an image to a lower bit depth. Each output channel holds the
+    /// nearest representable level, scaled to the output channel type. Panics
+    /// if the number of pixels is not a multiple of `width`.
+    pub fn dither_to_depth<T, U>(&self, pixels: &[Rgb<T>], width: usize, depth: BitDepth) -> Vec<Rgb<U>>
+        where T: Channel,
+              U: Channel {
+        let max = depth.max_levels();
+        let spread = [1.0 / max[0], 1.0 / max[1], 1.0 / max[2]];
+        self.dither(pixels, width, spread, |c| {
+            let level = |i: usize| (c[i] * max[i]).round() / max[i];
+            let quantized = [level(0), level(1), level(2)];
+            (quantized, Rgb::from_slice(quantized).convert())
+        })
+    }
+
+    /// Dither an image to a palette, returning the palette index of each
+    /// pixel. Panics if the palette is empty, or if the number of pixels is
+    /// not a multiple of `width`.
+    pub fn dither_to_palette<T, C>(&self, pixels: &[Rgb<T>], width: usize,
+                                   palette: &Palette<C>, metric: DistanceMetric) -> Vec<usize>
+        where T: Channel,
+              C: QuantizeColor {
+        assert!(!palette.is_empty());
+        let search = NearestSearch::new(palette, metric);
+        // Ordered dithering needs an idea of the distance between palette
+        // colors; assume they are spread evenly through the color cube.
+        let levels = (palette.len() as f64).cbrt().max(2.0) - 1.0;
+        let spread = [1.0 / levels; 3];
+        self.dither(pixels, width, spread, |c| {
+            let index = search.nearest([c[0], c[1], c[2], 1.0]);
+            let chosen = palette[index].to_rgba_components();
+            ([chosen[0], chosen[1], chosen[2]], index)
+        })
+    }
+
+    /// Dither an image, where `quantize` maps an sRGB color to the nearest
+    /// available sRGB color and the output for that color, and `spread` is
+    /// the approximate distance between available colors in each channel.
+    fn dither<T, O, Q>(&self, pixels: &[Rgb<T>], width: usize, spread: [f64; 3], mut quantize: Q) -> Vec<O>
+        where T: Channel,
+              Q: FnMut([f64; 3]) -> ([f64; 3], O) {
+        assert!(width > 0 && pixels.len() % width == 0);
+        let linear = self.linear_light;
+        let decode = |c: [f64; 3]| if linear { map3(c, srgb_to_linear) } else { c };
+        let encode = |c: [f64; 3]| {
+            let c = map3(c, |x| clamp(x, 0.0, 1.0));
+            if linear { map3(c, linear_to_srgb) } else { c }
+        };
+        let mut work: Vec<[f64; 3]> = pixels.iter()
+                                            .map(|p| decode(p.convert::<f64>().to_slice()))
+                                            .collect();
+        let height = pixels.len() / width;
+
+        if let Some((matrix, size)) = self.method.threshold_matrix() {
+            let mut output = Vec::with_capacity(pixels.len());
+            for (i, value) in work.iter().enumerate() {
+                let threshold = matrix[(i / width % size) * size + i % width % size];
+                let srgb = encode(*value);
+                let mut offset = [0.0; 3];
+                for c in 0..3 {
+                    // In linear light, scale the offset by the local slope of the transfer curve.
+                    let half = spread[c] / 2.0;
+                    let step = if linear {
+                        srgb_to_linear(clamp(srgb[c] + half, 0.0, 1.0)) -
+                        srgb_to_linear(clamp(srgb[c] - half, 0.0, 1.0))
+                    } else {
+                        spread[c]
+                    };
+                    offset[c] = value[c] + threshold * step;
+                }
+                output.push(quantize(encode(offset)).1);
+            }
+            return output;
+        }
+
+        let (weights, divisor) = self.method.kernel().unwrap_or((&[], 1.0));
+        let mut output: Vec<Option<O>> = (0..pixels.len()).map(|_| None).collect();
+        for y in 0..height {
+            let reverse = self.serpentine && y % 2 == 1;
+            for step in 0..width {
+                let x = if reverse { width - 1 - step } else { step };
+                let i = y * width + x;
+                let value = work[i];
+                let (chosen, out) = quantize(encode(value));
+                output[i] = Some(out);
+
+                let chosen = decode(chosen);
+                let error = [value[0] - chosen[0], value[1] - chosen[1], value[2] - chosen[2]];
+                for &(dx, dy, weight) in weights {
+                    let nx = x as isize + if reverse { -dx } else { dx };
+                    let ny = y + dy;
+                    if nx < 0 || nx >= width as isize || ny >= height {
+                        continue;
+                    }
+                    let target = &mut work[ny * width + nx as usize];
+                    for c in 0..3 {
+                        target[c] += error[c] * weight / divisor;
+                    }
+                }
+            }
+        }
+        output.into_iter().map(|o| o.unwrap()).collect()
+    }
+}
+
+#[inline]
+fn map3<F: Fn(f64) -> f64>(c: [f64; 3], f: F) -> [f64; 3] {
+    [f(c[0]), f(c[1]), f(c[2])]
+}
+
+/// Build a Bayer matrix of thresholds between -0.5 and 0.5.
+fn bayer_matrix(size: usize) -> Vec<f64> {
+    assert!(size >= 2 && size.is_power_of_two());
+    let mut matrix = vec![0usize];
+    let mut n = 1;
+    while n < size {
+        let mut next = vec![0; n * n * 4];
+        for y in 0..n {
+            for x in 0..n {
+                let v = matrix[y * n + x] * 4;
+                next[y * 2 * n + x] = v;
+                next[y * 2 * n + x + n] = v + 2;
+                next[(y + n) * 2 * n + x] = v + 3;
+                next[(y + n) * 2 * n + x + n] = v + 1;
+            }
+        }
+        matrix = next;
+        n *= 2;
+    }
+    ranks_to_thresholds(&matrix)
+}
+
+/// Rank the cells of a blue noise matrix from 0 to `size * size - 1`, using
+/// the void-and-cluster method on a torus. This is too slow to run for every
+/// image, so `BLUE_NOISE_RANKS` holds its output for `BLUE_NOISE_SIZE`.
+#[cfg(test)]
+fn blue_noise_ranks(size: usize) -> Vec<usize> {
+    let count = size * size;
+    let sigma = 1.5;
+    // Gaussian energy contributed by a point at each toroidal offset.
+    let mut kernel = vec![0.0; count];
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x.min(size - x) as f64;
+            let dy = y.min(size - y) as f64;
+            kernel[y * size + x] = (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp();
+        }
+    }
+
+    let mut pattern = vec![false; count];
+    let mut energy = vec![0.0; count];
+    let toggle = |pattern: &mut Vec<bool>, energy: &mut Vec<f64>, p: usize| {
+        pattern[p] = !pattern[p];
+        let sign = if pattern[p] { 1.0 } else { -1.0 };
+        let (px, py) = (p % size, p / size);
+        for y in 0..size {
+            for x in 0..size {
+                let offset = ((y + size - py) % size) * size + (x + size - px) % size;
+                energy[y * size + x] += sign * kernel[offset];
+            }
+        }
+    };
+    let extreme = |pattern: &Vec<bool>, energy: &Vec<f64>, set: bool, largest: bool| {
+        let mut best: Option<usize> = None;
+        for p in 0..count {
+            if pattern[p] != set {
+                continue;
+            }
+            best = match best {
+                Some(b) if (largest && energy[p] <= energy[b]) || (!largest && energy[p] >= energy[b]) => Some(b),
+                _ => Some(p)
+            };
+        }
+        best.unwrap()
+    };
+
+    // Start from a random pattern and relax it by moving points from the
+    // tightest cluster into the largest void.
+    let mut rng = XorShift::new(0);
+    let initial = count / 10;
+    let mut placed = 0;
+    while placed < initial {
+        let p = rng.below(count);
+        if !pattern[p] {
+            toggle(&mut pattern, &mut energy, p);
+            placed += 1;
+        }
+    }
+    loop {
+        let cluster = extreme(&pattern, &energy, true, true);
+        toggle(&mut pattern, &mut energy, cluster);
+        let void = extreme(&pattern, &energy, false, false);
+        toggle(&mut pattern, &mut energy, void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; count];
+    let (prototype, prototype_energy) = (pattern.clone(), energy.clone());
+    // Rank the initial points by repeatedly removing the tightest cluster.
+    for rank in (0..initial).rev() {
+        let cluster = extreme(&pattern, &energy, true, true);
+        toggle(&mut pattern, &mut energy, cluster);
+        ranks[cluster] = rank;
+    }
+    // Rank the remaining points by repeatedly filling the largest void.
+    pattern = prototype;
+    energy = prototype_energy;
+    for rank in initial..count {
+        let void = extreme(&pattern, &energy, false, false);
+        toggle(&mut pattern, &mut energy, void);
+        ranks[void] = rank;
+    }
+    ranks
+}
+
+/// Convert a matrix of ranks from 0 to n - 1 to thresholds between -0.5 and 0.5.
+fn ranks_to_thresholds(ranks: &[usize]) -> Vec<f64> {
+    let count = ranks.len() as f64;
+    ranks.iter().map(|&r| (r as f64 + 0.5) / count - 0.5).collect()
+}
+
+/// The rank of each cell of the blue noise threshold matrix, in row-major
+/// order, as generated by `blue_noise_ranks`.
+static BLUE_NOISE_RANKS: [u16; BLUE_NOISE_SIZE * BLUE_NOISE_SIZE] = [
+     716, 4065,  308, 2932, 1645,  734, 1883, 1147, 2080,  239, 2960, 1256,  166, 3126, 1039, 1672,
+     223, 1506, 2797, 2240, 1724, 1283, 2636, 1521, 2003, 3575,  450, 3753, 2611,  145, 1443,  601,
+    1232,  219, 3890,  464, 3179, 2061, 3830, 1536, 3028,  306, 1655, 2830, 3802, 2060,  374, 4048,
+    1119,  244, 2771, 1241, 2095, 2475,  442, 3925, 2763, 2018, 2583, 1514, 3952,  156, 2200, 3317,
+    2394, 1759, 1243, 3672, 2117, 3922, 3208,  362, 3484, 2479, 1717, 3953, 3391, 2033,  544, 3510,
+    2504, 3121,  530, 1036, 3546,  647, 3261,  863,  256, 3329,  988, 1586,  750, 2004, 3554, 2900,
+    2283, 3264, 2515, 1708, 3568, 1196, 2785,  796, 2020, 2612, 3520,  941,  562, 3038, 1629,  788,
+    1921, 2268, 3214, 3712,   36, 3533, 1051, 2280,  717,  390, 3462,  774, 2402, 2878, 3776,  452,
+    3515, 3124, 2657,  887,  199, 1362, 2333, 2838,  875, 3714,  634, 2304,  869, 1450, 2948, 2216,
+     838, 1805, 3835, 2416, 2959,  343, 2120, 4011, 2448, 1806, 2858, 2198, 3185, 3816,  416, 1718,
+    3995,  942, 1350, 2916,  706, 2375,  115, 3314, 3675,  488, 1296, 1933, 2454, 1364, 3638, 2653,
+    3438,  556, 1499,  936, 2632, 1822, 3087, 1589, 3656, 1266, 3034, 1846,  324, 1308, 1675, 1042,
+      56, 1447,  581, 2231, 2884, 3531,  535, 1748, 1233, 3018, 1554,  345, 2799, 3597,  111, 3999,
+    1262, 3360,   66, 1397, 1855, 3735, 1556, 2914, 1172,  614, 3905,   45, 1273, 2438, 1040, 2654,
+      76, 2036, 3720,  369, 1920, 4059, 1465, 1819, 1064, 2325, 3102, 3997,   42, 3226, 2126,  274,
+    1247, 2938, 3872, 2037,  469, 1351, 3431,  194, 2842, 2179, 4002, 1020, 3256, 3589, 2675, 2107,
+    3685, 1895, 3333, 3966, 1612, 1037, 2548, 4020, 2090,  171, 3310, 3849, 1834, 1074, 2557, 1946,
+     448, 2728, 2150, 3245,  790, 2520, 1006,  158, 3236, 1467, 2542, 3485, 1891,  583, 3080, 1535,
+    3392,  649, 3113, 2647, 1130, 3163,  523, 2929, 3885,  215, 1560,  780, 2630, 1101,  636, 3939,
+    1818, 2385,  146, 3315, 2781, 4090,  624, 2529,  915, 1682,   44, 2596, 2008,  503,  809, 2975,
+     404, 2521,  995,  269, 3068, 1999,   24, 3347,  735, 2716, 2398, 1300, 2190,  659, 3270, 1488,
+    3763,  707, 1107, 4073,  288, 2811, 3511, 2258, 3796, 1987,  426,  912, 2885, 4080, 2148, 3620,
+     885, 2367, 1442,  195, 2191, 3559, 2499,  891, 2000, 2723, 3440, 2175, 3725, 1753, 3335, 2818,
+     867, 3561, 1146, 1671,  854, 2206, 1882, 1235, 3839, 2978,  671, 3760, 1516, 2370, 4028, 1214,
+    2201, 3832, 1386, 2699,  691, 3779, 1317, 2955, 1648, 3613,  963,  491, 3070, 3695,  278, 2846,
+    2307, 3516, 1716, 3017, 1316, 1963,  508, 1702,  766, 2658, 3127, 1347, 1687,  187, 1187,  356,
+    2783, 1762, 3961, 3373,  803, 1583,   23, 1353, 3292,  620, 1192,  387, 2984, 1387,  157, 2302,
+    1505,  425, 2575, 3000, 3734,  291, 3096, 3386,  430, 1941, 3469, 1096, 3122,  162, 3320, 1643,
+    2895,  653, 3252, 1758, 2266, 3407,  429, 2339, 1103,  258, 1985, 4066, 1532, 2491, 1782,  858,
+    1282,  149, 2587,  563, 2347, 3909, 2956, 1234, 3318,  122, 3936, 2213, 3601, 2518, 3285, 1934,
+    3772, 1053,  420, 2883, 1842, 3908, 2831, 3680, 2378, 1694, 3573, 1937, 2474,  910, 3825, 1912,
+    3123, 3984, 2055,  656, 1456, 2419, 1004, 1593, 2343, 2715, 1428, 2243,  572, 1901, 2696,  918,
+    3552, 1967,  124, 3683, 1048, 1529, 2736, 1854, 3884, 3271, 2574, 2902,   41, 1133, 3471, 3962,
+    2990, 1996, 3332, 3643, 1574,   30,  933, 3619, 2397, 1622, 1108,  607, 3027,  822, 1454, 2407,
+     619, 3007, 2145, 1238, 2532,  610, 2071,  328,  931, 3075,  198, 4079,  569, 3422, 2720,  363,
+     760, 1301, 3448,   19, 3303, 2751, 3964,  182, 3670,  856,  248, 2913, 3629, 1298, 3898,  301,
+    2410, 1181, 3024, 2551,  334, 3992,  835, 3060,  623, 1356, 1688,  789, 3368, 2076,  558, 2257,
+     399, 1498,  754, 1073, 2097, 3193, 2640, 1939,  417, 2925, 3489, 1972,  265, 2744, 3943,   72,
+    3336, 1625, 3577,  216, 3275, 1025, 3450, 1527, 3855, 2013, 1323, 2794, 1610, 2104, 1236, 3679,
+    2426, 2897, 1743, 2284, 1203, 1860,  703, 2114, 1284, 3183, 4055, 1709, 2490,  763, 2165, 1472,
+     440, 4095, 1594,  637, 1940, 3238, 2174,   91, 2523, 3632,  433, 2274, 3746, 1466, 2780, 1010,
+    3293, 2488, 3892, 2753,  338, 3813,  650, 1349, 4004,  850, 2308, 1325, 3677, 1745,  970, 2096,
+    1289, 2671,  815, 4064, 1459, 2279, 2684,  538, 2953, 2421,  736, 3260,  999,   50, 3174, 1792,
+    1046,  261, 3595,  821, 3840,  407, 3486, 3057, 2502, 1828,  493, 1007, 3298,   16, 2985, 3385,
+    2703,  893, 2209, 3479, 2809, 1095, 1474, 3795,  998, 2057, 3188, 1166, 2635,  235, 3116, 1868,
+    3658,   79, 1754, 3079, 1421, 2446, 1785, 3349, 2135,  240, 2685, 3140,  531, 2359, 3408, 2887,
+    3851,  333, 2464, 1789, 2979,   87, 3701, 1845, 1058,  132, 3593, 2177, 3843, 2676, 2305,  509,
+    4036, 2598, 1545, 3137, 2467, 2832, 1616,  955,   75, 3565, 2181, 2819, 1538, 3754, 1098, 1768,
+    3790, 3196,   84, 1330, 3899,  284, 2428, 3429, 1725, 2952,  173, 3893, 1825,  881, 3983, 1359,
+     705, 2149, 1193,  537, 3567,  983,  130, 2976, 1121, 3782, 1714, 1027, 4056, 1403,  292,  681,
+    1906, 1104, 3159,  551, 2105,  861, 1287, 3195, 3958, 2571, 1393,  371, 1691,  668, 3553, 1435,
+    3296, 1956,  560, 1143,  141, 2017, 1309, 3780, 2949,  663, 1254, 3894,  385, 2425, 2058,  661,
+    1396, 2482, 1839, 2629,  810, 1880, 3105,  473,  758, 2607, 1546,  615, 3341, 2353,  458, 2525,
+    2853, 3228, 4062, 2323, 1885, 2721, 3741, 1561, 2526,  605, 3464,   51, 2042, 2593, 3182, 1650,
+    3468, 2297, 3630, 1389, 3828, 3379, 2368,  313, 1572, 1947, 3363, 2852, 1194, 3036, 1990,  950,
+     100, 2971, 3671, 2215, 3973, 3426,  575, 2299, 1681, 2581, 3231, 1870,  827, 2761, 3556,  218,
+    2147, 1003, 3689,  565, 2944, 3639, 1371, 2108, 3955, 1221, 3572, 1995, 2880, 1258, 1699, 3543,
+     188, 1543,  901,  280, 3439,  709, 2199,  383, 3299, 1894, 2829, 1457, 3029,  878, 3719, 1202,
+    2772,    2,  928, 2660,  277, 1730, 2762,  710, 3073,  889,  504, 4043, 2437,  299, 3901, 2724,
+    2346, 1228,  757, 2733, 1710,  908, 3206,  230, 4008,  984,  325, 2275, 3380, 1188, 1620, 3128,
+    3920,  397, 3249, 1599, 2233,  989,    8, 2814, 3268,  303, 2413,  987,   54, 3737, 3181, 1065,
+    2025, 3764, 2459, 2908, 1497, 3190, 1078, 4026, 1345,  853, 2312, 3625,  561, 2259,  237, 1945,
+     595, 4025, 2169, 1591, 3093, 1032, 3915, 2052, 3634, 2666, 2164, 1022, 1796, 3421,  799, 1566,
+    3767, 1808, 3377,  245, 1343, 2937, 2543, 1893, 1376, 2808, 3661, 1478,  143, 4030,  542, 2841,
+    1821, 1299, 2738,  226, 4038, 3312, 2547, 1571,  866, 1802, 2982, 4082, 2116,  685, 2265,  388,
+    2750,  648, 1843, 1210, 3866,   11, 1751, 2414, 2764,  169, 3876, 1153, 1799, 3324, 3937, 2473,
+    2965, 1334, 3307,  431, 3592, 2301,  150, 1462,  389, 1209, 3739,   22, 3192, 1415, 2208,  227,
+    3154,  477, 2528, 3842, 2154,  459, 3728,  745, 3514, 2046,  596, 2450, 2995, 1998, 2519,  879,
+    2310, 3594,  737, 2015, 1195, 1812,  522, 3838, 2261, 3521,  548, 1315, 2706, 1541, 2993, 3929,
+    1321, 3505, 3153,  443, 2576, 2043, 3032,  546, 3383, 1988, 2986,  398, 2679, 1392,  953, 1563,
+    3503,  837, 1801, 2550,  687, 1277, 2934, 3304, 2505, 3443, 1555, 2907, 2377,  552, 3607, 2881,
+    1111, 2099, 1471,  966, 3118, 1632, 1125, 2393,  107, 3262, 1260, 3833, 1728, 1019, 3277,   37,
+    2973, 1553, 3342, 2522, 3041,  824, 3473, 1257,  161, 2605, 1656, 3254,  330, 3698,  946, 1781,
+    2535,   96, 2196, 1023, 3405,  753, 1406, 3678, 1009, 1576,  748, 3549, 2084,  177, 2868,  479,
+    2068,  196, 3827, 3002, 2012, 4001, 1723,  884, 1876,  651, 2022,  857, 3818, 1279, 1907, 2559,
+    4074,  684, 3537, 2820,    7, 3976, 3322, 1791, 2954,  934, 2592,  264,  698, 3504, 1390, 3794,
+     584, 1057,  365, 3773,  108, 2341, 2862, 2034, 3120, 1091, 3891,  825, 1932, 2423,  234, 3381,
+     730, 1491, 3664, 1761, 2793, 3998, 2254,  312, 2590, 3956, 2278, 1207, 3237, 3867, 2319, 3674,
+    3136, 2689, 1246,  974,   69, 2458,  484, 3765, 2796,  138, 3968, 2687,  281, 3125,  917,   93,
+    1668, 3258,  331, 1857, 2357,  726, 2719,  405, 1409, 4041, 1897, 3165, 2335, 2782,  315, 2069,
+    4077, 2815, 2244, 1700, 1361, 3985,  444, 1507, 3748,  378, 2160, 2905, 3569, 1199, 2789, 2029,
+    4050, 3046, 2396,  627,  155, 1596, 1178, 3078, 1863,   99, 2775,  520, 1606,  852, 1809, 1120,
+     598, 1651, 2162, 3622, 3203, 1419, 3378, 1126, 2157, 3157, 1377, 2260, 1733, 3466, 2351, 3769,
+    1372, 2167, 2591, 3667, 1249, 1539, 2049, 3449, 2255,  501, 3532, 1493, 1068, 3723, 1673, 2545,
+    1265, 1862, 3557,  897, 3295, 1942,  971, 2688,  724, 1807, 2495,   83, 1468,  635, 3287, 1608,
+    1077,  364, 1274, 3766, 3160, 2513, 3602,  715, 3451, 1453, 3156, 3797, 2478, 2983,   34, 3301,
+    2493, 3917,  276, 2802,  628, 1899, 2621,  253, 1600, 3649,  945,  567, 2889, 1090,  395, 2777,
+     749, 3054, 1079,  485, 2998, 3829,  154, 1072, 3777,  826, 2812,   88, 2205,  536, 3215,  843,
+      74, 3045,  512, 2644,  314, 2942, 3628, 2129, 3227, 3477, 1145, 4027, 3094, 2248, 3814,  133,
+    2661, 2123, 2870, 1850,  968, 2028,  476, 2747, 1129, 2176,  800, 1927,  307, 1324, 4046, 1966,
+    1394,  793, 3369, 1509, 2348, 3736,  812, 4081, 2930,  376, 2471, 3544, 1500, 4012, 2122, 1800,
+    3409,  172, 3982, 1740,  851, 2390, 3147, 2756, 1568, 2498, 1769, 3042, 3928, 1368, 2006, 3488,
+    2337, 1519, 3942, 1205, 2365, 1634,   61, 1248,  471, 1578, 2801,  787, 1720,  461, 2531,  922,
+    3149, 3883,  783, 3481,  286, 3900, 1526, 2328, 4091,  384, 3662, 1085, 3509, 2697, 2210,  446,
+    3558, 2926, 1852, 1109,  360, 3066, 1297, 2249, 1871, 1201, 3235, 1980,   57, 3025,  529, 3618,
+    1212, 2420, 2032, 2848, 3529,  319, 1813,  632, 3305,  339, 1176, 3434,  900, 2668,  326, 2845,
+    3650,  804, 1977, 3401,  688, 3865, 3172, 2567, 3947, 2262,  296, 3709, 2066, 1179, 3490, 1865,
+    1369,  525, 1697, 2476, 1335, 2847, 3278,   73, 1816, 2898, 2380, 3098, 1657,  582, 3199, 1024,
+    2546,  120, 2188, 3990, 2665, 1712,   20, 3483,  559, 2757, 3877,  820, 2363, 1318, 2677,  899,
+    1617, 3282,  618, 1461, 1167, 2212, 4063, 1333, 2146, 3738, 1929,  606, 2372, 1565, 3844, 1100,
+    2134, 3218,  144, 2768, 1440, 2182, 1000, 1836,  674, 3035, 1041, 2503, 3382, 2773,  323, 3727,
+    2237, 3313,   21, 3004, 2115,  571, 1012, 3519, 1267,  701, 1479,  128, 2082,  868, 3716, 1776,
+    1280, 3791,  677, 3161,  935, 3626, 2485, 3175,  979, 1550,  211, 1726, 3702, 3265, 1919, 3940,
+     355, 2597, 3854,  106, 3176, 2663,  916, 3427,   71, 2582, 2904, 3986,  135, 3294, 1831,  467,
+    1295, 2579, 1664, 3757,  470, 2999,  228, 3743, 1508, 3534, 1889,   43, 1410,  702, 1646, 2906,
+     816, 2628, 4017, 1114, 3655, 1676, 2558, 2152, 3053, 3817, 2637, 3419, 3935, 2859, 2431,  217,
+    3410, 2774, 1636,  423, 2072, 1412,  718, 1969, 3750, 2272, 3064, 2614,  591, 1066,  160, 2276,
+    3112,  836, 2161, 1827, 3759,  415, 1525, 2945,  743, 1640,  982, 1404, 2193, 2776,  771, 3051,
+     266, 4024,  739, 1088, 2405, 3357, 1991, 2701,  347, 1275, 2833, 4007, 2156, 3212, 3871,  165,
+    1190, 2002, 1510,  692, 3177,  178, 3974,  841,  283, 1943,  507, 1045, 1348,  411, 1528, 3090,
+    1961,  845, 2331, 3445, 2935, 3944,  232, 2866, 1216,  346, 4040, 1286, 2137, 3502, 2850, 1695,
+    1379, 3612, 1150, 2891,  704, 2447, 1983, 3913, 2282, 3210, 3616,  318, 3423, 1141, 3798, 2451,
+    3286, 1884, 2861, 3530, 1764, 1288,  832, 3475, 2371, 3230,  877,  502, 2594, 1028, 1881, 2460,
+    3596, 3354,  373, 2330, 2725, 1875, 1418, 2804, 3623, 1590, 2441, 3115, 1992, 2306, 3786, 1135,
+     490, 4060, 1384,   86, 1156, 2404, 1652, 3538, 2508, 1910,  844, 3417, 1602,  435, 3755,  689,
+    2652,  271, 3406, 1615, 3283, 1044, 3548,  272, 1220,  532, 1902, 2510, 1727,  570, 2063, 1544,
+     962,  574, 2143,  359, 2646,    9, 4083, 1633,  612, 2093, 3775, 1690, 3591,  341, 3099, 1416,
+     554, 2784, 1658, 3852,  958, 3452,  496, 2338, 1080, 3362,  728, 4034,   35, 3319,  744, 2749,
+    2133, 3173, 2601, 3584, 1840,  603, 3216,  952,  432, 3281, 2840,   38, 2461, 3047, 1215, 2364,
+    4086, 1950,  573, 2245,   55, 1408, 2826, 1737, 2610, 3810, 3040,  862, 4072, 3138,  167, 3563,
+    1344, 3699, 3139, 1448, 3783, 3031, 2051, 1165, 3103,  202, 1076, 2746, 1302, 2295,  839, 4068,
+    2138, 1043, 3061,  275, 2078, 1255, 3793, 3069, 2021,  209, 2928, 1777,  990, 2617, 1704, 3465,
+     180, 1614,  923,  393, 2748, 3822, 2183, 1455, 3930, 1685, 1123, 2048, 3948,  795, 1830,  109,
+    3097,  960, 2807, 3660, 2584, 4021,  646, 3343,  943, 2089,   15, 1486, 2737, 1198, 2253, 2641,
+    2909,   95, 2443, 1055,  699, 2294,  455, 2816, 3896, 2406, 3424, 1917,  119, 3337, 2844, 1736,
+      77, 3684, 2442,  732, 3297, 2540,   47,  802, 1522, 3834, 1307, 2494, 3708, 1436,  527, 3941,
+    1211, 2465, 3789, 2040, 3086, 1116,  126, 2987,  733, 2643, 3690,  566, 1424, 2683, 3396, 2195,
+    1495, 3497, 1261, 1729,  871, 1936, 2313,  203, 2958, 1303, 3523, 2349,  654, 3729,  449, 1804,
+     775, 1660, 3879, 1918, 3458, 1598, 3581,  873, 1811, 1434,  721, 3014, 3950, 1492,  597, 3507,
+    2623, 1370, 1849, 3975, 1444, 2940, 1767, 3390, 2226, 2731,  543, 2070,  242, 3280, 2211, 2936,
+    1951,  642, 3255, 1437,  746, 1739, 2573, 3631, 1892,  247, 2286, 3077, 3547,  337, 1021, 3921,
+     478, 2440,  176, 3836,  381, 3111, 3508, 1573, 3959, 2477,  372, 1667, 3411, 1970, 3055, 3945,
+    2376, 3327,  533, 2712,  263, 2466, 1322,  112, 2664, 3651,  434, 2219,  944, 2506, 2011, 1136,
+     403, 3197,  909,  174, 2277,  577, 1134, 4057,  392,  975, 3640, 3023,  831, 2686, 1105,  352,
+    3673, 2726,    3, 2300, 3991, 3340,  506, 2326, 1206, 3325, 1533,  959, 1911, 2392, 2865, 1626,
+     768, 2970, 3323, 2140, 2707, 1360, 1015,  499, 1858,  752, 3669, 2856,  930,  131, 1432, 1067,
+     233, 2047, 1197, 3207,  940, 2910, 3861, 3267, 2141, 1151, 2851, 1683, 3585,  246, 3279, 3853,
+    2267, 2855, 3609, 1994, 3350, 3706, 2766, 1960, 2417, 3167, 1677, 1242, 3911, 1815, 3388, 1559,
+     939, 1747, 3512, 1239,  293, 2735, 1490,  870, 4067, 2805,  528, 3888,  181, 1285, 3257, 2100,
+    3785, 1829,  947, 1517,  592, 3624, 2860, 2194, 3291, 2649, 1115, 2031, 3191, 2285, 2760, 3474,
+    3805, 2947, 1485, 4016, 2163, 1711,  498, 1879,  682, 4054,  159, 3180, 1225, 2730,  834, 1794,
+    1463,  625, 1082, 2603,  421, 1595,  888,  238, 1395, 3541,   82, 2289,  599, 2415,  148, 4087,
+    3050, 2507,  776, 2966, 1909, 3587, 2112, 3065,   48, 1722, 2184, 2577, 2967, 3641,  640,    6,
+    1183, 2787,  249, 3907, 2452, 1873,   46, 3804, 1253,  186, 3847, 1503,  400, 3994,  645, 1624,
+     948,  366, 2549,  722,   53, 3694, 1070, 2568, 2968, 1464, 2444, 2001, 3881,  486, 2334, 3092,
+      14, 3954, 1692, 3052, 1294, 2484, 3251, 3870, 2931,  670, 1888, 2876, 3437, 1414, 2813, 1986,
+     454, 1382, 2159, 3926,  590, 1110,  342, 2524, 3414, 1059, 3703, 1381,  807, 1757, 2350, 3413,
+    2553, 3566, 2204, 3229, 1139, 3016,  880, 2412, 1689, 3143,  669, 2462, 3398, 1250, 1924, 2631,
+    2247, 3637, 1814, 3461, 2422, 3037, 1383, 3570,  287, 3366,  932,  611, 1623, 3412, 1342, 3718,
+    2702, 2172, 3403,  225, 3781,  713, 1732, 2186, 1162, 2615, 3946,  980,  361, 3784, 1132,  711,
+    3211, 3730,  127, 2655, 1585, 3148, 3740, 1304, 1984,  655, 3013,  349, 2053, 3919, 1049, 1588,
+     453, 1374,  694, 1654,  414, 4071, 1439, 3425,  451, 2059, 2874, 1803,  911, 2920,   31, 3263,
+     609, 1224, 3108,  927, 1548,  428, 1997,  785, 2227, 1741, 3792, 3022, 2634,  212, 2035,  926,
+     516, 1272,  765, 1838, 2235, 2828,   59, 3642,  465, 2085, 1515, 3242, 1771, 2118, 2599, 3590,
+    2344, 1797, 1169, 3375,  860, 2327, 1734,  497, 3903, 2734, 1628, 3545, 3205,  205, 2839, 3142,
+    4009, 1957, 2977, 3722, 2625, 2111,  712, 2691, 3696,  957, 4022,  208, 3588, 2298, 3761, 1460,
+    4094, 2788,  298, 2045, 3910, 2616, 3233, 4006, 2786, 1217,   40, 2121, 1050, 4033, 2911, 1666,
+    3246, 2399, 2892, 4092, 1035, 3522, 1451, 3058,  914, 3364,  163, 2445,  772, 3063,   58, 1619,
+     996,  311, 3009, 1948, 3859,  189, 2917, 3288, 2242,  175,  985, 2340, 1271, 2527,  600, 2144,
+     921,  210, 2321,  997,   98, 1765, 3266,  260, 1577, 2246, 1329, 2651, 1630,  456, 1016, 2024,
+     147, 1605, 2382, 3311,  658, 1173,  121, 1567,  526, 3627, 2562, 3272, 1482,  693, 2492, 3652,
+     117, 3493, 1430,  350, 2566,  602, 2010, 2517, 4005, 1366, 2888, 3697, 1231, 3972,  579, 3389,
+    2875, 4013, 2453,  539, 1337, 2604,  738, 1117, 1480, 3453, 1896, 4051,  777, 1774, 3800, 1352,
+    2672, 3603, 3187, 1305, 2836, 3636, 2387, 1168, 2912, 3338,  589, 3119,  806, 3365, 2534, 3030,
+    3480,  906, 3815, 1339, 2896, 3717, 1915, 2470, 3145,  849, 1795,  379, 3540, 1878,  316, 1191,
+    2087,  967, 1935, 3049, 1639, 3308, 1185,  294, 1832,  665, 2171,  336, 1647, 2740, 2318, 1355,
+    2062,  767, 1520, 3659, 2151, 3499, 1793, 3963, 2690,  686, 2980,  422, 2817, 3331,   60, 3501,
+    1603,  394, 1788, 3889,  517, 1475,  782, 3960, 1954,   64, 3807, 1861, 2180, 3951, 1268, 1772,
+    2230, 2656,  500, 1790,  213, 2314,  913, 3496, 1293, 2130, 3967, 1164, 2992, 2228, 3819, 3146,
+    2670, 3897,  700, 3604,  134, 3933, 2217, 2997, 3726, 2674, 3492, 1083, 3219, 1949,  920, 3600,
+     348, 2650, 3202,   25,  904, 3091,  406, 2056,   89, 2400, 3704, 1420, 2222, 1161, 2016,  633,
+    3008, 2516,  769, 2075, 3418, 2256, 3129,  358, 2619,  929, 2500, 1142,  221, 2823,  375,  683,
+    3152, 1140, 3555, 2110, 3209, 1458, 2765,  344, 2927,  140, 2411,  616, 2713,  903, 1402,  580,
+    1618,  386, 2489, 2139, 1118, 2710,  762, 1564,  986,    5, 1760, 2432,  489, 3850,  123, 3033,
+    1773, 3771, 1208, 2355, 1653, 2759, 1276, 3608, 3302, 1069, 1735,  241, 3880, 2638, 3169,  993,
+    2281, 4058, 1158, 2758,  193, 1018, 1756, 3610, 1373, 3397, 1674, 3576, 3083, 1501, 2077, 3713,
+    1445,  129, 2957,  817, 4047,  457, 3621, 1820, 3848, 1534, 3355, 3711, 1693,   78, 3459, 2316,
+    3019, 3416, 1336, 2918, 1787,  483, 3536, 2486, 3135, 1401, 4035, 2950,  801, 3420, 1551, 2539,
+    1031,  534, 1922, 3447, 3923,  629, 2287, 1592,  792, 3026, 2158, 3476,  874,  466, 1661, 3751,
+    1441,  102, 3345, 1581, 3808, 2472, 2962,  660, 2189, 2873,  427,  729, 2408, 3826,  964, 2552,
+    1928, 3918, 1649, 2409, 1219, 2627,  727, 2223, 1033,  553, 1981, 1278, 3170, 2536, 4029, 1824,
+    1081,  224, 3977,  840, 3243, 3821, 1968,  279, 3430,  587, 2238, 1157, 2007, 2642, 1213, 2234,
+    4078, 3130, 2821,  222, 1094, 3248,  309, 4032, 2602,  445, 1310, 2798, 1877, 2427, 3415,  305,
+    2834, 2170,  613, 3071, 1959,  460, 1244, 4019,  151, 1905, 3912, 1340, 1848,   28, 3253,  462,
+    3356,  652, 2727,  302, 1978, 3387, 1637, 3259, 2514, 3107, 2770,  214,  992, 2023,  472,  779,
+    2827, 2065, 2386, 1662,  103, 1365, 2362, 1112, 1665, 2790, 3707,  184, 3164,  382, 3665,  679,
+     168, 1575,  848, 2153, 2544, 1721, 2863, 1184, 1938, 3692, 3276,   13, 4000, 1124, 2963,  740,
+    1866, 3635, 2554, 1363,  886, 3579, 2613, 1641, 3223, 1089, 2580, 2951, 3539, 2241, 1229, 2810,
+    2288, 1292, 3752, 3085,  972, 3875,    1, 1331,  351, 3996,  755, 2332, 3812, 1502, 3269, 3614,
+    1291, 3742,  608, 3495, 2608, 3011,  540, 3653, 2561,  819, 1913, 1425, 3914, 1749, 2779, 2044,
+    3359, 2469, 3710, 1388, 3831,  741, 3513,  152, 2389,  731, 1638, 2290,  621, 1494, 2088, 3864,
+    1270,  965,  353, 3981, 2232,   26, 3101,  830, 2315, 3681,  267,  924,  555, 1570, 4045,  808,
+    3524,   92, 2091, 1476,  550, 2296, 2708, 3526, 2073, 1438, 1779, 3446, 2924,  300, 2680, 2271,
+      18, 1587, 2903, 1092, 2005,  773, 4049, 2102,   80, 3241, 3542,  480, 2418,  882, 1332, 3012,
+    1097, 1823,  304, 3204,  481, 1869, 3114, 1469, 3857, 2741, 1093, 3062, 3666, 2589,  153, 3186,
+    2669, 3472, 1663, 3232, 2864, 1452, 1974, 3491,  492, 1357, 2103, 3334, 2434, 3106,  231, 1731,
+    3048, 1054, 2533, 3560, 3194, 1826, 1155,  813, 2991, 3663, 1062,  487, 2119, 1237,  892, 1783,
+    3134, 2511,  401, 3886, 1446, 3220, 1696, 1189, 2919, 1547, 1030, 2128, 2867, 3470,   33, 3979,
+     568, 3551, 2695, 1149, 2825, 2329,  976, 2094,  410, 3394, 1890,  322,  905, 3300, 1780,  585,
+    2291,  254, 2041,  638, 1005, 3756,  285, 2560, 1597, 3010, 3934, 1763, 1186, 3788, 2050, 2693,
+     594, 3987, 1784,  778,  289, 2843, 4084,  474, 2439,  118, 2618, 3198, 1613, 3869, 3482,  549,
+    4076, 1011, 3316, 2293,  164, 2755,  419, 3478, 2303,  320, 3970, 3084,  708, 1684, 2606, 1487,
+    2309,  890, 2113, 1609, 4042,   39, 3688, 2626,  666, 1358, 4085, 2800, 2192, 1313, 3969, 1034,
+    1530, 3076, 3916, 2722, 1841, 2388, 1200, 4093,  664, 2705,   97,  742, 2869,  437,  919, 1422,
+    2356,  335, 2769, 1264, 3747, 1504, 2127, 3339, 1659, 1979, 3965,  725, 2395,  139, 2792, 2214,
+    1380, 2039,  761, 1775, 3444, 1056, 2026, 3862,  631, 2682, 1952, 1230,  255, 3811, 1982,  367,
+    3089, 3837,  197, 3435,  747, 1417, 3289, 1707, 3072, 2379,   85, 1549, 3564,  418, 2403, 2872,
+    3693,  759, 1327,  136, 3384,  463, 3132, 2092, 3371, 1099, 1916, 3525, 1512, 2538, 3352, 3687,
+    1171, 3168, 2030, 3400, 2383,   94,  938, 2645, 1227,  332, 3393, 1413, 3088, 1931, 1148, 3358,
+     204, 3006, 2648, 3646,  576, 2436, 3074, 1399,  954, 3376, 1611, 3582, 2322, 3166, 1017, 3517,
+    2717, 1223, 1847, 2537, 2974, 2221,  380, 1170, 3778,  898, 2009, 3020,  697, 1859, 3395,  101,
+    1925, 2565, 2203, 3583, 1552, 2639,  872, 1742,  357, 2457, 3846, 2270, 3039,  191, 2173, 1679,
+      27, 3895,  864,  511, 1701, 2933, 3571,  557, 3799, 2854, 2229, 1038,  409, 3676,  805, 2564,
+    1540, 3749,  329, 1314, 1621, 3801,   32, 1856, 2857, 2430,  113,  797, 2791, 1400,  617, 2202,
+    1635,  695, 3244,  438, 1052, 3647, 1926, 2795,  252, 3460, 2570, 1180, 3927, 2709,  894, 1245,
+    3117,  436, 1087, 2969,  657, 3887, 1290, 3705, 2767, 1449,  814,  494, 1252, 4053,  720, 2939,
+    1872, 2578, 1511, 3044, 3803, 1159, 1904, 3144, 1489,  794, 1750, 3906, 2463, 2921, 1770, 3989,
+     626, 1953,  951, 3131, 2155, 2698,  786, 3527,  482, 4018, 1160, 2067, 3700,  396, 4069, 3005,
+      68, 3633, 2081, 3980, 1569, 2497,  833, 3924, 2264, 1604,  505, 3225,  321, 1524, 2178, 3858,
+    1627, 3328, 3993, 1755, 2101,  273, 2324, 3003,   52, 3598, 3201, 1705, 3436, 1976, 1047, 3498,
+    1320, 3615, 2142,  340, 2501,  672, 2250,  295, 2429, 3454,   67, 3155,  673, 1433,  125, 2273,
+    2890, 3399, 2456, 3873,  262, 1128, 3234, 1481, 2098, 2972, 1719, 3222, 2541, 1562, 1908, 2600,
+    1071, 2433, 1338, 2835,  105, 3402,  545, 1354, 3133, 1002, 3745, 1887, 2374, 3500,  200, 2778,
+     643, 2354,   17,  847, 2877, 3487, 1631,  630, 1993, 1131, 2124, 2718,  251, 2435, 2806,  519,
+    3290,  220, 1001, 3957, 1411, 2837, 3668,  977, 4044, 1989, 2714, 1137, 2136, 3562, 3213, 1029,
+     391, 1251, 1738,  690, 2915, 1903, 3931, 2345, 1014,  270, 3550,  676,  201, 1127, 3351,  770,
+    3860, 3162,  510,  907, 1973, 3021, 1678, 2678,  179, 2064, 2849, 1319,  798, 2989, 1113, 3682,
+    1955, 1367, 2620, 3758, 1226, 2512,  994, 4015, 3141, 2572,  424, 3938,  895, 1431, 3787, 2251,
+     846, 2729, 3150, 1778, 3442,   49, 1835, 1269, 2988,  593, 1391, 3768,  282, 1715, 2595, 3809,
+    2074, 3606,   63, 3200, 1405, 2509,  368,  641, 3721, 2659, 1259, 2366, 3949, 2893, 2187,  370,
+    1484, 1798, 2292, 3580, 3904, 1122, 2197, 3467, 4061,  675, 3321,   62, 3978, 1744, 2487,  447,
+    3372,  961, 3001, 1898,  514, 3240,  142, 1833, 1398,  791, 3654, 1584, 2941, 3367,  114, 1644,
+    4037, 2373, 1222,  578, 2320,  829, 3217, 2530,  207, 1670, 2361, 3374, 2824,  764, 1328,  468,
+    1607, 2803, 2263, 4003,  865, 3599, 1680, 2894, 3274, 1958, 1601, 3104,  937, 1698, 3762, 2667,
+    3528,  192, 2752, 1426,  259, 2588,  413,  925, 1817, 2468, 1496, 2662, 2109,  588, 3189, 1513,
+    2236, 4075,  206, 3456, 1427, 2218, 3806, 2673, 3506,  243, 2360, 1914,  541, 1106, 1964, 3082,
+    1407,  377, 1975, 3731, 2964, 1483, 3874, 2132, 3330, 3820,  883,  441, 1864, 4014, 3100, 2352,
+    3348,  644, 1084, 1874, 2633,  170, 2185, 1346,  859,  116, 3841,  518, 2079,   29, 1281,  714,
+    2027, 1008, 3428,  756, 3284, 1579, 3744, 3059, 1240,  317, 3715,  981, 3455, 1263, 3823,  268,
+    2739,  678, 1669, 2483,  784, 2961,  402, 1177, 2086, 2899, 1013, 3224, 2556, 3845, 2207,  662,
+    3644, 2609, 3309,  190, 1026, 2704,  310,  667, 1138, 1965, 3081, 2563, 1086, 2168,  183,  969,
+    3868, 1473, 3043,  439, 3463, 1154, 3326, 4089, 2585, 2239, 1152, 3370, 2622, 3617, 2946, 3273,
+    2384, 4052, 1867, 2996, 2311, 2014,  719, 2732, 3605, 2225, 2922, 1703,  229, 2358, 2879, 1061,
+    1837, 3171, 1204, 3732, 2019, 3578, 1686,  680, 3306, 1470, 4070,   10, 1378, 3457,  297, 2882,
+    1853,  823, 1580, 2269, 3988, 2054, 1713, 3535, 2822, 1423,    0, 3518, 1542, 2901, 3433, 1786,
+    2624,  104, 2401, 3770, 1537, 2923,  564, 1766,  354, 3645, 2886, 1518,  781, 2252,  327, 1582,
+     495, 1375,   81, 1175,  515, 3971, 1326,    4, 1886,  876,  524, 3902, 3109, 1962,  639, 3404,
+    2131, 3882,  408, 2694,  185, 1060, 2586, 3856, 2336,  513, 1851, 2742,  818, 1706, 2449, 1174,
+      90, 3056, 3586,  547, 1341, 3158,  902, 2391,  475, 4088, 2220,  723, 3733,  412, 1311,  622,
+    2083, 3239, 1218, 1971,  751, 2480, 2106, 1063, 3178,  696, 1900,  236, 4023, 1810,  956, 3824,
+    2681, 3095, 3648, 2555, 2871, 3346, 1752, 3494, 2569, 3247, 1385, 2496,  811, 1557, 3691,   65,
+    1429,  855, 2317, 3110, 1531, 3361, 1944,  110, 3067,  949, 3353, 2224, 3657,  521, 3184, 3878,
+    2700, 2038, 1075, 2481, 3432,   70, 2754, 3774, 1523, 3250,  991, 2692, 1844, 2369, 3932, 2745,
+    3611,  896, 4039,  290, 3151, 3686,   12, 3863, 2743, 1312, 2342, 3015, 1182, 3221, 2455, 3441,
+    1923,  842, 2166, 1558,  973,  257, 2424,  586, 1102, 4031, 2125,  137, 3344, 1163, 2711, 2381,
+    2994, 3574, 1746,  604, 4010,  828, 2943, 1306, 1642, 3724,  250, 1144, 2981, 1930,  978, 1477
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{DistanceMetric, Palette, Rgb};
+
+    const ALL_METHODS: [Dither; 11] = [Dither::None, Dither::FloydSteinberg, Dither::JarvisJudiceNinke,
+                                       Dither::Stucki, Dither::Atkinson, Dither::Sierra,
+                                       Dither::TwoRowSierra, Dither::SierraLite, Dither::Bayer(2),
+                                       Dither::Bayer(8), Dither::BlueNoise];
+
+    fn grey_image(level: f64, width: usize, height: usize) -> Vec<Rgb<f64>> {
+        vec![Rgb::with_components(level, level, level); width * height]
+    }
+
+    fn mean_red(pixels: &[Rgb<f64>]) -> f64 {
+        pixels.iter().fold(0.0, |sum, p| sum + p.r()) / pixels.len() as f64
+    }
+
+    #[test]
+    fn bayer_matrix_contains_each_rank_once() {
+        let mut matrix = bayer_matrix(4);
+        matrix.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (i, t) in matrix.iter().enumerate() {
+            assert!((t - ((i as f64 + 0.5) / 16.0 - 0.5)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn blue_noise_table_matches_generator() {
+        let ranks = blue_noise_ranks(BLUE_NOISE_SIZE);
+        assert!(ranks.iter().zip(BLUE_NOISE_RANKS.iter()).all(|(&a, &b)| a == b as usize));
+    }
+
+    #[test]
+    fn dithering_preserves_average_level() {
+        let image = grey_image(0.3, 32, 32);
+        for &method in ALL_METHODS.iter().skip(1) {
+            let mut ditherer = Ditherer::new(method);
+            ditherer.set_serpentine(true);
+            let dithered: Vec<Rgb<f64>> = ditherer.dither_to_depth(&image, 32, BitDepth::uniform(1));
+            assert!((mean_red(&dithered) - 0.3).abs() < 0.08, "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn linear_light_dithering_preserves_brightness() {
+        let image = grey_image(0.5, 32, 32);
+        let mut ditherer = Ditherer::new(Dither::FloydSteinberg);
+        ditherer.set_linear_light(true);
+        let dithered: Vec<Rgb<f64>> = ditherer.dither_to_depth(&image, 32, BitDepth::uniform(1));
+        // sRGB 0.5 is about 21% of the light of white.
+        assert!((mean_red(&dithered) - 0.214).abs() < 0.03);
+    }
+
+    #[test]
+    fn rgb565_levels() {
+        let image = vec![Rgb::with_components(65535u16, 32768, 0); 4];
+        let dithered: Vec<Rgb<u8>> = Ditherer::new(Dither::FloydSteinberg)
+            .dither_to_depth(&image, 2, BitDepth::new(5, 6, 5));
+        for p in &dithered {
+            assert_eq!(p.r() >> 3, 31);
+            assert!(p.g() >> 2 == 31 || p.g() >> 2 == 32);
+            assert_eq!(p.b(), 0);
+        }
+    }
+
+    #[test]
+    fn dithering_to_a_palette_returns_indices() {
+        let palette = Palette::new(vec![Rgb::with_components(0u8, 0, 0), Rgb::with_components(255u8, 255, 255)]);
+        let image = grey_image(0.5, 8, 8);
+        for &method in ALL_METHODS.iter() {
+            let indices = Ditherer::new(method).dither_to_palette(&image, 8, &palette, DistanceMetric::Rgb);
+            assert_eq!(indices.len(), 64);
+            assert!(indices.iter().all(|&i| i < 2));
+            if method != Dither::None {
+                assert!(indices.contains(&0) && indices.contains(&1));
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use super::{Channel, ColorMatrix, Rgb, Rgba};
+
+/// One of the CSS `filter` functions that adjust color, with its amount.
+/// Amounts are given as numbers rather than percentages, so `50%` is `0.5`,
+/// and out of range amounts are clamped as the Filter Effects
+/// specification requires.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CssFilter {
+    /// `brightness()`: scale red, green and blue. One leaves colors
+    /// unchanged.
+    Brightness(f64),
+
+    /// `contrast()`: scale red, green and blue around one half. One leaves
+    /// colors unchanged.
+    Contrast(f64),
+
+    /// `grayscale()`: convert toward grey, where one is fully grey.
+    Grayscale(f64),
+
+    /// `hue-rotate()`: rotate hues by an angle in degrees.
+    HueRotate(f64),
+
+    /// `invert()`: invert red, green and blue, where one fully inverts and
+    /// one half gives grey.
+    Invert(f64),
+
+    /// `opacity()`: scale alpha. Opaque `Rgb` colors are unchanged.
+    Opacity(f64),
+
+    /// `saturate()`: scale saturation, where zero is grey and one leaves
+    /// colors unchanged.
+    Saturate(f64),
+
+    /// `sepia()`: convert toward sepia, where one is fully sepia.
+    Sepia(f64)
+}
+
+impl CssFilter {
+    /// Returns the color matrix equivalent to the filter.
+    pub fn matrix(self) -> ColorMatrix {
+        match self {
+            CssFilter::Brightness(amount) => ColorMatrix::brightness(amount),
+            CssFilter::Contrast(amount) => ColorMatrix::contrast(amount),
+            CssFilter::Grayscale(amount) => ColorMatrix::grayscale(amount),
+            CssFilter::HueRotate(degrees) => ColorMatrix::hue_rotate(degrees),
+            CssFilter::Invert(amount) => ColorMatrix::invert(amount),
+            CssFilter::Opacity(amount) => ColorMatrix::opacity(amount),
+            CssFilter::Saturate(amount) => ColorMatrix::css_saturate(amount),
+            CssFilter::Sepia(amount) => ColorMatrix::sepia(amount)
+        }
+    }
+}
+
+/// A color which CSS filter functions can be applied to. As in browsers,
+/// filters operate on the sRGB encoded channels, and colors with alpha are
+/// not premultiplied.
+///
+/// ```rust
+/// use simplecolor::{FilterColor, Rgb};
+///
+/// let dimmed = Rgb::with_components(200u8, 120, 40).brightness(0.8).saturate(1.5);
+/// ```
+pub trait FilterColor: Sized {
+    /// Apply a single filter function.
+    fn filter(&self, filter: CssFilter) -> Self;
+
+    /// Apply the CSS `brightness()` filter.
+    fn brightness(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Brightness(amount))
+    }
+
+    /// Apply the CSS `contrast()` filter.
+    fn contrast(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Contrast(amount))
+    }
+
+    /// Apply the CSS `grayscale()` filter.
+    fn grayscale(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Grayscale(amount))
+    }
+
+    /// Apply the CSS `hue-rotate()` filter, with an angle in degrees.
+    fn hue_rotate(&self, degrees: f64) -> Self {
+        self.filter(CssFilter::HueRotate(degrees))
+    }
+
+    /// Apply the CSS `invert()` filter. `Color::invert` is the same as an
+    /// amount of one.
+    fn invert_by(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Invert(amount))
+    }
+
+    /// Apply the CSS `opacity()` filter.
+    fn opacity(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Opacity(amount))
+    }
+
+    /// Apply the CSS `saturate()` filter.
+    fn saturate(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Saturate(amount))
+    }
+
+    /// Apply the CSS `sepia()` filter.
+    fn sepia(&self, amount: f64) -> Self {
+        self.filter(CssFilter::Sepia(amount))
+    }
+}
+
+impl<T: Channel> FilterColor for Rgb<T> {
+    fn filter(&self, filter: CssFilter) -> Rgb<T> {
+        filter.matrix().apply(self)
+    }
+}
+
+impl<T: Channel> FilterColor for Rgba<T> {
+    fn filter(&self, filter: CssFilter) -> Rgba<T> {
+        filter.matrix().apply_rgba(self)
+    }
+}
+
+/// A list of CSS filter functions applied in order, like the value of the
+/// `filter` property. Each function's result is clamped before the next is
+/// applied, so the pipeline is not generally equivalent to a single matrix.
+///
+/// ```rust
+/// use simplecolor::{FilterPipeline, Rgb};
+///
+/// let filters = FilterPipeline::new().sepia(0.6).contrast(1.2).hue_rotate(-10.0);
+/// let out = filters.apply(&Rgb::with_components(0.3f32, 0.5, 0.7));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FilterPipeline {
+    /// Filters, in the order they are applied
+    filters: Vec<CssFilter>
+}
+
+impl FilterPipeline {
+    /// Create an empty pipeline, which leaves colors unchanged.
+    pub fn new() -> FilterPipeline {
+        FilterPipeline { filters: Vec::new() }
+    }
+
+    /// Returns the filters in the order they are applied.
+    pub fn filters(&self) -> &[CssFilter] {
+        &self.filters
+    }
+
+    /// Append a filter to the pipeline.
+    pub fn push(&mut self, filter: CssFilter) {
+        self.filters.push(filter);
+    }
+
+    /// Returns the pipeline with a filter appended.
+    pub fn then(mut self, filter: CssFilter) -> FilterPipeline {
+        self.push(filter);
+        self
+    }
+
+    /// Returns the pipeline with a `brightness()` filter appended.
+    pub fn brightness(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Brightness(amount)) }
+
+    /// Returns the pipeline with a `contrast()` filter appended.
+    pub fn contrast(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Contrast(amount)) }
+
+    /// Returns the pipeline with a `grayscale()` filter appended.
+    pub fn grayscale(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Grayscale(amount)) }
+
+    /// Returns the pipeline with a `hue-rotate()` filter appended, with an
+    /// angle in degrees.
+    pub fn hue_rotate(self, degrees: f64) -> FilterPipeline { self.then(CssFilter::HueRotate(degrees)) }
+
+    /// Returns the pipeline with an `invert()` filter appended.
+    pub fn invert(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Invert(amount)) }
+
+    /// Returns the pipeline with an `opacity()` filter appended.
+    pub fn opacity(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Opacity(amount)) }
+
+    /// Returns the pipeline with a `saturate()` filter appended.
+    pub fn saturate(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Saturate(amount)) }
+
+    /// Returns the pipeline with a `sepia()` filter appended.
+    pub fn sepia(self, amount: f64) -> FilterPipeline { self.then(CssFilter::Sepia(amount)) }
+
+    /// Apply every filter in turn to a color.
+    pub fn apply<C: FilterColor + Copy>(&self, color: &C) -> C {
+        self.filters.iter().fold(*color, |color, &filter| color.filter(filter))
+    }
+
+    /// Apply every filter in turn to a buffer of colors in place.
+    pub fn apply_in_place<C: FilterColor + Copy>(&self, pixels: &mut [C]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Color, Rgb, Rgba};
+    use ::test::assert_near;
+
+    const COLOR: (f64, f64, f64) = (0.8, 0.35, 0.1);
+
+    fn color() -> Rgb<f64> {
+        Rgb::with_components(COLOR.0, COLOR.1, COLOR.2)
+    }
+
+    #[test]
+    fn component_transfer_filters_follow_spec_formulas() {
+        let (r, g, b) = COLOR;
+        let brightness = |c: f64| (c * 1.2).min(1.0);
+        assert_near(color().brightness(1.2), (brightness(r), brightness(g), brightness(b)), 1e-9);
+        let contrast = |c: f64| 0.7 * c + (0.5 - 0.7 / 2.0);
+        assert_near(color().contrast(0.7), (contrast(r), contrast(g), contrast(b)), 1e-9);
+        let invert = |c: f64| 0.3 * (1.0 - c) + (1.0 - 0.3) * c;
+        assert_near(color().invert_by(0.3), (invert(r), invert(g), invert(b)), 1e-9);
+    }
+
+    #[test]
+    fn matrix_filters_follow_spec_formulas() {
+        let (r, g, b) = COLOR;
+        let grey = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        assert_near(color().grayscale(1.0), (grey, grey, grey), 1e-9);
+        assert_near(color().grayscale(2.0), (grey, grey, grey), 1e-9);
+
+        let s = 1.0 - 0.4;
+        let sepia = (
+            (0.393 + 0.607 * s) * r + (0.769 - 0.769 * s) * g + (0.189 - 0.189 * s) * b,
+            (0.349 - 0.349 * s) * r + (0.686 + 0.314 * s) * g + (0.168 - 0.168 * s) * b,
+            (0.272 - 0.272 * s) * r + (0.534 - 0.534 * s) * g + (0.131 + 0.869 * s) * b);
+        assert_near(color().sepia(0.4), sepia, 1e-9);
+
+        let (sin, cos) = 90.0f64.to_radians().sin_cos();
+        let hue_red = (0.213 + cos * 0.787 - sin * 0.213) * r +
+                      (0.715 - cos * 0.715 - sin * 0.715) * g +
+                      (0.072 - cos * 0.072 + sin * 0.928) * b;
+        assert!((color().hue_rotate(90.0).r() - hue_red.max(0.0)).abs() < 1e-9);
+
+        assert_near(color().saturate(-1.0), color().saturate(0.0).components(), 1e-9);
+    }
+
+    #[test]
+    fn full_invert_matches_color_invert() {
+        let col = Rgb::with_components(10u8, 128, 250);
+        assert_eq!(col.invert_by(1.0).components(), col.invert().components());
+        assert_eq!(col.invert_by(0.0).components(), col.components());
+    }
+
+    #[test]
+    fn opacity_only_affects_alpha() {
+        let col = Rgba::with_components(0.2f64, 0.4, 0.6, 0.5);
+        assert_eq!(col.opacity(0.5).components(), (0.2, 0.4, 0.6, 0.25));
+        assert_eq!(color().opacity(0.5).components(), COLOR);
+    }
+
+    #[test]
+    fn pipeline_clamps_between_filters() {
+        let filters = FilterPipeline::new().brightness(2.0).brightness(0.5);
+        assert_eq!(filters.filters(), &[CssFilter::Brightness(2.0), CssFilter::Brightness(0.5)]);
+        assert_near(filters.apply(&color()), (0.5, 0.35, 0.1), 1e-9);
+    }
+
+    #[test]
+    fn pipeline_applies_filters_in_order() {
+        let filters = FilterPipeline::new().sepia(0.5).hue_rotate(30.0).contrast(1.1);
+        let expected = color().sepia(0.5).hue_rotate(30.0).contrast(1.1);
+        assert_near(filters.apply(&color()), expected.components(), 1e-9);
+
+        let mut pixels = vec![Rgba::with_components(200u8, 100, 50, 255); 3];
+        FilterPipeline::new().grayscale(1.0).opacity(0.2).apply_in_place(&mut pixels);
+        assert_eq!(pixels[2].components(), (118, 118, 118, 51));
+        assert_eq!(FilterPipeline::new().apply(&color()), color());
+    }
+}
@@ -0,0 +1,458 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use super::{clamp, Rgb, YCbCr, YCbCrEncoding};
+
+/// The memory layout of a Y'CbCr video frame. Planes and rows are tightly
+/// packed, with luma first. Chroma planes of subsampled formats have half
+/// the width, rounded up, and for 4:2:0 formats half the height, rounded up.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// 4:4:4 planar: Y, Cb and Cr planes at full resolution.
+    I444,
+
+    /// 4:2:2 planar: a Y plane, then Cb and Cr planes at half width.
+    I422,
+
+    /// 4:2:0 planar: a Y plane, then Cb and Cr planes at half width and
+    /// half height. Also known as YUV420p.
+    I420,
+
+    /// 4:2:0 planar like `I420`, with the Cr plane before the Cb plane.
+    Yv12,
+
+    /// 4:2:0 semi-planar: a Y plane, then one plane of interleaved Cb and Cr
+    /// samples.
+    Nv12,
+
+    /// 4:2:0 semi-planar like `Nv12`, with Cr before Cb in each pair.
+    Nv21,
+
+    /// 4:2:2 packed: each pair of pixels is stored as Y0, Cb, Y1, Cr. Rows
+    /// with an odd width are padded with a repeated luma sample.
+    Yuyv
+}
+
+impl FrameFormat {
+    /// Returns whether chroma is subsampled horizontally and vertically.
+    fn subsampling(self) -> (bool, bool) {
+        match self {
+            FrameFormat::I444 => (false, false),
+            FrameFormat::I422 | FrameFormat::Yuyv => (true, false),
+            FrameFormat::I420 | FrameFormat::Yv12 | FrameFormat::Nv12 | FrameFormat::Nv21 => (true, true)
+        }
+    }
+
+    /// Returns the width and height of the chroma planes for a frame of the
+    /// given size.
+    pub fn chroma_size(self, width: usize, height: usize) -> (usize, usize) {
+        let (horizontal, vertical) = self.subsampling();
+        (if horizontal { width / 2 + width % 2 } else { width },
+         if vertical { height / 2 + height % 2 } else { height })
+    }
+
+    /// Returns the number of bytes in a frame of the given size.
+    pub fn frame_size(self, width: usize, height: usize) -> usize {
+        let (chroma_width, chroma_height) = self.chroma_size(width, height);
+        match self {
+            FrameFormat::Yuyv => chroma_width * 4 * height,
+            _ => width * height + 2 * chroma_width * chroma_height
+        }
+    }
+}
+
+/// The position of each chroma sample relative to the luma samples it
+/// covers.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ChromaSiting {
+    /// Chroma lies midway between luma samples horizontally and vertically,
+    /// as in JPEG and MPEG-1.
+    Center,
+
+    /// Chroma is co-sited with the left luma sample of each pair and midway
+    /// between rows, as in MPEG-2, H.264 and BT.709 video.
+    Left,
+
+    /// Chroma is co-sited with the top left luma sample, as in BT.2020 4:2:0
+    /// and 4:2:2 video.
+    TopLeft
+}
+
+impl ChromaSiting {
+    /// Returns whether chroma is co-sited with luma horizontally and
+    /// vertically.
+    fn cosited(self) -> (bool, bool) {
+        match self {
+            ChromaSiting::Center => (false, false),
+            ChromaSiting::Left => (true, false),
+            ChromaSiting::TopLeft => (true, true)
+        }
+    }
+}
+
+/// The filter used to resample chroma planes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ChromaFilter {
+    /// Take the nearest sample. This is fastest, but blocky.
+    Nearest,
+
+    /// Average neighbouring samples when downsampling, weighted by the
+    /// chroma siting, and interpolate linearly when upsampling.
+    Linear
+}
+
+/// Converts between interleaved `Rgb<u8>` images and 8 bit Y'CbCr frames.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct FrameConverter {
+    /// Frame layout
+    format: FrameFormat,
+
+    /// Matrix and range
+    encoding: YCbCrEncoding,
+
+    /// Chroma sample positions
+    siting: ChromaSiting,
+
+    /// Chroma resampling filter
+    filter: ChromaFilter
+}
+
+impl FrameConverter {
+    /// Create a converter for the given frame format and encoding, with
+    /// left chroma siting and linear filtering. Panics unless the encoding
+    /// has a bit depth of 8.
+    pub fn new(format: FrameFormat, encoding: YCbCrEncoding) -> FrameConverter {
+        assert_eq!(encoding.bit_depth(), 8);
+        FrameConverter {
+            format: format,
+            encoding: encoding,
+            siting: ChromaSiting::Left,
+            filter: ChromaFilter::Linear
+        }
+    }
+
+    /// Returns the frame format.
+    #[inline]
+    pub fn format(&self) -> FrameFormat { self.format }
+
+    /// Returns the Y'CbCr encoding.
+    #[inline]
+    pub fn encoding(&self) -> YCbCrEncoding { self.encoding }
+
+    /// Returns the chroma siting.
+    #[inline]
+    pub fn siting(&self) -> ChromaSiting { self.siting }
+
+    /// Set the chroma siting.
+    #[inline]
+    pub fn set_siting(&mut self, siting: ChromaSiting) { self.siting = siting; }
+
+    /// Returns the chroma resampling filter.
+    #[inline]
+    pub fn filter(&self) -> ChromaFilter { self.filter }
+
+    /// Set the chroma resampling filter.
+    #[inline]
+    pub fn set_filter(&mut self, filter: ChromaFilter) { self.filter = filter; }
+
+    /// Convert an image to a frame. Chroma is resampled before it is rounded
+    /// to code values. Panics if the number of pixels is not a multiple of
+    /// `width`.
+    pub fn from_rgb(&self, pixels: &[Rgb<u8>], width: usize) -> Vec<u8> {
+        if pixels.is_empty() {
+            return Vec::new();
+        }
+        assert!(width > 0 && pixels.len() % width == 0);
+        let height = pixels.len() / width;
+        let ((y_scale, y_offset), (c_scale, c_offset)) = self.encoding.quantization();
+        let mut luma = Vec::with_capacity(pixels.len());
+        let mut cb = Vec::with_capacity(pixels.len());
+        let mut cr = Vec::with_capacity(pixels.len());
+        for pixel in pixels {
+            let ycbcr: YCbCr<f64> = YCbCr::from_rgb(pixel, &self.encoding);
+            luma.push(code(ycbcr.y() * y_scale + y_offset));
+            cb.push(ycbcr.cb() * c_scale + c_offset);
+            cr.push(ycbcr.cr() * c_scale + c_offset);
+        }
+
+        let (horizontal, vertical) = self.format.subsampling();
+        let (cosited_x, cosited_y) = self.siting.cosited();
+        let (w, h) = self.format.chroma_size(width, height);
+        if horizontal {
+            cb = resample_rows(&cb, width, height, w, |line, n| downsample(line, n, cosited_x, self.filter));
+            cr = resample_rows(&cr, width, height, w, |line, n| downsample(line, n, cosited_x, self.filter));
+        }
+        if vertical {
+            cb = transpose(&resample_rows(&transpose(&cb, w, height), height, w, h,
+                                          |line, n| downsample(line, n, cosited_y, self.filter)), h, w);
+            cr = transpose(&resample_rows(&transpose(&cr, w, height), height, w, h,
+                                          |line, n| downsample(line, n, cosited_y, self.filter)), h, w);
+        }
+        let cb: Vec<u8> = cb.iter().map(|&x| code(x)).collect();
+        let cr: Vec<u8> = cr.iter().map(|&x| code(x)).collect();
+
+        let mut frame = Vec::with_capacity(self.format.frame_size(width, height));
+        match self.format {
+            FrameFormat::I444 | FrameFormat::I422 | FrameFormat::I420 => {
+                frame.extend_from_slice(&luma);
+                frame.extend_from_slice(&cb);
+                frame.extend_from_slice(&cr);
+            }
+            FrameFormat::Yv12 => {
+                frame.extend_from_slice(&luma);
+                frame.extend_from_slice(&cr);
+                frame.extend_from_slice(&cb);
+            }
+            FrameFormat::Nv12 | FrameFormat::Nv21 => {
+                frame.extend_from_slice(&luma);
+                let (first, second) = if self.format == FrameFormat::Nv12 { (&cb, &cr) } else { (&cr, &cb) };
+                for (a, b) in first.iter().zip(second.iter()) {
+                    frame.push(*a);
+                    frame.push(*b);
+                }
+            }
+            FrameFormat::Yuyv => {
+                for y in 0..height {
+                    for x in 0..w {
+                        let y0 = luma[y * width + 2 * x];
+                        let y1 = if 2 * x + 1 < width { luma[y * width + 2 * x + 1] } else { y0 };
+                        frame.extend_from_slice(&[y0, cb[y * w + x], y1, cr[y * w + x]]);
+                    }
+                }
+            }
+        }
+        frame
+    }
+
+    /// Convert a frame to an image. Panics if the frame is smaller than
+    /// `FrameFormat::frame_size` for the given dimensions.
+    pub fn to_rgb(&self, frame: &[u8], width: usize, height: usize) -> Vec<Rgb<u8>> {
+        assert!(frame.len() >= self.format.frame_size(width, height));
+        if width == 0 || height == 0 {
+            return Vec::new();
+        }
+        let (w, h) = self.format.chroma_size(width, height);
+        let (luma_size, chroma_size) = (width * height, w * h);
+        let plane = |start: usize| -> Vec<f64> {
+            frame[start..start + chroma_size].iter().map(|&x| x as f64).collect()
+        };
+        let interleaved = |offset: usize| -> Vec<f64> {
+            (0..chroma_size).map(|i| frame[luma_size + 2 * i + offset] as f64).collect()
+        };
+
+        let (luma, mut cb, mut cr) = match self.format {
+            FrameFormat::I444 | FrameFormat::I422 | FrameFormat::I420 => {
+                (frame[..luma_size].to_vec(), plane(luma_size), plane(luma_size + chroma_size))
+            }
+            FrameFormat::Yv12 => {
+                (frame[..luma_size].to_vec(), plane(luma_size + chroma_size), plane(luma_size))
+            }
+            FrameFormat::Nv12 => (frame[..luma_size].to_vec(), interleaved(0), interleaved(1)),
+            FrameFormat::Nv21 => (frame[..luma_size].to_vec(), interleaved(1), interleaved(0)),
+            FrameFormat::Yuyv => {
+                let mut luma = Vec::with_capacity(luma_size);
+                let (mut cb, mut cr) = (Vec::with_capacity(chroma_size), Vec::with_capacity(chroma_size));
+                for row in frame[..w * 4 * height].chunks(w * 4) {
+                    for (x, group) in row.chunks(4).enumerate() {
+                        luma.push(group[0]);
+                        if 2 * x + 1 < width {
+                            luma.push(group[2]);
+                        }
+                        cb.push(group[1] as f64);
+                        cr.push(group[3] as f64);
+                    }
+                }
+                (luma, cb, cr)
+            }
+        };
+
+        let (horizontal, vertical) = self.format.subsampling();
+        let (cosited_x, cosited_y) = self.siting.cosited();
+        if vertical {
+            cb = transpose(&resample_rows(&transpose(&cb, w, h), h, w, height,
+                                          |line, n| upsample(line, n, cosited_y, self.filter)), height, w);
+            cr = transpose(&resample_rows(&transpose(&cr, w, h), h, w, height,
+                                          |line, n| upsample(line, n, cosited_y, self.filter)), height, w);
+        }
+        if horizontal {
+            cb = resample_rows(&cb, w, height, width, |line, n| upsample(line, n, cosited_x, self.filter));
+            cr = resample_rows(&cr, w, height, width, |line, n| upsample(line, n, cosited_x, self.filter));
+        }
+
+        let ((y_scale, y_offset), (c_scale, c_offset)) = self.encoding.quantization();
+        (0..luma_size).map(|i| {
+            YCbCr::with_components((luma[i] as f64 - y_offset) / y_scale,
+                                   (cb[i] - c_offset) / c_scale,
+                                   (cr[i] - c_offset) / c_scale).to_rgb(&self.encoding)
+        }).collect()
+    }
+}
+
+/// Round a sample to an 8 bit code value.
+#[inline]
+fn code(x: f64) -> u8 {
+    clamp(x.round(), 0.0, 255.0) as u8
+}
+
+/// Resample each row of a plane to a new width.
+fn resample_rows<M>(plane: &[f64], width: usize, height: usize, new_width: usize, resample: M) -> Vec<f64>
+        where M: Fn(&[f64], usize) -> Vec<f64> {
+    let mut out = Vec::with_capacity(new_width * height);
+    for row in plane.chunks(width).take(height) {
+        out.extend(resample(row, new_width));
+    }
+    out
+}
+
+/// Transpose a plane, so that columns can be resampled as rows.
+fn transpose(plane: &[f64], width: usize, height: usize) -> Vec<f64> {
+    let mut out = Vec::with_capacity(plane.len());
+    for x in 0..width {
+        for y in 0..height {
+            out.push(plane[y * width + x]);
+        }
+    }
+    out
+}
+
+/// Halve the resolution of a line of samples.
+fn downsample(line: &[f64], len: usize, cosited: bool, filter: ChromaFilter) -> Vec<f64> {
+    let last = line.len() as isize - 1;
+    let sample = |i: isize| line[clamp(i, 0, last) as usize];
+    (0..len as isize).map(|i| match (filter, cosited) {
+        (ChromaFilter::Nearest, _) => sample(2 * i),
+        (ChromaFilter::Linear, false) => (sample(2 * i) + sample(2 * i + 1)) / 2.0,
+        (ChromaFilter::Linear, true) => {
+            (sample(2 * i - 1) + 2.0 * sample(2 * i) + sample(2 * i + 1)) / 4.0
+        }
+    }).collect()
+}
+
+/// Double the resolution of a line of samples, to the given length.
+fn upsample(line: &[f64], len: usize, cosited: bool, filter: ChromaFilter) -> Vec<f64> {
+    let last = line.len() as isize - 1;
+    let sample = |i: isize| line[clamp(i, 0, last) as usize];
+    (0..len).map(|x| match filter {
+        ChromaFilter::Nearest => line[x / 2],
+        ChromaFilter::Linear => {
+            let u = if cosited { x as f64 / 2.0 } else { (x as f64 - 0.5) / 2.0 };
+            let i = u.floor();
+            let t = u - i;
+            sample(i as isize) * (1.0 - t) + sample(i as isize + 1) * t
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, YCbCrEncoding, YCbCrMatrix, YCbCrRange};
+
+    const FORMATS: [FrameFormat; 7] = [
+        FrameFormat::I444, FrameFormat::I422, FrameFormat::I420, FrameFormat::Yv12,
+        FrameFormat::Nv12, FrameFormat::Nv21, FrameFormat::Yuyv];
+
+    fn bt709() -> YCbCrEncoding {
+        YCbCrEncoding::new(YCbCrMatrix::Bt709, YCbCrRange::Limited, 8)
+    }
+
+    #[test]
+    fn frame_sizes() {
+        assert_eq!(FrameFormat::I420.frame_size(4, 2), 8 + 2 * 2);
+        assert_eq!(FrameFormat::Nv12.frame_size(5, 3), 15 + 2 * 3 * 2);
+        assert_eq!(FrameFormat::I422.frame_size(4, 2), 8 + 2 * 4);
+        assert_eq!(FrameFormat::I444.frame_size(3, 3), 27);
+        assert_eq!(FrameFormat::Yuyv.frame_size(3, 2), 16);
+    }
+
+    #[test]
+    fn layouts_order_planes() {
+        let pixels = [Rgb::with_components(255u8, 0, 0); 4];
+        let red: YCbCr<u8> = YCbCr::from_rgb(&pixels[0], &bt709());
+        let (y, cb, cr) = red.components();
+        let frame = |format| FrameConverter::new(format, bt709()).from_rgb(&pixels, 2);
+        assert_eq!(frame(FrameFormat::I420), vec![y, y, y, y, cb, cr]);
+        assert_eq!(frame(FrameFormat::Yv12), vec![y, y, y, y, cr, cb]);
+        assert_eq!(frame(FrameFormat::Nv12), vec![y, y, y, y, cb, cr]);
+        assert_eq!(frame(FrameFormat::Nv21), vec![y, y, y, y, cr, cb]);
+        assert_eq!(frame(FrameFormat::I422), vec![y, y, y, y, cb, cb, cr, cr]);
+        assert_eq!(frame(FrameFormat::Yuyv), vec![y, cb, y, cr, y, cb, y, cr]);
+    }
+
+    #[test]
+    fn flat_images_round_trip_in_every_format() {
+        let col = Rgb::with_components(40u8, 120, 200);
+        let pixels = vec![col; 5 * 3];
+        for &format in FORMATS.iter() {
+            for &siting in [ChromaSiting::Center, ChromaSiting::Left, ChromaSiting::TopLeft].iter() {
+                for &filter in [ChromaFilter::Nearest, ChromaFilter::Linear].iter() {
+                    let mut converter = FrameConverter::new(format, bt709());
+                    converter.set_siting(siting);
+                    converter.set_filter(filter);
+                    let frame = converter.from_rgb(&pixels, 5);
+                    assert_eq!(frame.len(), format.frame_size(5, 3));
+                    for pixel in converter.to_rgb(&frame, 5, 3) {
+                        assert!((pixel.r() as i32 - 40).abs() <= 1);
+                        assert!((pixel.g() as i32 - 120).abs() <= 1);
+                        assert!((pixel.b() as i32 - 200).abs() <= 1);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn downsampling_follows_siting() {
+        let line = [0.0, 4.0, 8.0, 12.0];
+        assert_eq!(downsample(&line, 2, false, ChromaFilter::Linear), vec![2.0, 10.0]);
+        assert_eq!(downsample(&line, 2, true, ChromaFilter::Linear), vec![1.0, 8.0]);
+        assert_eq!(downsample(&line, 2, true, ChromaFilter::Nearest), vec![0.0, 8.0]);
+    }
+
+    #[test]
+    fn upsampling_follows_siting() {
+        let line = [0.0, 8.0];
+        assert_eq!(upsample(&line, 4, true, ChromaFilter::Linear), vec![0.0, 4.0, 8.0, 8.0]);
+        assert_eq!(upsample(&line, 4, false, ChromaFilter::Linear), vec![0.0, 2.0, 6.0, 8.0]);
+        assert_eq!(upsample(&line, 4, false, ChromaFilter::Nearest), vec![0.0, 0.0, 8.0, 8.0]);
+    }
+
+    #[test]
+    fn empty_images_give_empty_results() {
+        for &format in FORMATS.iter() {
+            let converter = FrameConverter::new(format, bt709());
+            assert!(converter.from_rgb(&[], 0).is_empty());
+            assert!(converter.from_rgb(&[], 4).is_empty());
+            assert!(converter.to_rgb(&[], 0, 3).is_empty());
+            assert!(converter.to_rgb(&[], 4, 0).is_empty());
+        }
+    }
+
+    #[test]
+    fn chroma_is_rounded_once() {
+        // Full range Cb codes of 128 and 128.5 average to 128.25, but would
+        // give 129 if each were rounded before averaging.
+        let encoding = YCbCrEncoding::new(YCbCrMatrix::Bt709, YCbCrRange::Full, 8);
+        let pixels = [Rgb::with_components(0u8, 0, 0), Rgb::with_components(0u8, 0, 1)];
+        let mut converter = FrameConverter::new(FrameFormat::I422, encoding);
+        converter.set_siting(ChromaSiting::Center);
+        let frame = converter.from_rgb(&pixels, 2);
+        assert_eq!(frame[2], 128);
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp::Ordering;
+
+use super::{wrap_hue, Channel, Hsl, Lab, Oklab, Rgb, Rgba};
+
+/// Chroma or saturation values below this are treated as achromatic, and
+/// their hue is ignored when interpolating.
+const ACHROMATIC_THRESHOLD: f64 = 1e-4;
+
+/// The direction taken around the hue circle when interpolating between
+/// two hues. These mirror the hue interpolation methods in CSS Color 4.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum HueInterpolation {
+    /// Take the shortest arc between the two hues.
+    Shorter,
+
+    /// Take the longest arc between the two hues.
+    Longer,
+
+    /// Always move towards increasing hue angles.
+    Increasing,
+
+    /// Always move towards decreasing hue angles.
+    Decreasing
+}
+
+/// The color space in which colors are interpolated.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum InterpolationSpace {
+    /// Interpolate the sRGB encoded channels directly.
+    Srgb,
+
+    /// Interpolate the channels in linear light.
+    LinearRgb,
+
+    /// Interpolate the hue, saturation and lightness of the `Hsl` representation.
+    Hsl(HueInterpolation),
+
+    /// Interpolate in CIE `Lab`.
+    Lab,
+
+    /// Interpolate the lightness, chroma and hue of CIE `Lab`.
+    Lch(HueInterpolation),
+
+    /// Interpolate in `Oklab`.
+    Oklab,
+
+    /// Interpolate the lightness, chroma and hue of `Oklab`.
+    Oklch(HueInterpolation)
+}
+
+impl InterpolationSpace {
+    /// Returns the hue interpolation method, if this is a cylindrical space.
+    fn hue_interpolation(self) -> Option<HueInterpolation> {
+        match self {
+            InterpolationSpace::Hsl(method) |
+            InterpolationSpace::Lch(method) |
+            InterpolationSpace::Oklch(method) => Some(method),
+            _ => None
+        }
+    }
+
+    /// Convert an sRGB color to the components of this space. Cylindrical
+    /// spaces store their chroma (or saturation) second, and their hue last.
+    fn to_components(self, rgb: &Rgb<f64>) -> [f64; 3] {
+        match self {
+            InterpolationSpace::Srgb => rgb.to_slice(),
+            InterpolationSpace::LinearRgb => rgb.to_linear().to_slice(),
+            InterpolationSpace::Hsl(_) => {
+                let (h, s, l) = Hsl::from_rgb(rgb).components();
+                [l, s, h]
+            }
+            InterpolationSpace::Lab => {
+                let (l, a, b) = Lab::from_rgb(rgb).components();
+                [l, a, b]
+            }
+            InterpolationSpace::Lch(_) => {
+                let lab = Lab::from_rgb(rgb);
+                [lab.l(), lab.chroma(), lab.hue()]
+            }
+            InterpolationSpace::Oklab => {
+                let (l, a, b) = Oklab::from_rgb(rgb).components();
+                [l, a, b]
+            }
+            InterpolationSpace::Oklch(_) => {
+                let lab = Oklab::from_rgb(rgb);
+                [lab.l(), lab.chroma(), lab.hue()]
+            }
+        }
+    }
+
+    /// Convert the components of this space back to an sRGB color.
+    fn to_rgb(self, c: [f64; 3]) -> Rgb<f64> {
+        match self {
+            InterpolationSpace::Srgb => Rgb::from_slice(c),
+            InterpolationSpace::LinearRgb => Rgb::from_slice(c).to_srgb(),
+            InterpolationSpace::Hsl(_) => Hsl::with_components(c[2], c[1], c[0]).to_rgb(),
+            InterpolationSpace::Lab => Lab::with_components(c[0], c[1], c[2]).to_rgb(),
+            InterpolationSpace::Lch(_) => Lab::from_lch(c[0], c[1], c[2]).to_rgb(),
+            InterpolationSpace::Oklab => Oklab::with_components(c[0], c[1], c[2]).to_rgb(),
+            InterpolationSpace::Oklch(_) => Oklab::from_lch(c[0], c[1], c[2]).to_rgb()
+        }
+    }
+
+    /// Multiply each non-hue component by `alpha`.
+    fn premultiply(self, c: [f64; 3], alpha: f64) -> [f64; 3] {
+        match self.hue_interpolation() {
+            Some(_) => [c[0] * alpha, c[1] * alpha, c[2]],
+            None => [c[0] * alpha, c[1] * alpha, c[2] * alpha]
+        }
+    }
+
+    /// Interpolate between two sets of components of this space.
+    fn interpolate(self, a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+        let mut result = [lerp(a[0], b[0], t), lerp(a[1], b[1], t), lerp(a[2], b[2], t)];
+        if let Some(method) = self.hue_interpolation() {
+            let (mut h1, mut h2) = (a[2], b[2]);
+            if a[1].abs() < ACHROMATIC_THRESHOLD { h1 = h2; }
+            if b[1].abs() < ACHROMATIC_THRESHOLD { h2 = h1; }
+            let delta = h2 - h1;
+            match method {
+                HueInterpolation::Shorter => {
+                    if delta > 180.0 { h1 += 360.0; } else if delta < -180.0 { h2 += 360.0; }
+                }
+                HueInterpolation::Longer => {
+                    if 0.0 < delta && delta < 180.0 {
+                        h1 += 360.0;
+                    } else if -180.0 < delta && delta <= 0.0 {
+                        h2 += 360.0;
+                    }
+                }
+                HueInterpolation::Increasing => if h2 < h1 { h2 += 360.0; },
+                HueInterpolation::Decreasing => if h1 < h2 { h1 += 360.0; }
+            }
+            result[2] = wrap_hue(lerp(h1, h2, t));
+        }
+        result
+    }
+}
+
+impl Default for InterpolationSpace {
+    /// Interpolate in Oklab, which is the default for CSS gradients.
+    fn default() -> InterpolationSpace { InterpolationSpace::Oklab }
+}
+
+/// A color which can be interpolated in any `InterpolationSpace`.
+pub trait GradientColor: Copy {
+    /// Interpolate between this color and another color, where a `t` of 0
+    /// gives this color and a `t` of 1 gives the other color.
+    fn interpolate(&self, other: &Self, t: f64, space: InterpolationSpace) -> Self;
+}
+
+impl<T: Channel> GradientColor for Rgb<T> {
+    fn interpolate(&self, other: &Rgb<T>, t: f64, space: InterpolationSpace) -> Rgb<T> {
+        let a = space.to_components(&self.convert());
+        let b = space.to_components(&other.convert());
+        space.to_rgb(space.interpolate(a, b, t)).convert()
+    }
+}
+
+impl<T: Channel> GradientColor for Rgba<T> {
+    /// Interpolate between two colors using premultiplied alpha, so that
+    /// transparent stops do not tint the colors next to them.
+    fn interpolate(&self, other: &Rgba<T>, t: f64, space: InterpolationSpace) -> Rgba<T> {
+        let (alpha_a, alpha_b) = (self.a().to_float::<f64>(), other.a().to_float::<f64>());
+        let a = space.premultiply(space.to_components(&self.rgb().convert()), alpha_a);
+        let b = space.premultiply(space.to_components(&other.rgb().convert()), alpha_b);
+        let alpha = lerp(alpha_a, alpha_b, t);
+        let mut result = space.interpolate(a, b, t);
+        if alpha != 0.0 {
+            result = space.premultiply(result, 1.0 / alpha);
+        }
+        space.to_rgb(result).convert().rgba(T::from_float(alpha))
+    }
+}
+
+/// A gradient made up of any number of color stops at arbitrary positions.
+///
+/// ```rust
+/// use simplecolor::{Gradient, Rgb};
+///
+/// let gradient = Gradient::new(vec![(0.0, Rgb::with_components(255u8, 0, 0)),
+///                                   (1.0, Rgb::with_components(0u8, 0, 255))]);
+/// let purple = gradient.sample(0.5);
+/// let steps: Vec<_> = gradient.sample_n(10).collect();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient<C> {
+    /// Color stops, sorted by position
+    stops: Vec<(f64, C)>,
+
+    /// The space colors are interpolated in
+    space: InterpolationSpace
+}
+
+impl<C: GradientColor> Gradient<C> {
+    /// Create a gradient from a list of `(position, color)` stops, which
+    /// interpolates in the default `InterpolationSpace`. Stops which share
+    /// a position create a hard edge. Panics if there are no stops.
+    pub fn new(mut stops: Vec<(f64, C)>) -> Gradient<C> {
+        assert!(!stops.is_empty());
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Gradient {
+            stops: stops,
+            space: InterpolationSpace::default()
+        }
+    }
+
+    /// Create a gradient from a list of colors, spaced evenly between
+    /// positions 0 and 1. Panics if there are no colors.
+    pub fn evenly_spaced(colors: &[C]) -> Gradient<C> {
+        let last = if colors.len() > 1 { (colors.len() - 1) as f64 } else { 1.0 };
+        Gradient::new(colors.iter()
+                            .enumerate()
+                            .map(|(i, c)| (i as f64 / last, *c))
+                            .collect())
+    }
+
+    /// Returns the color stops, sorted by position.
+    #[inline]
+    pub fn stops(&self) -> &[(f64, C)] { &self.stops }
+
+    /// Add a color stop. A stop at the same position as an existing stop is
+    /// placed after it.
+    pub fn add_stop(&mut self, position: f64, color: C) {
+        let index = self.stops.iter().position(|&(p, _)| p > position).unwrap_or(self.stops.len());
+        self.stops.insert(index, (position, color));
+    }
+
+    /// Returns the space colors are interpolated in.
+    #[inline]
+    pub fn space(&self) -> InterpolationSpace { self.space }
+
+    /// Set the space colors are interpolated in.
+    #[inline]
+    pub fn set_space(&mut self, space: InterpolationSpace) { self.space = space; }
+
+    /// Sample the gradient at a position. Positions before the first stop or
+    /// after the last stop take the color of that stop.
+    pub fn sample(&self, t: f64) -> C {
+        match self.stops.iter().position(|&(p, _)| p > t) {
+            Some(0) => self.stops[0].1,
+            Some(i) => {
+                let (p0, ref c0) = self.stops[i - 1];
+                let (p1, ref c1) = self.stops[i];
+                c0.interpolate(c1, (t - p0) / (p1 - p0), self.space)
+            }
+            None => self.stops[self.stops.len() - 1].1
+        }
+    }
+
+    /// Returns an iterator over `n` colors, sampled at evenly spaced
+    /// positions from the first stop to the last stop.
+    pub fn sample_n(&self, n: usize) -> Samples<'_, C> {
+        Samples {
+            gradient: self,
+            index: 0,
+            count: n
+        }
+    }
+}
+
+/// An iterator over evenly spaced samples of a `Gradient`. Created by
+/// `Gradient::sample_n`.
+#[derive(Clone, Debug)]
+pub struct Samples<'a, C: 'a> {
+    /// The gradient being sampled
+    gradient: &'a Gradient<C>,
+
+    /// The index of the next sample
+    index: usize,
+
+    /// The total number of samples
+    count: usize
+}
+
+impl<'a, C: GradientColor> Iterator for Samples<'a, C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<C> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let stops = self.gradient.stops();
+        let (start, end) = (stops[0].0, stops[stops.len() - 1].0);
+        let t = if self.count == 1 {
+            start
+        } else {
+            lerp(start, end, self.index as f64 / (self.count - 1) as f64)
+        };
+        self.index += 1;
+        Some(self.gradient.sample(t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, C: GradientColor> ExactSizeIterator for Samples<'a, C> { }
+
+#[inline]
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, Rgba};
+
+    #[test]
+    fn endpoints_are_the_stop_colors() {
+        let red = Rgb::with_components(255u8, 0, 0);
+        let blue = Rgb::with_components(0u8, 0, 255);
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, blue)]);
+        assert_eq!(gradient.sample(-1.0), red);
+        assert_eq!(gradient.sample(0.0), red);
+        assert_eq!(gradient.sample(1.0), blue);
+        assert_eq!(gradient.sample(2.0), blue);
+    }
+
+    #[test]
+    fn srgb_midpoint_is_the_channel_average() {
+        let mut gradient = Gradient::evenly_spaced(&[Rgb::with_components(0.0f64, 0.2, 1.0),
+                                                     Rgb::with_components(1.0f64, 0.4, 0.0)]);
+        gradient.set_space(InterpolationSpace::Srgb);
+        let (r, g, b) = gradient.sample(0.5).components();
+        assert!((r - 0.5).abs() < 1e-9);
+        assert!((g - 0.3).abs() < 1e-9);
+        assert!((b - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hard_edges_at_shared_positions() {
+        let black = Rgb::with_components(0u8, 0, 0);
+        let white = Rgb::with_components(255u8, 255, 255);
+        let gradient = Gradient::new(vec![(0.0, black), (0.5, black), (0.5, white), (1.0, white)]);
+        assert_eq!(gradient.sample(0.49), black);
+        assert_eq!(gradient.sample(0.5), white);
+    }
+
+    #[test]
+    fn hue_interpolation_methods() {
+        let red = Rgb::with_components(1.0f64, 0.0, 0.0);
+        let blue = Rgb::with_components(0.0f64, 0.0, 1.0);
+        let hue_at_midpoint = |method| {
+            let mut gradient = Gradient::evenly_spaced(&[red, blue]);
+            gradient.set_space(InterpolationSpace::Hsl(method));
+            ::Hsl::from_rgb(&gradient.sample(0.5)).h().round()
+        };
+        assert_eq!(hue_at_midpoint(HueInterpolation::Shorter), 300.0);
+        assert_eq!(hue_at_midpoint(HueInterpolation::Longer), 120.0);
+        assert_eq!(hue_at_midpoint(HueInterpolation::Increasing), 120.0);
+        assert_eq!(hue_at_midpoint(HueInterpolation::Decreasing), 300.0);
+    }
+
+    #[test]
+    fn transparent_stops_do_not_tint() {
+        let clear_black = Rgba::with_components(0.0f64, 0.0, 0.0, 0.0);
+        let white = Rgba::with_components(1.0f64, 1.0, 1.0, 1.0);
+        let mut gradient = Gradient::evenly_spaced(&[clear_black, white]);
+        gradient.set_space(InterpolationSpace::Srgb);
+        let mid = gradient.sample(0.5);
+        assert!((mid.r() - 1.0).abs() < 1e-9);
+        assert!((mid.a() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sample_n_spans_the_stops() {
+        let gradient = Gradient::new(vec![(0.0, Rgb::with_components(0u8, 0, 0)),
+                                          (1.0, Rgb::with_components(255u8, 255, 255))]);
+        let samples: Vec<_> = gradient.sample_n(5).collect();
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples[0], Rgb::with_components(0, 0, 0));
+        assert_eq!(samples[4], Rgb::with_components(255, 255, 255));
+    }
+}
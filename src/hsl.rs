@@ -0,0 +1,130 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, wrap_hue, Channel, Rgb};
+
+/// A color in the Hsl color space, a cylindrical representation of
+/// sRGB. The hue is measured in degrees between 0 and 360, and the
+/// saturation and lightness are between 0 and 1.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Hsl<F: Float> {
+    /// Hue component
+    h: F,
+
+    /// Saturation component
+    s: F,
+
+    /// Lightness component
+    l: F
+}
+
+impl<F: Float> Hsl<F> {
+    /// Construct an Hsl color piecewise from individual components. The
+    /// hue is wrapped to lie between 0 and 360.
+    pub fn with_components(h: F, s: F, l: F) -> Hsl<F> {
+        Hsl {
+            h: wrap_hue(h),
+            s: s,
+            l: l
+        }
+    }
+
+    /// Returns the hue component in degrees.
+    #[inline]
+    pub fn h(&self) -> F { self.h }
+
+    /// Returns the saturation component.
+    #[inline]
+    pub fn s(&self) -> F { self.s }
+
+    /// Returns the lightness component.
+    #[inline]
+    pub fn l(&self) -> F { self.l }
+
+    /// Return each component in a 3-element tuple. Useful for destructuring.
+    pub fn components(&self) -> (F, F, F) {
+        (self.h, self.s, self.l)
+    }
+}
+
+impl<F: Channel + Float> Hsl<F> {
+    /// Convert an sRGB encoded color to Hsl.
+    pub fn from_rgb(rgb: &Rgb<F>) -> Hsl<F> {
+        let (r, g, b) = rgb.components();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / float(2.0);
+        let delta = max - min;
+        if delta == F::zero() {
+            return Hsl::with_components(F::zero(), F::zero(), l);
+        }
+
+        let s = delta / (F::one() - (l * float(2.0) - F::one()).abs());
+        let h = if max == r {
+            (g - b) / delta
+        } else if max == g {
+            (b - r) / delta + float(2.0)
+        } else {
+            (r - g) / delta + float(4.0)
+        };
+        Hsl::with_components(h * float(60.0), s, l)
+    }
+
+    /// Convert this color to an sRGB encoded color.
+    pub fn to_rgb(&self) -> Rgb<F> {
+        let chroma = (F::one() - (self.l * float(2.0) - F::one()).abs()) * self.s;
+        let sector = self.h / float(60.0);
+        let x = chroma * (F::one() - (sector % float(2.0) - F::one()).abs());
+        let m = self.l - chroma / float(2.0);
+        let z = F::zero();
+        let (r, g, b) = match sector.floor().to_u8().unwrap_or(0) {
+            0 => (chroma, x, z),
+            1 => (x, chroma, z),
+            2 => (z, chroma, x),
+            3 => (z, x, chroma),
+            4 => (x, z, chroma),
+            _ => (chroma, z, x)
+        };
+        Rgb::with_components(r + m, g + m, b + m)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn primaries_have_expected_hues() {
+        assert_eq!(Hsl::from_rgb(&Rgb::with_components(1.0f64, 0.0, 0.0)).h(), 0.0);
+        assert_eq!(Hsl::from_rgb(&Rgb::with_components(0.0f64, 1.0, 0.0)).h(), 120.0);
+        assert_eq!(Hsl::from_rgb(&Rgb::with_components(0.0f64, 0.0, 1.0)).h(), 240.0);
+    }
+
+    #[test]
+    fn round_trip_through_rgb() {
+        let col = Rgb::with_components(0.25f64, 0.5, 0.75);
+        let (r, g, b) = Hsl::from_rgb(&col).to_rgb().components();
+        assert!((r - 0.25).abs() < 1e-9);
+        assert!((g - 0.5).abs() < 1e-9);
+        assert!((b - 0.75).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,1648 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{clamp, mat3_apply, mat3_inverse, mat3_mul, AdaptationMethod, Channel, ChromaticAdaptation,
+            Cmyk, Lab, Matrix3, Rgb, RgbSpace, TransferFunction, Xyz};
+
+/// The D50 illuminant of the profile connection space, as rounded to
+/// s15Fixed16 numbers by the ICC specification.
+const PCS_WHITE: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// An error encountered while reading an ICC profile or converting colors
+/// with it.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum IccError {
+    /// The data ends before the end of a structure it describes.
+    UnexpectedEnd,
+
+    /// The header does not contain the `acsp` profile file signature.
+    InvalidSignature,
+
+    /// A tag has a type which cannot be used for that tag.
+    UnsupportedTagType([u8; 4]),
+
+    /// A tag is internally inconsistent.
+    Malformed(&'static str),
+
+    /// The profile lacks the tags needed to convert colors in the requested
+    /// direction.
+    MissingTags,
+
+    /// A color has a different number of channels than the color space of
+    /// the profile.
+    ChannelMismatch
+}
+
+impl fmt::Display for IccError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IccError::UnsupportedTagType(sig) => {
+                write!(f, "unsupported ICC tag type '{}'", String::from_utf8_lossy(&sig))
+            }
+            IccError::Malformed(what) => write!(f, "malformed ICC profile: {}", what),
+            _ => f.write_str(self.description())
+        }
+    }
+}
+
+impl Error for IccError {
+    fn description(&self) -> &str {
+        match *self {
+            IccError::UnexpectedEnd => "ICC profile data ends unexpectedly",
+            IccError::InvalidSignature => "not an ICC profile",
+            IccError::UnsupportedTagType(_) => "unsupported ICC tag type",
+            IccError::Malformed(_) => "malformed ICC profile",
+            IccError::MissingTags => "ICC profile lacks the tags needed for conversion",
+            IccError::ChannelMismatch => "color does not match the ICC profile's color space"
+        }
+    }
+}
+
+/// The color space of the device side or connection side of a profile.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum IccColorSpace {
+    /// CIE XYZ
+    Xyz,
+
+    /// CIE Lab
+    Lab,
+
+    /// RGB
+    Rgb,
+
+    /// Single channel grey
+    Gray,
+
+    /// CMYK
+    Cmyk,
+
+    /// Any other color space, by signature.
+    Other([u8; 4])
+}
+
+impl IccColorSpace {
+    fn from_signature(sig: [u8; 4]) -> IccColorSpace {
+        match &sig {
+            b"XYZ " => IccColorSpace::Xyz,
+            b"Lab " => IccColorSpace::Lab,
+            b"RGB " => IccColorSpace::Rgb,
+            b"GRAY" => IccColorSpace::Gray,
+            b"CMYK" => IccColorSpace::Cmyk,
+            _ => IccColorSpace::Other(sig)
+        }
+    }
+
+    /// Returns the four byte signature of the color space.
+    pub fn signature(self) -> [u8; 4] {
+        match self {
+            IccColorSpace::Xyz => *b"XYZ ",
+            IccColorSpace::Lab => *b"Lab ",
+            IccColorSpace::Rgb => *b"RGB ",
+            IccColorSpace::Gray => *b"GRAY",
+            IccColorSpace::Cmyk => *b"CMYK",
+            IccColorSpace::Other(sig) => sig
+        }
+    }
+
+    /// Returns the number of channels in the color space, if known.
+    pub fn channels(self) -> Option<usize> {
+        match self {
+            IccColorSpace::Gray => Some(1),
+            IccColorSpace::Xyz | IccColorSpace::Lab | IccColorSpace::Rgb => Some(3),
+            IccColorSpace::Cmyk => Some(4),
+            IccColorSpace::Other(_) => None
+        }
+    }
+}
+
+/// The class of device a profile describes.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum IccDeviceClass {
+    /// An input device, such as a scanner or camera.
+    Input,
+
+    /// A display device.
+    Display,
+
+    /// An output device, such as a printer.
+    Output,
+
+    /// A direct conversion between two device color spaces.
+    DeviceLink,
+
+    /// A conversion between a color space and the connection space.
+    ColorSpace,
+
+    /// An effect applied within the connection space.
+    Abstract,
+
+    /// A list of named colors.
+    NamedColor,
+
+    /// Any other class, by signature.
+    Other([u8; 4])
+}
+
+impl IccDeviceClass {
+    fn from_signature(sig: [u8; 4]) -> IccDeviceClass {
+        match &sig {
+            b"scnr" => IccDeviceClass::Input,
+            b"mntr" => IccDeviceClass::Display,
+            b"prtr" => IccDeviceClass::Output,
+            b"link" => IccDeviceClass::DeviceLink,
+            b"spac" => IccDeviceClass::ColorSpace,
+            b"abst" => IccDeviceClass::Abstract,
+            b"nmcl" => IccDeviceClass::NamedColor,
+            _ => IccDeviceClass::Other(sig)
+        }
+    }
+
+    /// Returns the four byte signature of the device class.
+    pub fn signature(self) -> [u8; 4] {
+        match self {
+            IccDeviceClass::Input => *b"scnr",
+            IccDeviceClass::Display => *b"mntr",
+            IccDeviceClass::Output => *b"prtr",
+            IccDeviceClass::DeviceLink => *b"link",
+            IccDeviceClass::ColorSpace => *b"spac",
+            IccDeviceClass::Abstract => *b"abst",
+            IccDeviceClass::NamedColor => *b"nmcl",
+            IccDeviceClass::Other(sig) => sig
+        }
+    }
+}
+
+/// The rendering intent used to choose between the conversions in a
+/// profile.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RenderingIntent {
+    /// Compress the whole gamut for a pleasing result.
+    Perceptual,
+
+    /// Reproduce in-gamut colors exactly, relative to the media white.
+    RelativeColorimetric,
+
+    /// Preserve saturation, for business graphics.
+    Saturation,
+
+    /// Reproduce in-gamut colors exactly, including the media white.
+    AbsoluteColorimetric
+}
+
+impl RenderingIntent {
+    fn from_number(n: u32) -> RenderingIntent {
+        match n {
+            1 => RenderingIntent::RelativeColorimetric,
+            2 => RenderingIntent::Saturation,
+            3 => RenderingIntent::AbsoluteColorimetric,
+            _ => RenderingIntent::Perceptual
+        }
+    }
+
+    /// Returns the number of the intent in a profile header, which is also
+    /// the suffix of the `A2Bn` and `B2An` tags it uses.
+    pub fn number(self) -> u32 {
+        match self {
+            RenderingIntent::Perceptual => 0,
+            RenderingIntent::RelativeColorimetric => 1,
+            RenderingIntent::Saturation => 2,
+            RenderingIntent::AbsoluteColorimetric => 3
+        }
+    }
+
+    /// The index of the lookup table tags used by this intent.
+    fn table(self) -> usize {
+        match self {
+            RenderingIntent::Perceptual => 0,
+            RenderingIntent::Saturation => 2,
+            _ => 1
+        }
+    }
+}
+
+/// The fixed size header at the start of an ICC profile.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IccHeader {
+    /// Profile size in bytes
+    size: u32,
+
+    /// Preferred color management module
+    cmm: [u8; 4],
+
+    /// Major, minor and bug fix version
+    version: (u8, u8, u8),
+
+    /// Device class
+    class: IccDeviceClass,
+
+    /// Device color space
+    color_space: IccColorSpace,
+
+    /// Profile connection space
+    pcs: IccColorSpace,
+
+    /// Default rendering intent
+    rendering_intent: RenderingIntent,
+
+    /// Illuminant of the connection space
+    illuminant: Xyz<f64>,
+
+    /// Profile creator
+    creator: [u8; 4]
+}
+
+impl IccHeader {
+    /// Returns the size of the profile in bytes, as recorded in the header.
+    #[inline]
+    pub fn size(&self) -> u32 { self.size }
+
+    /// Returns the signature of the preferred color management module.
+    #[inline]
+    pub fn cmm(&self) -> [u8; 4] { self.cmm }
+
+    /// Returns the major, minor and bug fix version of the profile format.
+    #[inline]
+    pub fn version(&self) -> (u8, u8, u8) { self.version }
+
+    /// Returns the class of device the profile describes.
+    #[inline]
+    pub fn class(&self) -> IccDeviceClass { self.class }
+
+    /// Returns the color space of the device.
+    #[inline]
+    pub fn color_space(&self) -> IccColorSpace { self.color_space }
+
+    /// Returns the profile connection space, which is XYZ or Lab.
+    #[inline]
+    pub fn pcs(&self) -> IccColorSpace { self.pcs }
+
+    /// Returns the default rendering intent.
+    #[inline]
+    pub fn rendering_intent(&self) -> RenderingIntent { self.rendering_intent }
+
+    /// Returns the illuminant of the profile connection space, normally D50.
+    #[inline]
+    pub fn illuminant(&self) -> Xyz<f64> { self.illuminant }
+
+    /// Returns the signature of the profile's creator.
+    #[inline]
+    pub fn creator(&self) -> [u8; 4] { self.creator }
+}
+
+/// A one dimensional tone curve from an ICC profile, mapping values
+/// between zero and one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IccCurve {
+    /// The identity curve.
+    Identity,
+
+    /// A pure power law with the given exponent.
+    Gamma(f64),
+
+    /// Evenly spaced samples, linearly interpolated.
+    Table(Vec<f64>),
+
+    /// One of the five ICC parametric curve functions, numbered 0 to 4,
+    /// with its parameters g, a, b, c, d, e and f. Unused parameters are
+    /// zero.
+    Parametric(u16, [f64; 7])
+}
+
+impl IccCurve {
+    /// Evaluate the curve. The input and output are clamped between zero
+    /// and one. An empty table is treated as the identity, and a table of
+    /// one entry as a constant.
+    pub fn eval(&self, x: f64) -> f64 {
+        let x = clamp(x, 0.0, 1.0);
+        let y = match *self {
+            IccCurve::Identity => x,
+            IccCurve::Gamma(gamma) => x.powf(gamma),
+            IccCurve::Table(ref table) if table.is_empty() => x,
+            IccCurve::Table(ref table) if table.len() == 1 => table[0],
+            IccCurve::Table(ref table) => {
+                let position = x * (table.len() - 1) as f64;
+                let i = (position.floor() as usize).min(table.len() - 2);
+                let t = position - i as f64;
+                table[i] * (1.0 - t) + table[i + 1] * t
+            }
+            IccCurve::Parametric(function, p) => {
+                let (g, a, b, c, d, e, f) = (p[0], p[1], p[2], p[3], p[4], p[5], p[6]);
+                let power = |x: f64| (a * x + b).max(0.0).powf(g);
+                match function {
+                    0 => x.powf(g),
+                    1 => if x >= -b / a { power(x) } else { 0.0 },
+                    2 => if x >= -b / a { power(x) + c } else { c },
+                    3 => if x >= d { power(x) } else { c * x },
+                    _ => if x >= d { power(x) + e } else { c * x + f }
+                }
+            }
+        };
+        clamp(y, 0.0, 1.0)
+    }
+
+    /// Evaluate the inverse of the curve, which must be monotonic.
+    pub fn eval_inverse(&self, y: f64) -> f64 {
+        let y = clamp(y, 0.0, 1.0);
+        match *self {
+            IccCurve::Identity => y,
+            IccCurve::Gamma(gamma) => y.powf(1.0 / gamma),
+            _ => {
+                let increasing = self.eval(1.0) >= self.eval(0.0);
+                let (mut low, mut high) = (0.0, 1.0);
+                for _ in 0..48 {
+                    let mid = (low + high) / 2.0;
+                    if (self.eval(mid) < y) == increasing { low = mid; } else { high = mid; }
+                }
+                (low + high) / 2.0
+            }
+        }
+    }
+}
+
+/// A multi-dimensional color lookup table, interpolated multilinearly.
+#[derive(Clone, Debug, PartialEq)]
+struct Clut {
+    /// Grid points along each input dimension
+    grid: Vec<usize>,
+
+    /// Number of output channels
+    outputs: usize,
+
+    /// Output values between zero and one, with the first input dimension
+    /// varying slowest
+    data: Vec<f64>
+}
+
+impl Clut {
+    fn eval(&self, input: &[f64]) -> Vec<f64> {
+        let dimensions = self.grid.len();
+        let mut base = 0;
+        let mut stride = vec![0; dimensions];
+        let mut fraction = vec![0.0; dimensions];
+        let mut step = self.outputs;
+        for i in (0..dimensions).rev() {
+            let position = clamp(input[i], 0.0, 1.0) * (self.grid[i] - 1) as f64;
+            let index = (position.floor() as usize).min(self.grid[i].saturating_sub(2));
+            fraction[i] = position - index as f64;
+            stride[i] = if self.grid[i] > 1 { step } else { 0 };
+            base += index * step;
+            step *= self.grid[i];
+        }
+
+        let mut out = vec![0.0; self.outputs];
+        for corner in 0..(1usize << dimensions) {
+            let (mut offset, mut weight) = (base, 1.0);
+            for i in 0..dimensions {
+                if corner & (1 << (dimensions - 1 - i)) != 0 {
+                    offset += stride[i];
+                    weight *= fraction[i];
+                } else {
+                    weight *= 1.0 - fraction[i];
+                }
+            }
+            if weight != 0.0 {
+                for (o, value) in out.iter_mut().zip(&self.data[offset..offset + self.outputs]) {
+                    *o += weight * value;
+                }
+            }
+        }
+        out
+    }
+}
+
+/// One processing element of a lookup table tag.
+#[derive(Clone, Debug, PartialEq)]
+enum LutStage {
+    /// A curve for each channel
+    Curves(Vec<IccCurve>),
+
+    /// A 3x3 matrix by rows, followed by an offset
+    Matrix([f64; 12]),
+
+    /// A color lookup table
+    Clut(Clut)
+}
+
+/// A lookup table based conversion from an ICC profile, read from an
+/// `mft1`, `mft2`, `mAB ` or `mBA ` tag. Inputs and outputs are encoded
+/// between zero and one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccLut {
+    /// Number of input channels
+    inputs: usize,
+
+    /// Number of output channels
+    outputs: usize,
+
+    /// Processing elements, in order
+    stages: Vec<LutStage>,
+
+    /// Whether Lab values use the 16 bit encoding of ICC version 2
+    legacy_lab: bool
+}
+
+impl IccLut {
+    /// Returns the number of input channels.
+    #[inline]
+    pub fn input_channels(&self) -> usize { self.inputs }
+
+    /// Returns the number of output channels.
+    #[inline]
+    pub fn output_channels(&self) -> usize { self.outputs }
+
+    /// Evaluate the lookup table. Panics if the input does not have
+    /// `input_channels` values.
+    pub fn eval(&self, input: &[f64]) -> Vec<f64> {
+        assert_eq!(input.len(), self.inputs);
+        let mut values = input.to_vec();
+        for stage in &self.stages {
+            values = match *stage {
+                LutStage::Curves(ref curves) => {
+                    values.iter().zip(curves).map(|(&x, curve)| curve.eval(x)).collect()
+                }
+                LutStage::Matrix(ref m) => {
+                    let v = [values[0], values[1], values[2]];
+                    vec![m[0] * v[0] + m[1] * v[1] + m[2] * v[2] + m[9],
+                         m[3] * v[0] + m[4] * v[1] + m[5] * v[2] + m[10],
+                         m[6] * v[0] + m[7] * v[1] + m[8] * v[2] + m[11]]
+                }
+                LutStage::Clut(ref clut) => clut.eval(&values)
+            };
+        }
+        values.iter().map(|&x| clamp(x, 0.0, 1.0)).collect()
+    }
+}
+
+/// A parsed ICC profile, version 2 or 4. Only the tags needed to convert
+/// colors are kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccProfile {
+    /// Profile header
+    header: IccHeader,
+
+    /// Media white point (`wtpt`)
+    white_point: Option<Xyz<f64>>,
+
+    /// Chromatic adaptation matrix (`chad`)
+    chromatic_adaptation: Option<Matrix3>,
+
+    /// Red, green and blue colorants (`rXYZ`, `gXYZ`, `bXYZ`)
+    colorants: Option<[Xyz<f64>; 3]>,
+
+    /// Red, green and blue (`rTRC`, `gTRC`, `bTRC`) or grey (`kTRC`) curves
+    tone_curves: Option<Vec<IccCurve>>,
+
+    /// `A2B0`, `A2B1` and `A2B2` tables
+    a_to_b: [Option<IccLut>; 3],
+
+    /// `B2A0`, `B2A1` and `B2A2` tables
+    b_to_a: [Option<IccLut>; 3]
+}
+
+impl IccProfile {
+    /// Parse a profile from its bytes.
+    pub fn parse(data: &[u8]) -> Result<IccProfile, IccError> {
+        if data.len() < 132 {
+            return Err(IccError::UnexpectedEnd);
+        }
+        if &data[36..40] != b"acsp" {
+            return Err(IccError::InvalidSignature);
+        }
+        let header = IccHeader {
+            size: try!(read_u32(data, 0)),
+            cmm: try!(read_signature(data, 4)),
+            version: (data[8], data[9] >> 4, data[9] & 0xf),
+            class: IccDeviceClass::from_signature(try!(read_signature(data, 12))),
+            color_space: IccColorSpace::from_signature(try!(read_signature(data, 16))),
+            pcs: IccColorSpace::from_signature(try!(read_signature(data, 20))),
+            rendering_intent: RenderingIntent::from_number(try!(read_u32(data, 64)) & 0xffff),
+            illuminant: try!(read_xyz_number(data, 68)),
+            creator: try!(read_signature(data, 80))
+        };
+
+        let mut profile = IccProfile {
+            header: header,
+            white_point: None,
+            chromatic_adaptation: None,
+            colorants: None,
+            tone_curves: None,
+            a_to_b: [None, None, None],
+            b_to_a: [None, None, None]
+        };
+        let (mut colorants, mut curves) = ([None, None, None], [None, None, None]);
+        let count = try!(read_u32(data, 128)) as usize;
+        for i in 0..count {
+            let entry = 132 + 12 * i;
+            let signature = try!(read_signature(data, entry));
+            let offset = try!(read_u32(data, entry + 4)) as usize;
+            let size = try!(read_u32(data, entry + 8)) as usize;
+            match offset.checked_add(size) {
+                Some(end) if end <= data.len() => {}
+                _ => return Err(IccError::UnexpectedEnd)
+            }
+            let tag = &data[offset..offset + size];
+            match &signature {
+                b"wtpt" => profile.white_point = Some(try!(read_xyz_tag(tag))),
+                b"chad" => profile.chromatic_adaptation = Some(try!(read_sf32_matrix(tag))),
+                b"rXYZ" => colorants[0] = Some(try!(read_xyz_tag(tag))),
+                b"gXYZ" => colorants[1] = Some(try!(read_xyz_tag(tag))),
+                b"bXYZ" => colorants[2] = Some(try!(read_xyz_tag(tag))),
+                b"rTRC" => curves[0] = Some(try!(read_curve(tag)).0),
+                b"gTRC" => curves[1] = Some(try!(read_curve(tag)).0),
+                b"bTRC" => curves[2] = Some(try!(read_curve(tag)).0),
+                b"kTRC" => profile.tone_curves = Some(vec![try!(read_curve(tag)).0]),
+                b"A2B0" => profile.a_to_b[0] = Some(try!(read_lut(tag))),
+                b"A2B1" => profile.a_to_b[1] = Some(try!(read_lut(tag))),
+                b"A2B2" => profile.a_to_b[2] = Some(try!(read_lut(tag))),
+                b"B2A0" => profile.b_to_a[0] = Some(try!(read_lut(tag))),
+                b"B2A1" => profile.b_to_a[1] = Some(try!(read_lut(tag))),
+                b"B2A2" => profile.b_to_a[2] = Some(try!(read_lut(tag))),
+                _ => {}
+            }
+        }
+
+        if colorants.iter().all(Option::is_some) {
+            profile.colorants = Some([colorants[0].unwrap(), colorants[1].unwrap(), colorants[2].unwrap()]);
+        }
+        if curves.iter().all(Option::is_some) {
+            profile.tone_curves = Some(curves.iter().cloned().map(Option::unwrap).collect());
+        }
+        Ok(profile)
+    }
+
+    /// Returns the profile header.
+    #[inline]
+    pub fn header(&self) -> &IccHeader { &self.header }
+
+    /// Returns the media white point, from the `wtpt` tag.
+    #[inline]
+    pub fn white_point(&self) -> Option<Xyz<f64>> { self.white_point }
+
+    /// Returns the matrix, by rows, that adapted the device's white to the
+    /// connection space illuminant, from the `chad` tag.
+    #[inline]
+    pub fn chromatic_adaptation(&self) -> Option<[[f64; 3]; 3]> { self.chromatic_adaptation }
+
+    /// Returns the XYZ of the red, green and blue colorants of a
+    /// matrix/TRC profile, from the `rXYZ`, `gXYZ` and `bXYZ` tags.
+    #[inline]
+    pub fn colorants(&self) -> Option<[Xyz<f64>; 3]> { self.colorants }
+
+    /// Returns the red, green and blue tone curves of a matrix/TRC profile,
+    /// or the single grey tone curve of a grey profile.
+    pub fn tone_curves(&self) -> Option<&[IccCurve]> {
+        self.tone_curves.as_ref().map(|curves| &curves[..])
+    }
+
+    /// Returns the table converting device colors to the connection space
+    /// for a rendering intent, if the profile has one.
+    pub fn a_to_b(&self, intent: RenderingIntent) -> Option<&IccLut> {
+        self.a_to_b[intent.table()].as_ref()
+    }
+
+    /// Returns the table converting from the connection space to device
+    /// colors for a rendering intent, if the profile has one.
+    pub fn b_to_a(&self, intent: RenderingIntent) -> Option<&IccLut> {
+        self.b_to_a[intent.table()].as_ref()
+    }
+
+    /// Returns the number of device channels.
+    fn channels(&self) -> Result<usize, IccError> {
+        self.header.color_space.channels().ok_or(IccError::MissingTags)
+    }
+
+    /// Convert a device color, with components between zero and one, to
+    /// XYZ in the D50 connection space.
+    pub fn to_pcs(&self, device: &[f64], intent: RenderingIntent) -> Result<Xyz<f64>, IccError> {
+        if device.len() != try!(self.channels()) {
+            return Err(IccError::ChannelMismatch);
+        }
+        let lut = self.a_to_b(intent).or_else(|| self.a_to_b(RenderingIntent::Perceptual));
+        let xyz = match (lut, self.colorants.as_ref(), self.tone_curves.as_ref()) {
+            (Some(lut), _, _) => {
+                if lut.input_channels() != device.len() || lut.output_channels() != 3 {
+                    return Err(IccError::ChannelMismatch);
+                }
+                try!(self.decode_pcs(&lut.eval(device), lut.legacy_lab))
+            }
+            (None, Some(colorants), Some(curves)) if curves.len() == 3 => {
+                let linear = [curves[0].eval(device[0]), curves[1].eval(device[1]), curves[2].eval(device[2])];
+                mat3_apply(&colorant_matrix(colorants), linear)
+            }
+            (None, _, Some(curves)) if curves.len() == 1 => {
+                let y = curves[0].eval(device[0]);
+                [PCS_WHITE[0] * y, PCS_WHITE[1] * y, PCS_WHITE[2] * y]
+            }
+            _ => return Err(IccError::MissingTags)
+        };
+        let scale = self.absolute_scale(intent);
+        Ok(Xyz::with_components(xyz[0] * scale[0], xyz[1] * scale[1], xyz[2] * scale[2]))
+    }
+
+    /// Convert XYZ in the D50 connection space to a device color, with
+    /// components between zero and one.
+    pub fn from_pcs(&self, xyz: &Xyz<f64>, intent: RenderingIntent) -> Result<Vec<f64>, IccError> {
+        let channels = try!(self.channels());
+        let scale = self.absolute_scale(intent);
+        let xyz = [xyz.x() / scale[0], xyz.y() / scale[1], xyz.z() / scale[2]];
+        let lut = self.b_to_a(intent).or_else(|| self.b_to_a(RenderingIntent::Perceptual));
+        match (lut, self.colorants.as_ref(), self.tone_curves.as_ref()) {
+            (Some(lut), _, _) => {
+                if lut.input_channels() != 3 || lut.output_channels() != channels {
+                    return Err(IccError::ChannelMismatch);
+                }
+                Ok(lut.eval(&try!(self.encode_pcs(xyz, lut.legacy_lab))))
+            }
+            (None, Some(colorants), Some(curves)) if curves.len() == 3 => {
+                let linear = mat3_apply(&mat3_inverse(&colorant_matrix(colorants)), xyz);
+                Ok(linear.iter().zip(curves).map(|(&x, curve)| curve.eval_inverse(x)).collect())
+            }
+            (None, _, Some(curves)) if curves.len() == 1 => {
+                Ok(vec![curves[0].eval_inverse(xyz[1])])
+            }
+            _ => Err(IccError::MissingTags)
+        }
+    }
+
+    /// The scale from relative to absolute colorimetric XYZ.
+    fn absolute_scale(&self, intent: RenderingIntent) -> [f64; 3] {
+        match (intent, self.white_point) {
+            (RenderingIntent::AbsoluteColorimetric, Some(white)) => {
+                [white.x() / PCS_WHITE[0], white.y() / PCS_WHITE[1], white.z() / PCS_WHITE[2]]
+            }
+            _ => [1.0; 3]
+        }
+    }
+
+    /// Decode the connection space output of a lookup table to XYZ.
+    fn decode_pcs(&self, v: &[f64], legacy_lab: bool) -> Result<[f64; 3], IccError> {
+        match self.header.pcs {
+            IccColorSpace::Xyz => {
+                let scale = 65535.0 / 32768.0;
+                Ok([v[0] * scale, v[1] * scale, v[2] * scale])
+            }
+            IccColorSpace::Lab => {
+                let scale = if legacy_lab { 65535.0 / 65280.0 } else { 1.0 };
+                let lab = Lab::with_components(v[0] * scale * 100.0,
+                                               v[1] * scale * 255.0 - 128.0,
+                                               v[2] * scale * 255.0 - 128.0);
+                let xyz = lab.to_xyz(&pcs_white());
+                Ok([xyz.x(), xyz.y(), xyz.z()])
+            }
+            _ => Err(IccError::Malformed("connection space must be XYZ or Lab"))
+        }
+    }
+
+    /// Encode XYZ as the connection space input of a lookup table.
+    fn encode_pcs(&self, xyz: [f64; 3], legacy_lab: bool) -> Result<Vec<f64>, IccError> {
+        match self.header.pcs {
+            IccColorSpace::Xyz => {
+                let scale = 32768.0 / 65535.0;
+                Ok(vec![xyz[0] * scale, xyz[1] * scale, xyz[2] * scale])
+            }
+            IccColorSpace::Lab => {
+                let scale = if legacy_lab { 65280.0 / 65535.0 } else { 1.0 };
+                let lab = Lab::from_xyz(&from_array(xyz), &pcs_white());
+                Ok(vec![lab.l() / 100.0 * scale,
+                        (lab.a() + 128.0) / 255.0 * scale,
+                        (lab.b() + 128.0) / 255.0 * scale])
+            }
+            _ => Err(IccError::Malformed("connection space must be XYZ or Lab"))
+        }
+    }
+}
+
+/// A color which can be converted by an `IccTransform`.
+pub trait IccColor {
+    /// Returns the number of components of the color.
+    fn channels() -> usize;
+
+    /// Returns the components of the color, between zero and one.
+    fn to_device(&self) -> Vec<f64>;
+
+    /// Create a color from components between zero and one.
+    fn from_device(device: &[f64]) -> Self;
+}
+
+impl<T: Channel> IccColor for Rgb<T> {
+    fn channels() -> usize { 3 }
+
+    fn to_device(&self) -> Vec<f64> {
+        vec![self.r().to_float(), self.g().to_float(), self.b().to_float()]
+    }
+
+    fn from_device(device: &[f64]) -> Rgb<T> {
+        Rgb::with_components(T::from_float(device[0]), T::from_float(device[1]), T::from_float(device[2]))
+    }
+}
+
+impl<T: Channel> IccColor for Cmyk<T> {
+    fn channels() -> usize { 4 }
+
+    fn to_device(&self) -> Vec<f64> {
+        vec![self.c().to_float(), self.m().to_float(), self.y().to_float(), self.k().to_float()]
+    }
+
+    fn from_device(device: &[f64]) -> Cmyk<T> {
+        Cmyk::with_components(T::from_float(device[0]), T::from_float(device[1]),
+                              T::from_float(device[2]), T::from_float(device[3]))
+    }
+}
+
+/// A conversion from the device colors of one profile to the device colors
+/// of another, through the profile connection space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccTransform {
+    /// Profile of the input colors
+    source: IccProfile,
+
+    /// Profile of the output colors
+    destination: IccProfile,
+
+    /// Rendering intent
+    intent: RenderingIntent
+}
+
+impl IccTransform {
+    /// Create a transform between two profiles. Fails if the source cannot
+    /// convert to the connection space, or the destination cannot convert
+    /// from it.
+    pub fn new(source: &IccProfile, destination: &IccProfile,
+               intent: RenderingIntent) -> Result<IccTransform, IccError> {
+        let black = vec![0.0; try!(source.channels())];
+        try!(destination.from_pcs(&try!(source.to_pcs(&black, intent)), intent));
+        Ok(IccTransform {
+            source: source.clone(),
+            destination: destination.clone(),
+            intent: intent
+        })
+    }
+
+    /// Returns the rendering intent.
+    #[inline]
+    pub fn intent(&self) -> RenderingIntent { self.intent }
+
+    /// Convert device components between zero and one.
+    pub fn apply(&self, device: &[f64]) -> Result<Vec<f64>, IccError> {
+        let xyz = try!(self.source.to_pcs(device, self.intent));
+        self.destination.from_pcs(&xyz, self.intent)
+    }
+
+    /// Convert a color, such as an `Rgb` or `Cmyk` color. Fails with
+    /// `IccError::ChannelMismatch` unless the color types have as many
+    /// channels as the color spaces of their profiles.
+    ///
+    /// ```rust,no_run
+    /// use simplecolor::{write_icc_profile, Cmyk, IccProfile, IccTransform, RenderingIntent, Rgb, RgbSpace};
+    ///
+    /// let srgb = IccProfile::parse(&write_icc_profile(&RgbSpace::srgb(), "sRGB")).unwrap();
+    /// let press = IccProfile::parse(&std::fs::read("press.icc").unwrap()).unwrap();
+    /// let transform = IccTransform::new(&srgb, &press, RenderingIntent::Perceptual).unwrap();
+    /// let ink: Cmyk<u8> = transform.convert(&Rgb::with_components(255u8, 0, 0)).unwrap();
+    /// ```
+    pub fn convert<C: IccColor, D: IccColor>(&self, color: &C) -> Result<D, IccError> {
+        if C::channels() != try!(self.source.channels()) || D::channels() != try!(self.destination.channels()) {
+            return Err(IccError::ChannelMismatch);
+        }
+        let device = try!(self.apply(&color.to_device()));
+        Ok(D::from_device(&device))
+    }
+}
+
+/// Serialise a version 4 matrix/TRC display profile describing an RGB
+/// space, suitable for embedding in images. The colorants are adapted to
+/// the D50 connection space with the Bradford transform, which is recorded
+/// in the `chad` tag. Curves with an exact parametric form are written as
+/// `para` tags, and the others as sampled `curv` tables, which clamp linear
+/// values above one.
+///
+/// ```rust
+/// use simplecolor::{write_icc_profile, IccProfile, RgbSpace};
+///
+/// let icc = write_icc_profile(&RgbSpace::display_p3(), "Display P3");
+/// let profile = IccProfile::parse(&icc).unwrap();
+/// ```
+pub fn write_icc_profile(space: &RgbSpace, description: &str) -> Vec<u8> {
+    let adaptation = ChromaticAdaptation::new(AdaptationMethod::Bradford,
+                                              &space.white_xyz::<f64>(), &pcs_white());
+    let chad = adaptation.matrix();
+    let colorants = mat3_mul(&chad, &space.to_xyz_matrix());
+    let colorant = |i: usize| write_xyz_tag(colorants[0][i], colorants[1][i], colorants[2][i]);
+    let trc = write_curve(space.transfer());
+
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", write_mluc(description)),
+        (b"cprt", write_mluc("No copyright, use freely")),
+        (b"wtpt", write_xyz_tag(PCS_WHITE[0], PCS_WHITE[1], PCS_WHITE[2])),
+        (b"chad", write_sf32_matrix(&chad)),
+        (b"rXYZ", colorant(0)),
+        (b"gXYZ", colorant(1)),
+        (b"bXYZ", colorant(2)),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", trc.clone()),
+        (b"bTRC", trc)
+    ];
+
+    let mut table = Vec::new();
+    push_u32(&mut table, tags.len() as u32);
+    let mut body: Vec<u8> = Vec::new();
+    let start = 132 + 12 * tags.len();
+    let mut written: Vec<(usize, usize)> = Vec::new();
+    for (i, &(signature, ref tag)) in tags.iter().enumerate() {
+        // Identical tags, such as the three tone curves, share their data.
+        let shared = (0..i).find(|&j| tags[j].1 == *tag).map(|j| written[j].0);
+        let offset = match shared {
+            Some(offset) => offset,
+            None => {
+                let offset = start + body.len();
+                body.extend_from_slice(tag);
+                while body.len() % 4 != 0 {
+                    body.push(0);
+                }
+                offset
+            }
+        };
+        written.push((offset, tag.len()));
+        table.extend_from_slice(signature);
+        push_u32(&mut table, offset as u32);
+        push_u32(&mut table, tag.len() as u32);
+    }
+
+    let mut data = Vec::with_capacity(start + body.len());
+    push_u32(&mut data, (start + body.len()) as u32);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&[4, 0x30, 0, 0]);
+    data.extend_from_slice(b"mntrRGB XYZ ");
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(b"acsp");
+    data.extend_from_slice(&[0; 28]);
+    for &v in &PCS_WHITE {
+        push_s15_fixed16(&mut data, v);
+    }
+    data.extend_from_slice(&[0; 48]);
+    data.extend(table);
+    data.extend(body);
+    data
+}
+
+/// The D50 connection space illuminant.
+fn pcs_white() -> Xyz<f64> {
+    from_array(PCS_WHITE)
+}
+
+fn from_array(v: [f64; 3]) -> Xyz<f64> {
+    Xyz::with_components(v[0], v[1], v[2])
+}
+
+/// The matrix from linear RGB to XYZ, with a colorant in each column.
+fn colorant_matrix(colorants: &[Xyz<f64>; 3]) -> Matrix3 {
+    let (r, g, b) = (colorants[0], colorants[1], colorants[2]);
+    [[r.x(), g.x(), b.x()], [r.y(), g.y(), b.y()], [r.z(), g.z(), b.z()]]
+}
+
+fn push_u16(data: &mut Vec<u8>, x: u16) {
+    data.push((x >> 8) as u8);
+    data.push(x as u8);
+}
+
+fn push_u32(data: &mut Vec<u8>, x: u32) {
+    push_u16(data, (x >> 16) as u16);
+    push_u16(data, x as u16);
+}
+
+fn push_s15_fixed16(data: &mut Vec<u8>, x: f64) {
+    push_u32(data, (x * 65536.0).round() as i32 as u32);
+}
+
+/// Write an `XYZ ` tag with a single value.
+fn write_xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for &v in &[x, y, z] {
+        push_s15_fixed16(&mut tag, v);
+    }
+    tag
+}
+
+/// Write an `sf32` tag holding a 3x3 matrix by rows.
+fn write_sf32_matrix(m: &Matrix3) -> Vec<u8> {
+    let mut tag = b"sf32\0\0\0\0".to_vec();
+    for row in m {
+        for &v in row {
+            push_s15_fixed16(&mut tag, v);
+        }
+    }
+    tag
+}
+
+/// Write a `mluc` tag holding a single US English string.
+fn write_mluc(text: &str) -> Vec<u8> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let mut tag = b"mluc\0\0\0\0".to_vec();
+    push_u32(&mut tag, 1);
+    push_u32(&mut tag, 12);
+    tag.extend_from_slice(b"enUS");
+    push_u32(&mut tag, 2 * units.len() as u32);
+    push_u32(&mut tag, 28);
+    for unit in units {
+        push_u16(&mut tag, unit);
+    }
+    tag
+}
+
+/// Write a tone curve decoding a transfer function, as a `para` tag when
+/// it has an exact parametric form and as a sampled `curv` table otherwise.
+fn write_curve(transfer: TransferFunction) -> Vec<u8> {
+    // The full precision constants of the BT.709 curve.
+    let (alpha, beta) = (1.09929682680944, 0.018053968510807);
+    let parametric: Option<(u16, Vec<f64>)> = match transfer {
+        TransferFunction::Linear => Some((0, vec![1.0])),
+        TransferFunction::Gamma(gamma) => Some((0, vec![gamma])),
+        TransferFunction::Srgb => {
+            Some((3, vec![2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045]))
+        }
+        TransferFunction::Rec709 => {
+            Some((3, vec![1.0 / 0.45, 1.0 / alpha, (alpha - 1.0) / alpha, 1.0 / 4.5, 4.5 * beta]))
+        }
+        TransferFunction::ProPhoto => Some((3, vec![1.8, 1.0, 0.0, 1.0 / 16.0, 16.0 / 512.0])),
+        TransferFunction::Bt1886 { black, .. } if black <= 0.0 => Some((0, vec![2.4])),
+        _ => None
+    };
+
+    match parametric {
+        Some((function, params)) => {
+            let mut tag = b"para\0\0\0\0".to_vec();
+            push_u16(&mut tag, function);
+            push_u16(&mut tag, 0);
+            for v in params {
+                push_s15_fixed16(&mut tag, v);
+            }
+            tag
+        }
+        None => {
+            let entries = 4096;
+            let mut tag = b"curv\0\0\0\0".to_vec();
+            push_u32(&mut tag, entries);
+            for i in 0..entries {
+                let linear = transfer.decode(i as f64 / (entries - 1) as f64);
+                push_u16(&mut tag, (clamp(linear, 0.0, 1.0) * 65535.0).round() as u16);
+            }
+            tag
+        }
+    }
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, IccError> {
+    data.get(offset).cloned().ok_or(IccError::UnexpectedEnd)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, IccError> {
+    Ok((try!(read_u8(data, offset)) as u16) << 8 | try!(read_u8(data, offset + 1)) as u16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, IccError> {
+    Ok((try!(read_u16(data, offset)) as u32) << 16 | try!(read_u16(data, offset + 2)) as u32)
+}
+
+fn read_signature(data: &[u8], offset: usize) -> Result<[u8; 4], IccError> {
+    if offset + 4 > data.len() {
+        return Err(IccError::UnexpectedEnd);
+    }
+    Ok([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Result<f64, IccError> {
+    Ok(try!(read_u32(data, offset)) as i32 as f64 / 65536.0)
+}
+
+fn read_xyz_number(data: &[u8], offset: usize) -> Result<Xyz<f64>, IccError> {
+    Ok(Xyz::with_components(try!(read_s15_fixed16(data, offset)),
+                            try!(read_s15_fixed16(data, offset + 4)),
+                            try!(read_s15_fixed16(data, offset + 8))))
+}
+
+/// Read an `XYZ ` tag with a single value.
+fn read_xyz_tag(tag: &[u8]) -> Result<Xyz<f64>, IccError> {
+    match &try!(read_signature(tag, 0)) {
+        b"XYZ " => read_xyz_number(tag, 8),
+        _ => Err(IccError::UnsupportedTagType(try!(read_signature(tag, 0))))
+    }
+}
+
+/// Read an `sf32` tag holding a 3x3 matrix by rows.
+fn read_sf32_matrix(tag: &[u8]) -> Result<Matrix3, IccError> {
+    if &try!(read_signature(tag, 0)) != b"sf32" {
+        return Err(IccError::UnsupportedTagType(try!(read_signature(tag, 0))));
+    }
+    let mut m = [[0.0; 3]; 3];
+    for i in 0..9 {
+        m[i / 3][i % 3] = try!(read_s15_fixed16(tag, 8 + 4 * i));
+    }
+    Ok(m)
+}
+
+/// Read a `curv` or `para` curve, returning it and its size in bytes.
+fn read_curve(data: &[u8]) -> Result<(IccCurve, usize), IccError> {
+    let signature = try!(read_signature(data, 0));
+    match &signature {
+        b"curv" => {
+            let count = try!(read_u32(data, 8)) as usize;
+            if count > data.len().saturating_sub(12) / 2 {
+                return Err(IccError::UnexpectedEnd);
+            }
+            let curve = match count {
+                0 => IccCurve::Identity,
+                1 => IccCurve::Gamma(try!(read_u16(data, 12)) as f64 / 256.0),
+                _ => {
+                    let mut table = Vec::with_capacity(count);
+                    for i in 0..count {
+                        table.push(try!(read_u16(data, 12 + 2 * i)) as f64 / 65535.0);
+                    }
+                    IccCurve::Table(table)
+                }
+            };
+            Ok((curve, 12 + 2 * count))
+        }
+        b"para" => {
+            let function = try!(read_u16(data, 8));
+            let count = match function {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return Err(IccError::Malformed("unknown parametric curve function"))
+            };
+            let mut params = [0.0; 7];
+            for (i, param) in params.iter_mut().enumerate().take(count) {
+                *param = try!(read_s15_fixed16(data, 12 + 4 * i));
+            }
+            Ok((IccCurve::Parametric(function, params), 12 + 4 * count))
+        }
+        _ => Err(IccError::UnsupportedTagType(signature))
+    }
+}
+
+/// Read a sequence of curves, each aligned to four bytes.
+fn read_curves(data: &[u8], offset: usize, count: usize) -> Result<Vec<IccCurve>, IccError> {
+    let mut curves = Vec::with_capacity(count);
+    let mut offset = offset;
+    for _ in 0..count {
+        if offset > data.len() {
+            return Err(IccError::UnexpectedEnd);
+        }
+        let (curve, size) = try!(read_curve(&data[offset..]));
+        curves.push(curve);
+        offset += size + (4 - size % 4) % 4;
+    }
+    Ok(curves)
+}
+
+/// Read the `mft1`, `mft2`, `mAB ` or `mBA ` lookup table types.
+fn read_lut(tag: &[u8]) -> Result<IccLut, IccError> {
+    let signature = try!(read_signature(tag, 0));
+    let inputs = try!(read_u8(tag, 8)) as usize;
+    let outputs = try!(read_u8(tag, 9)) as usize;
+    if inputs == 0 || outputs == 0 || inputs > 15 {
+        return Err(IccError::Malformed("lookup table channel count"));
+    }
+    match &signature {
+        b"mft1" | b"mft2" => {
+            let wide = &signature == b"mft2";
+            let grid = try!(read_u8(tag, 10)) as usize;
+            if grid < 2 {
+                return Err(IccError::Malformed("lookup table grid size"));
+            }
+            let mut matrix = [0.0; 12];
+            for (i, value) in matrix.iter_mut().enumerate().take(9) {
+                *value = try!(read_s15_fixed16(tag, 12 + 4 * i));
+            }
+            let (input_entries, output_entries, mut offset) = if wide {
+                (try!(read_u16(tag, 48)) as usize, try!(read_u16(tag, 50)) as usize, 52)
+            } else {
+                (256, 256, 48)
+            };
+            if !(2..=4096).contains(&input_entries) || !(2..=4096).contains(&output_entries) {
+                return Err(IccError::Malformed("lookup table entry count"));
+            }
+            let (size, max) = if wide { (2, 65535.0) } else { (1, 255.0) };
+            let read = |offset: usize| -> Result<f64, IccError> {
+                Ok(if wide { try!(read_u16(tag, offset)) as f64 } else { try!(read_u8(tag, offset)) as f64 } / max)
+            };
+            let read_tables = |offset: &mut usize, count: usize, entries: usize| {
+                let mut curves = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let mut table = Vec::with_capacity(entries);
+                    for _ in 0..entries {
+                        table.push(try!(read(*offset)));
+                        *offset += size;
+                    }
+                    curves.push(IccCurve::Table(table));
+                }
+                Ok(curves)
+            };
+
+            let input_curves = try!(read_tables(&mut offset, inputs, input_entries));
+            let clut_size = try!(grid.checked_pow(inputs as u32)
+                                     .and_then(|points| points.checked_mul(outputs))
+                                     .ok_or(IccError::Malformed("lookup table size")));
+            if clut_size > tag.len().saturating_sub(offset) / size {
+                return Err(IccError::UnexpectedEnd);
+            }
+            let mut data = Vec::with_capacity(clut_size);
+            for _ in 0..clut_size {
+                data.push(try!(read(offset)));
+                offset += size;
+            }
+            let output_curves = try!(read_tables(&mut offset, outputs, output_entries));
+
+            let mut stages = Vec::new();
+            let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+            if inputs == 3 && matrix != identity {
+                stages.push(LutStage::Matrix(matrix));
+            }
+            stages.push(LutStage::Curves(input_curves));
+            stages.push(LutStage::Clut(Clut { grid: vec![grid; inputs], outputs: outputs, data: data }));
+            stages.push(LutStage::Curves(output_curves));
+            Ok(IccLut { inputs: inputs, outputs: outputs, stages: stages, legacy_lab: wide })
+        }
+        b"mAB " | b"mBA " => {
+            let offset = |position: usize| -> Result<Option<usize>, IccError> {
+                let offset = try!(read_u32(tag, position)) as usize;
+                Ok(if offset == 0 { None } else { Some(offset) })
+            };
+            let (b, matrix, m, clut, a) = (try!(offset(12)), try!(offset(16)), try!(offset(20)),
+                                           try!(offset(24)), try!(offset(28)));
+            let a_to_b = &signature == b"mAB ";
+            let (a_channels, b_channels) = if a_to_b { (inputs, outputs) } else { (outputs, inputs) };
+
+            let b = match b {
+                Some(b) => LutStage::Curves(try!(read_curves(tag, b, b_channels))),
+                None => return Err(IccError::Malformed("lookup table lacks B curves"))
+            };
+            let a = match a {
+                Some(a) => Some(LutStage::Curves(try!(read_curves(tag, a, a_channels)))),
+                None => None
+            };
+            let m = match m {
+                Some(m) => Some(LutStage::Curves(try!(read_curves(tag, m, 3)))),
+                None => None
+            };
+            let matrix = match matrix {
+                Some(offset) => {
+                    let mut values = [0.0; 12];
+                    for (i, value) in values.iter_mut().enumerate() {
+                        *value = try!(read_s15_fixed16(tag, offset + 4 * i));
+                    }
+                    Some(LutStage::Matrix(values))
+                }
+                None => None
+            };
+            let clut = match clut {
+                Some(offset) => Some(LutStage::Clut(try!(read_clut(tag, offset, inputs, outputs)))),
+                None => None
+            };
+            if (a.is_some() != clut.is_some()) || (m.is_some() != matrix.is_some()) {
+                return Err(IccError::Malformed("lookup table elements must be paired"));
+            }
+            // Only the CLUT changes the number of channels, and the matrix
+            // sits on the connection space side of it.
+            if clut.is_none() && inputs != outputs {
+                return Err(IccError::Malformed("lookup table channel counts"));
+            }
+            if matrix.is_some() && (if a_to_b { outputs } else { inputs }) != 3 {
+                return Err(IccError::Malformed("lookup table matrix needs three channels"));
+            }
+
+            let stages: Vec<LutStage> = if a_to_b {
+                vec![a, clut, m, matrix, Some(b)]
+            } else {
+                vec![Some(b), matrix, m, clut, a]
+            }.into_iter().flatten().collect();
+            Ok(IccLut { inputs: inputs, outputs: outputs, stages: stages, legacy_lab: false })
+        }
+        _ => Err(IccError::UnsupportedTagType(signature))
+    }
+}
+
+/// Read the color lookup table of an `mAB ` or `mBA ` tag.
+fn read_clut(tag: &[u8], offset: usize, inputs: usize, outputs: usize) -> Result<Clut, IccError> {
+    let mut grid = Vec::with_capacity(inputs);
+    for i in 0..inputs {
+        let points = try!(read_u8(tag, offset + i)) as usize;
+        if points == 0 {
+            return Err(IccError::Malformed("lookup table grid size"));
+        }
+        grid.push(points);
+    }
+    let precision = try!(read_u8(tag, offset + 16)) as usize;
+    if precision != 1 && precision != 2 {
+        return Err(IccError::Malformed("lookup table precision"));
+    }
+    let count = try!(grid.iter()
+                         .try_fold(outputs, |product, &points| product.checked_mul(points))
+                         .ok_or(IccError::Malformed("lookup table size")));
+    if count > tag.len().saturating_sub(offset + 20) / precision {
+        return Err(IccError::UnexpectedEnd);
+    }
+    let mut data = Vec::with_capacity(count);
+    for i in 0..count {
+        let position = offset + 20 + precision * i;
+        data.push(if precision == 1 {
+            try!(read_u8(tag, position)) as f64 / 255.0
+        } else {
+            try!(read_u16(tag, position)) as f64 / 65535.0
+        });
+    }
+    Ok(Clut { grid: grid, outputs: outputs, data: data })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Cmyk, Rgb, RgbSpace, TransferFunction, Xyz};
+
+    fn s15_fixed16(x: f64) -> Vec<u8> {
+        let n = (x * 65536.0).round() as i32 as u32;
+        vec![(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+    }
+
+    fn u16_bytes(x: u16) -> Vec<u8> {
+        vec![(x >> 8) as u8, x as u8]
+    }
+
+    fn xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for &v in &[x, y, z] { tag.extend(s15_fixed16(v)); }
+        tag
+    }
+
+    fn srgb_curve() -> Vec<u8> {
+        let mut tag = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        for &v in &[2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045] { tag.extend(s15_fixed16(v)); }
+        tag
+    }
+
+    /// An `mft2` table with a grid of two points and two entry identity
+    /// input and output tables.
+    fn mft2(inputs: usize, outputs: usize, matrix: [f64; 9], clut: &[u16]) -> Vec<u8> {
+        let mut tag = b"mft2\0\0\0\0".to_vec();
+        tag.extend(vec![inputs as u8, outputs as u8, 2, 0]);
+        for &v in &matrix { tag.extend(s15_fixed16(v)); }
+        tag.extend(u16_bytes(2));
+        tag.extend(u16_bytes(2));
+        for _ in 0..inputs { tag.extend(vec![0, 0, 0xff, 0xff]); }
+        for &v in clut { tag.extend(u16_bytes(v)); }
+        for _ in 0..outputs { tag.extend(vec![0, 0, 0xff, 0xff]); }
+        tag
+    }
+
+    fn profile(space: &[u8; 4], pcs: &[u8; 4], tags: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+        let mut data = vec![0; 128];
+        data[8] = 4;
+        data[9] = 0x30;
+        data[12..16].copy_from_slice(b"mntr");
+        data[16..20].copy_from_slice(space);
+        data[20..24].copy_from_slice(pcs);
+        data[36..40].copy_from_slice(b"acsp");
+        for (i, &v) in PCS_WHITE.iter().enumerate() {
+            data[68 + 4 * i..72 + 4 * i].copy_from_slice(&s15_fixed16(v));
+        }
+
+        let mut offset = 132 + 12 * tags.len();
+        let mut table = vec![0, 0, 0, tags.len() as u8];
+        let mut body = Vec::new();
+        for &(signature, ref tag) in &tags {
+            table.extend(signature.iter().cloned());
+            table.extend(vec![0, 0, (offset >> 8) as u8, offset as u8]);
+            table.extend(vec![0, 0, (tag.len() >> 8) as u8, tag.len() as u8]);
+            body.extend(tag.iter().cloned());
+            while body.len() % 4 != 0 { body.push(0); }
+            offset = 132 + 12 * tags.len() + body.len();
+        }
+        data.extend(table);
+        data.extend(body);
+        let size = data.len();
+        data[2] = (size >> 8) as u8;
+        data[3] = size as u8;
+        data
+    }
+
+    const RED: [f64; 3] = [0.4360747, 0.2225045, 0.0139322];
+    const GREEN: [f64; 3] = [0.3850649, 0.7168786, 0.0971045];
+    const BLUE: [f64; 3] = [0.1430804, 0.0606169, 0.7141733];
+
+    fn srgb_profile() -> IccProfile {
+        IccProfile::parse(&profile(b"RGB ", b"XYZ ", vec![
+            (b"wtpt", xyz_tag(PCS_WHITE[0], PCS_WHITE[1], PCS_WHITE[2])),
+            (b"rXYZ", xyz_tag(RED[0], RED[1], RED[2])),
+            (b"gXYZ", xyz_tag(GREEN[0], GREEN[1], GREEN[2])),
+            (b"bXYZ", xyz_tag(BLUE[0], BLUE[1], BLUE[2])),
+            (b"rTRC", srgb_curve()),
+            (b"gTRC", srgb_curve()),
+            (b"bTRC", srgb_curve())
+        ])).unwrap()
+    }
+
+    /// A CMYK profile whose device is an ideal subtractive version of the
+    /// sRGB profile's linear primaries.
+    fn cmyk_profile() -> IccProfile {
+        let mut a_to_b = Vec::new();
+        for i in 0..16 {
+            let ink = |bit: usize| if i & (8 >> bit) != 0 { 1.0 } else { 0.0 };
+            let k = ink(3);
+            let rgb = [(1.0 - ink(0)) * (1.0 - k), (1.0 - ink(1)) * (1.0 - k), (1.0 - ink(2)) * (1.0 - k)];
+            for c in 0..3 {
+                let xyz = RED[c] * rgb[0] + GREEN[c] * rgb[1] + BLUE[c] * rgb[2];
+                a_to_b.push((xyz * 32768.0).round() as u16);
+            }
+        }
+        let mut b_to_a = Vec::new();
+        for i in 0..8 {
+            for bit in 0..3 {
+                b_to_a.push(if i & (4 >> bit) != 0 { 0 } else { 0xffff });
+            }
+            b_to_a.push(0);
+        }
+        let m = mat3_inverse(&[[RED[0], GREEN[0], BLUE[0]], [RED[1], GREEN[1], BLUE[1]], [RED[2], GREEN[2], BLUE[2]]]);
+        let scale = 65535.0 / 32768.0;
+        let mut matrix = [0.0; 9];
+        for i in 0..9 { matrix[i] = m[i / 3][i % 3] * scale; }
+        IccProfile::parse(&profile(b"CMYK", b"XYZ ", vec![
+            (b"A2B0", mft2(4, 3, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], &a_to_b)),
+            (b"B2A0", mft2(3, 4, matrix, &b_to_a))
+        ])).unwrap()
+    }
+
+    #[test]
+    fn parses_matrix_trc_profile() {
+        let profile = srgb_profile();
+        assert_eq!(profile.header().version(), (4, 3, 0));
+        assert_eq!(profile.header().class(), IccDeviceClass::Display);
+        assert_eq!(profile.header().color_space(), IccColorSpace::Rgb);
+        assert_eq!(profile.header().pcs(), IccColorSpace::Xyz);
+        assert!((profile.colorants().unwrap()[1].y() - GREEN[1]).abs() < 1e-4);
+        assert_eq!(profile.tone_curves().unwrap().len(), 3);
+        assert!(profile.a_to_b(RenderingIntent::Perceptual).is_none());
+    }
+
+    #[test]
+    fn matrix_trc_white_maps_to_d50() {
+        let white = srgb_profile().to_pcs(&[1.0, 1.0, 1.0], RenderingIntent::Perceptual).unwrap();
+        assert!((white.x() - 0.9642).abs() < 1e-3);
+        assert!((white.y() - 1.0).abs() < 1e-3);
+        assert!((white.z() - 0.8249).abs() < 1e-3);
+    }
+
+    #[test]
+    fn matrix_trc_round_trip() {
+        let profile = srgb_profile();
+        let transform = IccTransform::new(&profile, &profile, RenderingIntent::RelativeColorimetric).unwrap();
+        let col = Rgb::with_components(0.2f64, 0.5, 0.9);
+        let (r, g, b) = transform.convert::<_, Rgb<f64>>(&col).unwrap().components();
+        assert!((r - 0.2).abs() < 1e-6);
+        assert!((g - 0.5).abs() < 1e-6);
+        assert!((b - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parametric_curve_matches_srgb() {
+        let (curve, _) = read_curve(&srgb_curve()).unwrap();
+        assert!((curve.eval(0.5) - 0.2140).abs() < 1e-3);
+        assert!((curve.eval(0.02) - 0.02 / 12.92).abs() < 1e-4);
+        assert!((curve.eval_inverse(curve.eval(0.7)) - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rgb_to_cmyk_through_luts() {
+        let transform = IccTransform::new(&srgb_profile(), &cmyk_profile(), RenderingIntent::Perceptual).unwrap();
+        let ink: Cmyk<u8> = transform.convert(&Rgb::with_components(255u8, 0, 0)).unwrap();
+        assert_eq!(ink.components(), (0, 255, 255, 0));
+
+        let back = IccTransform::new(&cmyk_profile(), &srgb_profile(), RenderingIntent::Perceptual).unwrap();
+        let rgb: Rgb<u8> = back.convert(&Cmyk::with_components(0u8, 255, 0, 0)).unwrap();
+        assert_eq!(rgb.components(), (255, 0, 255));
+    }
+
+    #[test]
+    fn convert_checks_color_channels() {
+        let transform = IccTransform::new(&srgb_profile(), &cmyk_profile(), RenderingIntent::Perceptual).unwrap();
+        let dropped_k: Result<Rgb<u8>, _> = transform.convert(&Rgb::with_components(255u8, 0, 0));
+        assert_eq!(dropped_k, Err(IccError::ChannelMismatch));
+        let wrong_source: Result<Cmyk<u8>, _> = transform.convert(&Cmyk::with_components(0u8, 0, 0, 0));
+        assert_eq!(wrong_source, Err(IccError::ChannelMismatch));
+    }
+
+    #[test]
+    fn legacy_lab_white_decodes_to_d50() {
+        let clut: Vec<u16> = (0..8).flat_map(|_| vec![0xff00, 0x8000, 0x8000]).collect();
+        let profile = IccProfile::parse(&profile(b"RGB ", b"Lab ", vec![
+            (b"A2B0", mft2(3, 3, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], &clut))
+        ])).unwrap();
+        let white = profile.to_pcs(&[0.3, 0.6, 0.9], RenderingIntent::Perceptual).unwrap();
+        assert!((white.x() - 0.9642).abs() < 1e-4);
+        assert!((white.y() - 1.0).abs() < 1e-4);
+        assert!((white.z() - 0.8249).abs() < 1e-4);
+    }
+
+    #[test]
+    fn evaluates_lut_a_to_b() {
+        let mut tag = b"mAB \0\0\0\0\x03\x03\0\0".to_vec();
+        for &offset in &[32u32, 0, 0, 68, 112] {
+            tag.extend(vec![0, 0, 0, offset as u8]);
+        }
+        for _ in 0..3 { tag.extend(b"curv\0\0\0\0\0\0\0\0".to_vec()); }
+        tag.extend(vec![2, 2, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        for i in 0..8 {
+            for bit in 0..3 { tag.push(if i & (4 >> bit) != 0 { 0xff } else { 0 }); }
+        }
+        for _ in 0..3 { tag.extend(b"curv\0\0\0\0\0\0\0\x01\x02\0\0\0".to_vec()); }
+
+        let lut = read_lut(&tag).unwrap();
+        let out = lut.eval(&[0.5, 0.2, 1.0]);
+        assert!((out[0] - 0.25).abs() < 1e-9);
+        assert!((out[1] - 0.04).abs() < 1e-9);
+        assert!((out[2] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_oversized_lookup_tables() {
+        let mut tag = mft2(15, 3, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], &[]);
+        tag[10] = 255;
+        assert_eq!(read_lut(&tag).err(), Some(IccError::Malformed("lookup table size")));
+        tag[10] = 16;
+        assert_eq!(read_lut(&tag).err(), Some(IccError::UnexpectedEnd));
+
+        let mut tag = b"mAB \0\0\0\0\x03\x03\0\0".to_vec();
+        for &offset in &[32u32, 0, 0, 104, 68] {
+            tag.extend(vec![0, 0, 0, offset as u8]);
+        }
+        for _ in 0..6 { tag.extend(b"curv\0\0\0\0\0\0\0\0".to_vec()); }
+        tag.extend(vec![255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(read_lut(&tag).err(), Some(IccError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn rejects_lookup_tables_with_mismatched_channels() {
+        let curves = |n: usize| -> Vec<u8> { (0..n).flat_map(|_| b"curv\0\0\0\0\0\0\0\0".to_vec()).collect() };
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let lut = |header: &[u8], offsets: [u32; 5], body: Vec<u8>| {
+            let mut tag = header.to_vec();
+            for &offset in &offsets { tag.extend(vec![0, 0, 0, offset as u8]); }
+            tag.extend(body);
+            read_lut(&tag).err()
+        };
+
+        // One input and three outputs, with a matrix but no CLUT.
+        let mut body = curves(3);
+        for &v in &identity { body.extend(s15_fixed16(v)); }
+        body.extend(curves(3));
+        assert_eq!(lut(b"mAB \0\0\0\0\x01\x03\0\0", [32, 68, 116, 0, 0], body),
+                   Some(IccError::Malformed("lookup table channel counts")));
+
+        // Three inputs and four outputs, with only B curves.
+        assert_eq!(lut(b"mBA \0\0\0\0\x03\x04\0\0", [32, 0, 0, 0, 0], curves(3)),
+                   Some(IccError::Malformed("lookup table channel counts")));
+
+        // A matrix reached by a single channel.
+        let mut body = curves(1);
+        for &v in &identity { body.extend(s15_fixed16(v)); }
+        body.extend(curves(3));
+        assert_eq!(lut(b"mAB \0\0\0\0\x01\x01\0\0", [32, 44, 92, 0, 0], body),
+                   Some(IccError::Malformed("lookup table matrix needs three channels")));
+    }
+
+    #[test]
+    fn rejects_invalid_table_entry_counts() {
+        let clut = [0u16; 24];
+        for &entries in &[0u16, 1, 4097] {
+            let mut tag = mft2(3, 3, [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], &clut);
+            tag[48..50].copy_from_slice(&u16_bytes(entries));
+            assert_eq!(read_lut(&tag).err(), Some(IccError::Malformed("lookup table entry count")));
+        }
+        assert_eq!(read_curve(b"curv\0\0\0\0\xff\xff\xff\xff\0\0").err(), Some(IccError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn short_tables_evaluate_without_panicking() {
+        assert_eq!(IccCurve::Table(Vec::new()).eval(0.25), 0.25);
+        assert_eq!(IccCurve::Table(vec![0.75]).eval(0.25), 0.75);
+    }
+
+    #[test]
+    fn absolute_intent_scales_by_media_white() {
+        let mut data = profile(b"GRAY", b"XYZ ", vec![
+            (b"wtpt", xyz_tag(0.9642 * 0.9, 0.9, 0.8249 * 0.9)),
+            (b"kTRC", b"curv\0\0\0\0\0\0\0\0".to_vec())
+        ]);
+        data[16..20].copy_from_slice(b"GRAY");
+        let profile = IccProfile::parse(&data).unwrap();
+        let relative = profile.to_pcs(&[1.0], RenderingIntent::RelativeColorimetric).unwrap();
+        let absolute = profile.to_pcs(&[1.0], RenderingIntent::AbsoluteColorimetric).unwrap();
+        assert!((relative.y() - 1.0).abs() < 1e-9);
+        assert!((absolute.y() - 0.9).abs() < 1e-4);
+        assert!((profile.from_pcs(&absolute, RenderingIntent::AbsoluteColorimetric).unwrap()[0] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn written_profile_round_trips_through_parser() {
+        let space = RgbSpace::display_p3();
+        let profile = IccProfile::parse(&write_icc_profile(&space, "Display P3")).unwrap();
+        assert_eq!(profile.header().version(), (4, 3, 0));
+        assert_eq!(profile.header().class(), IccDeviceClass::Display);
+        assert_eq!(profile.header().color_space(), IccColorSpace::Rgb);
+        assert_eq!(profile.header().size() as usize, write_icc_profile(&space, "Display P3").len());
+
+        let white = profile.to_pcs(&[1.0, 1.0, 1.0], RenderingIntent::Perceptual).unwrap();
+        assert!((white.x() - 0.9642).abs() < 1e-3);
+        assert!((white.y() - 1.0).abs() < 1e-3);
+        assert!((white.z() - 0.8249).abs() < 1e-3);
+
+        // The chad tag undoes the adaptation back to the space's own white.
+        let chad = profile.chromatic_adaptation().unwrap();
+        let xyz = mat3_apply(&mat3_inverse(&chad), [white.x(), white.y(), white.z()]);
+        let (x, y) = (xyz[0] / (xyz[0] + xyz[1] + xyz[2]), xyz[1] / (xyz[0] + xyz[1] + xyz[2]));
+        assert!((x - 0.3127).abs() < 1e-4);
+        assert!((y - 0.3290).abs() < 1e-4);
+    }
+
+    #[test]
+    fn written_profiles_convert_like_rgb_spaces() {
+        let srgb = IccProfile::parse(&write_icc_profile(&RgbSpace::srgb(), "sRGB")).unwrap();
+        let p3 = IccProfile::parse(&write_icc_profile(&RgbSpace::display_p3(), "Display P3")).unwrap();
+        let transform = IccTransform::new(&srgb, &p3, RenderingIntent::RelativeColorimetric).unwrap();
+        let col = Rgb::with_components(0.9f64, 0.3, 0.1);
+        let expected = RgbSpace::srgb().convert(&col, &RgbSpace::display_p3());
+        let actual: Rgb<f64> = transform.convert(&col).unwrap();
+        assert!((actual.r() - expected.r()).abs() < 1e-3);
+        assert!((actual.g() - expected.g()).abs() < 1e-3);
+        assert!((actual.b() - expected.b()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn written_curves_match_transfer_functions() {
+        let transfers = [TransferFunction::Srgb, TransferFunction::Gamma(2.2), TransferFunction::Rec709,
+                         TransferFunction::ProPhoto, TransferFunction::Pq { reference: 10000.0 },
+                         TransferFunction::Hlg, TransferFunction::Bt1886 { white: 100.0, black: 0.1 }];
+        for &transfer in transfers.iter() {
+            let space = RgbSpace::rec2020().with_transfer(transfer);
+            let profile = IccProfile::parse(&write_icc_profile(&space, "Test")).unwrap();
+            let curves = profile.tone_curves().unwrap();
+            for i in 0..11 {
+                let x = i as f64 / 10.0;
+                assert!((curves[1].eval(x) - transfer.decode(x)).abs() < 1e-4, "{:?} at {}", transfer, x);
+            }
+        }
+    }
+
+    #[test]
+    fn tone_curves_share_tag_data() {
+        let data = write_icc_profile(&RgbSpace::rec2100_pq(), "Rec. 2100 PQ");
+        assert!(data.len() < 10000);
+        assert_eq!(&data[132 + 12 * 7 + 4..132 + 12 * 7 + 8], &data[132 + 12 * 9 + 4..132 + 12 * 9 + 8]);
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        assert_eq!(IccProfile::parse(&[0; 64]), Err(IccError::UnexpectedEnd));
+        assert_eq!(IccProfile::parse(&[0; 200]), Err(IccError::InvalidSignature));
+
+        let mut truncated = profile(b"RGB ", b"XYZ ", vec![(b"wtpt", xyz_tag(1.0, 1.0, 1.0))]);
+        truncated.truncate(150);
+        assert_eq!(IccProfile::parse(&truncated), Err(IccError::UnexpectedEnd));
+
+        let wrong_type = profile(b"RGB ", b"XYZ ", vec![(b"rTRC", xyz_tag(1.0, 1.0, 1.0))]);
+        assert_eq!(IccProfile::parse(&wrong_type), Err(IccError::UnsupportedTagType(*b"XYZ ")));
+
+        let empty = IccProfile::parse(&profile(b"RGB ", b"XYZ ", vec![])).unwrap();
+        assert_eq!(empty.to_pcs(&[0.0, 0.0, 0.0], RenderingIntent::Perceptual), Err(IccError::MissingTags));
+        assert_eq!(srgb_profile().to_pcs(&[0.0], RenderingIntent::Perceptual), Err(IccError::ChannelMismatch));
+    }
+
+    #[test]
+    fn xyz_number_is_signed() {
+        let mut data = s15_fixed16(-0.5);
+        data.extend(s15_fixed16(1.5));
+        data.extend(s15_fixed16(0.25));
+        let xyz = read_xyz_number(&data, 0).unwrap();
+        assert_eq!(xyz, Xyz::with_components(-0.5, 1.5, 0.25));
+    }
+}
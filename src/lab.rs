@@ -0,0 +1,191 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{clamp, float, reduce_chroma_to_gamut, wrap_hue, Channel, Color, Rgb, Xyz};
+
+/// A color in the CIE 1976 L\*a\*b\* color space. L is the perceptual
+/// lightness between 0 and 100, and a and b are the green-red and
+/// blue-yellow opponent axes.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Lab<F: Float> {
+    /// Lightness component
+    l: F,
+
+    /// Green-red component
+    a: F,
+
+    /// Blue-yellow component
+    b: F
+}
+
+impl<F: Float> Lab<F> {
+    /// Construct a Lab color piecewise from individual components.
+    pub fn with_components(l: F, a: F, b: F) -> Lab<F> {
+        Lab {
+            l: l,
+            a: a,
+            b: b
+        }
+    }
+
+    /// Construct a Lab color from its cylindrical LCh representation, with
+    /// the hue given in degrees.
+    pub fn from_lch(l: F, c: F, h: F) -> Lab<F> {
+        let h = h.to_radians();
+        Lab::with_components(l, c * h.cos(), c * h.sin())
+    }
+
+    /// Returns the lightness component.
+    #[inline]
+    pub fn l(&self) -> F { self.l }
+
+    /// Returns the green-red component.
+    #[inline]
+    pub fn a(&self) -> F { self.a }
+
+    /// Returns the blue-yellow component.
+    #[inline]
+    pub fn b(&self) -> F { self.b }
+
+    /// Return each component in a 3-element tuple. Useful for destructuring.
+    pub fn components(&self) -> (F, F, F) {
+        (self.l, self.a, self.b)
+    }
+
+    /// Returns the chroma (colorfulness) of the color.
+    pub fn chroma(&self) -> F {
+        self.a.hypot(self.b)
+    }
+
+    /// Returns the hue angle of the color in degrees, between 0 and 360.
+    pub fn hue(&self) -> F {
+        wrap_hue(self.b.atan2(self.a).to_degrees())
+    }
+
+    /// Convert an Xyz color to Lab, relative to the given reference white.
+    pub fn from_xyz(xyz: &Xyz<F>, white: &Xyz<F>) -> Lab<F> {
+        let fx = lab_f(xyz.x() / white.x());
+        let fy = lab_f(xyz.y() / white.y());
+        let fz = lab_f(xyz.z() / white.z());
+        Lab::with_components(
+            float::<F>(116.0) * fy - float(16.0),
+            float::<F>(500.0) * (fx - fy),
+            float::<F>(200.0) * (fy - fz))
+    }
+
+    /// Convert this color to Xyz, relative to the given reference white.
+    pub fn to_xyz(&self, white: &Xyz<F>) -> Xyz<F> {
+        let fy = (self.l + float(16.0)) / float(116.0);
+        let fx = fy + self.a / float(500.0);
+        let fz = fy - self.b / float(200.0);
+        Xyz::with_components(
+            lab_f_inverse(fx) * white.x(),
+            lab_f_inverse(fy) * white.y(),
+            lab_f_inverse(fz) * white.z())
+    }
+}
+
+impl<F: Channel + Float> Lab<F> {
+    /// Convert an sRGB encoded color to Lab, relative to the D65 white point.
+    pub fn from_rgb(rgb: &Rgb<F>) -> Lab<F> {
+        Lab::from_xyz(&Xyz::from_rgb(rgb), &Xyz::d65())
+    }
+
+    /// Convert this color to an sRGB encoded color, relative to the D65
+    /// white point. Colors outside of the sRGB gamut will have channels
+    /// outside of [0, 1].
+    pub fn to_rgb(&self) -> Rgb<F> {
+        self.to_xyz(&Xyz::d65()).to_rgb()
+    }
+
+    /// Convert this color to an sRGB encoded color, reducing the chroma
+    /// until it lies within the sRGB gamut. The lightness is clamped between
+    /// 0 and 100, and the hue is preserved.
+    pub fn to_rgb_in_gamut(&self) -> Rgb<F> {
+        let l = clamp(self.l, F::zero(), float(100.0));
+        let (chroma, hue) = (self.chroma(), self.hue());
+        match reduce_chroma_to_gamut(chroma, |c| Lab::from_lch(l, c, hue).to_xyz(&Xyz::d65()).to_linear_rgb()) {
+            Some(c) => Lab::from_lch(l, c, hue).to_rgb().normalise(),
+            None => Lab::with_components(l, self.a, self.b).to_rgb().normalise()
+        }
+    }
+}
+
+/// The nonlinear compression function used by Lab.
+#[inline]
+fn lab_f<F: Float>(t: F) -> F {
+    let delta: F = float(6.0 / 29.0);
+    if t > delta * delta * delta {
+        t.cbrt()
+    } else {
+        t / (float::<F>(3.0) * delta * delta) + float(4.0 / 29.0)
+    }
+}
+
+/// The inverse of `lab_f`.
+#[inline]
+fn lab_f_inverse<F: Float>(t: F) -> F {
+    let delta: F = float(6.0 / 29.0);
+    if t > delta {
+        t * t * t
+    } else {
+        float::<F>(3.0) * delta * delta * (t - float(4.0 / 29.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn white_has_full_lightness_and_no_chroma() {
+        let white = Lab::from_rgb(&Rgb::with_components(1.0f64, 1.0, 1.0));
+        assert!((white.l() - 100.0).abs() < 1e-3);
+        assert!(white.chroma() < 1e-2);
+    }
+
+    #[test]
+    fn known_srgb_red() {
+        let (l, a, b) = Lab::from_rgb(&Rgb::with_components(1.0f64, 0.0, 0.0)).components();
+        assert!((l - 53.24).abs() < 0.01);
+        assert!((a - 80.09).abs() < 0.01);
+        assert!((b - 67.20).abs() < 0.01);
+    }
+
+    #[test]
+    fn round_trip_through_rgb() {
+        let col = Rgb::with_components(0.1f64, 0.6, 0.3);
+        let (r, g, b) = Lab::from_rgb(&col).to_rgb().components();
+        assert!((r - 0.1).abs() < 1e-6);
+        assert!((g - 0.6).abs() < 1e-6);
+        assert!((b - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gamut_mapping_preserves_lightness_and_hue() {
+        let vivid = Lab::from_lch(60.0f64, 150.0, 140.0);
+        let mapped = Lab::from_rgb(&vivid.to_rgb_in_gamut());
+        assert!((mapped.l() - 60.0).abs() < 1e-3);
+        assert!((mapped.hue() - 140.0).abs() < 1e-3);
+        assert!(mapped.chroma() < 150.0);
+    }
+}
@@ -34,9 +34,36 @@ extern crate num;
 #[cfg(test)]
 extern crate quickcheck;
 
+pub use adaptation::{AdaptationMethod, ChromaticAdaptation};
+pub use adjust::{Adjustment, Curves, Exposure, Levels, ToneCurve};
 pub use channel::Channel;
+pub use cmyk::{Cmyk, CmykConverter};
+pub use colormap::{Colormap, Discrete, ListedColormap, Reversed};
+pub use colormatrix::ColorMatrix;
+pub use contrast::{adjust_contrast, apca_contrast, contrast_ratio, ContrastSpace, ContrastTarget, meets_aa, meets_aaa, meets_wcag, TextSize, WcagLevel};
+pub use difference::{Cie94Application, ColorDifference};
+pub use dither::{BitDepth, Dither, Ditherer};
+pub use filter::{CssFilter, FilterColor, FilterPipeline};
+pub use frame::{ChromaFilter, ChromaSiting, FrameConverter, FrameFormat};
+pub use gradient::{Gradient, GradientColor, HueInterpolation, InterpolationSpace, Samples};
+pub use hsl::Hsl;
+pub use icc::{IccColor, IccColorSpace, IccCurve, IccDeviceClass, IccError, IccHeader, IccLut, IccProfile, IccTransform, RenderingIntent,
+              write_icc_profile};
+pub use lab::Lab;
+pub use lut::{hald_identity, Lut3d, LutError, LutInterpolation};
+pub use oklab::Oklab;
+pub use palette::{DistanceMetric, NearestSearch, Palette};
+pub use quantize::{QuantizeColor, QuantizeSpace, Quantizer};
 pub use rgb::Rgb;
 pub use rgba::Rgba;
+pub use scheme::{diverging_scheme, qualitative_scheme, sequential_scheme, Cubehelix};
+pub use space::RgbSpace;
+pub use tonemap::{ToneMapOperator, ToneMapper};
+pub use transfer::{bt1886_eotf, bt1886_inverse_eotf, hlg_eotf, hlg_inverse_eotf, hlg_inverse_oetf, hlg_oetf,
+                   hlg_system_gamma, pq_eotf, pq_inverse_eotf, TransferFunction};
+pub use whitebalance::{cct_to_chromaticity, chromaticity_to_cct, WhiteBalance};
+pub use xyz::Xyz;
+pub use ycbcr::{YCbCr, YCbCrEncoding, YCbCrMatrix, YCbCrRange};
 
 use num::{Float, NumCast, One, PrimInt, Unsigned, Zero};
 use num::traits::cast;
@@ -44,9 +71,35 @@ use num::traits::cast;
 #[macro_use]
 mod simplecolor_macros;
 
+mod adaptation;
+mod adjust;
 mod channel;
+mod cmyk;
+mod colormap;
+mod colormap_data;
+mod colormatrix;
+mod contrast;
+mod difference;
+mod dither;
+mod filter;
+mod frame;
+mod gradient;
+mod hsl;
+mod icc;
+mod lab;
+mod lut;
+mod oklab;
+mod palette;
+mod quantize;
 mod rgb;
 mod rgba;
+mod scheme;
+mod space;
+mod tonemap;
+mod transfer;
+mod whitebalance;
+mod xyz;
+mod ycbcr;
 
 /// A generic color.
 pub trait Color<T: Channel> {
@@ -73,6 +126,16 @@ pub trait Color<T: Channel> {
 
     /// Convert a color to greyscale.
     fn to_greyscale(&self) -> Self;
+
+    /// Linearly interpolate between this color and another color, where
+    /// a `t` of 0 gives this color and a `t` of 1 gives the other color.
+    /// Integral channels are rounded to the nearest value.
+    fn lerp<F: Float>(&self, other: &Self, t: F) -> Self;
+
+    /// Mix a list of colors together, weighting each color by its
+    /// corresponding weight. The weights do not need to sum to one. If
+    /// the weights sum to zero, the default color is returned.
+    fn mix_weighted<F: Float>(colors: &[(Self, F)]) -> Self where Self: Sized;
 }
 
 // crate-private functions
@@ -109,10 +172,183 @@ fn integral_to_float<I, F>(x: I) -> F
     cast::<I, F>(x).unwrap() / cast(I::max_value()).unwrap()
 }
 
+/// Convert a generic floating point value to a primitive integer type,
+/// scaled from [0.0, 1.0] to [0, integer::max_value()] and rounded to
+/// the nearest integer. Values outside of [0.0, 1.0] are clamped.
+#[inline]
+fn float_to_integral<F, I>(x: F) -> I
+    where F: Float + NumCast,
+          I: PrimInt + Unsigned {
+    let max: F = cast(I::max_value()).unwrap();
+    cast((clamp_to_zero_one(x) * max).round()).unwrap_or(I::max_value())
+}
+
+/// Linearly interpolate between two channels, rounding integral
+/// channels to the nearest value.
+#[inline]
+fn lerp_channel<T: Channel>(a: T, b: T, t: f64) -> T {
+    // Wide integral channels do not survive a round trip through f64.
+    if t == 0.0 {
+        return a;
+    }
+    if t == 1.0 {
+        return b;
+    }
+    T::from_float(a.to_float::<f64>() * (1.0 - t) + b.to_float::<f64>() * t)
+}
+
+/// A small xorshift64* pseudo-random number generator, so that randomised
+/// algorithms give reproducible results for a given seed.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> XorShift {
+        // The state must never be zero.
+        XorShift(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a value in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a value in [0, n).
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_f64() * n as f64) as usize).min(n - 1)
+    }
+}
+
+/// The squared Euclidean distance between two 4-dimensional points.
+#[inline]
+fn distance_squared(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    (0..4).fold(0.0, |sum, i| sum + (a[i] - b[i]) * (a[i] - b[i]))
+}
+
+/// Convert an f64 constant to a generic floating point value.
+#[inline]
+fn float<F: Float>(x: f64) -> F {
+    cast(x).unwrap()
+}
+
+/// Wrap a hue angle in degrees so that it lies between 0 and 360.
+#[inline]
+fn wrap_hue<F: Float>(h: F) -> F {
+    let h = h % float(360.0);
+    if h < F::zero() { h + float(360.0) } else { h }
+}
+
+/// Find the chroma at which a color of fixed lightness and hue leaves the
+/// sRGB gamut, where `to_linear_rgb` converts a chroma to linear light sRGB.
+/// Returns `None` if `chroma` itself is in gamut, or otherwise the largest
+/// in gamut chroma below it, found by bisection.
+fn reduce_chroma_to_gamut<F, G>(chroma: F, to_linear_rgb: G) -> Option<F>
+    where F: Channel + Float,
+          G: Fn(F) -> Rgb<F> {
+    let in_gamut = |c: F| {
+        let rgb = to_linear_rgb(c);
+        let tolerance: F = float(1e-7);
+        let lower = -tolerance;
+        let upper = F::one() + tolerance;
+        rgb.r() >= lower && rgb.r() <= upper &&
+        rgb.g() >= lower && rgb.g() <= upper &&
+        rgb.b() >= lower && rgb.b() <= upper
+    };
+    if in_gamut(chroma) {
+        return None;
+    }
+
+    let (mut low, mut high) = (F::zero(), chroma);
+    for _ in 0..32 {
+        let mid = (low + high) / float(2.0);
+        if in_gamut(mid) { low = mid; } else { high = mid; }
+    }
+    Some(low)
+}
+
+/// Decode a single sRGB encoded component to linear light. Negative
+/// values are mirrored, so that out of gamut colors survive a round trip.
+#[inline]
+fn srgb_to_linear<F: Float>(x: F) -> F {
+    let magnitude = x.abs();
+    let linear = if magnitude <= float(0.04045) {
+        magnitude / float(12.92)
+    } else {
+        ((magnitude + float(0.055)) / float(1.055)).powf(float(2.4))
+    };
+    if x < F::zero() { -linear } else { linear }
+}
+
+/// Encode a single linear light component with the sRGB transfer function.
+/// Negative values are mirrored, as in `srgb_to_linear`.
+#[inline]
+fn linear_to_srgb<F: Float>(x: F) -> F {
+    let magnitude = x.abs();
+    let encoded = if magnitude <= float(0.0031308) {
+        magnitude * float(12.92)
+    } else {
+        float::<F>(1.055) * magnitude.powf(float(1.0 / 2.4)) - float(0.055)
+    };
+    if x < F::zero() { -encoded } else { encoded }
+}
+
+/// A 3x3 matrix, stored by rows.
+type Matrix3 = [[f64; 3]; 3];
+
+/// The 3x3 identity matrix.
+const IDENTITY3: Matrix3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// Multiply two 3x3 matrices. The result applies `b` first, then `a`.
+fn mat3_mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = (0..3).fold(0.0, |sum, k| sum + a[i][k] * b[k][j]);
+        }
+    }
+    out
+}
+
+/// Invert a 3x3 matrix. Singular matrices give non-finite entries.
+fn mat3_inverse(m: &Matrix3) -> Matrix3 {
+    let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let adjugate = [
+        [cofactor(1, 2, 1, 2), -cofactor(0, 2, 1, 2), cofactor(0, 1, 1, 2)],
+        [-cofactor(1, 2, 0, 2), cofactor(0, 2, 0, 2), -cofactor(0, 1, 0, 2)],
+        [cofactor(1, 2, 0, 1), -cofactor(0, 2, 0, 1), cofactor(0, 1, 0, 1)]
+    ];
+    let determinant = m[0][0] * adjugate[0][0] + m[0][1] * adjugate[1][0] + m[0][2] * adjugate[2][0];
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = adjugate[i][j] / determinant;
+        }
+    }
+    out
+}
+
+/// Multiply a column vector by a 3x3 matrix.
+#[inline]
+fn mat3_apply(m: &Matrix3, v: [f64; 3]) -> [f64; 3] {
+    [m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+     m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+     m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2]]
+}
+
 #[cfg(test)]
 #[allow(unused_imports)]
 mod test {
-    use super::{clamp, integral_to_float};
+    use super::{clamp, integral_to_float, Rgb};
     use quickcheck::{quickcheck, TestResult};
 
     macro_rules! discard_if(
@@ -128,6 +364,13 @@ mod test {
         lower <= x && x <= higher
     }
 
+    pub fn assert_near(actual: Rgb<f64>, expected: (f64, f64, f64), tolerance: f64) {
+        let (r, g, b) = actual.components();
+        assert!((r - expected.0).abs() < tolerance &&
+                (g - expected.1).abs() < tolerance &&
+                (b - expected.2).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+
     #[test]
     fn test_clamping_number_is_clamped_properly() {
         fn prop_is_clamped_between<T: PartialOrd + Copy>(x: T, mn: T, mx: T) -> TestResult {
@@ -0,0 +1,611 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{clamp, Channel, Rgb};
+
+/// An error encountered while reading a `.cube` file or building a lookup
+/// table.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum LutError {
+    /// The line with the given number, counting from one, could not be
+    /// understood.
+    InvalidLine(usize),
+
+    /// The table size is missing, out of range, or given for both one and
+    /// three dimensions.
+    InvalidSize,
+
+    /// The number of table entries does not match the size. Holds the
+    /// expected and actual number of entries.
+    EntryCount(usize, usize)
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LutError::InvalidLine(line) => write!(f, "invalid LUT data on line {}", line),
+            LutError::EntryCount(expected, found) => {
+                write!(f, "expected {} LUT entries, found {}", expected, found)
+            }
+            _ => f.write_str(self.description())
+        }
+    }
+}
+
+impl Error for LutError {
+    fn description(&self) -> &str {
+        match *self {
+            LutError::InvalidLine(_) => "invalid LUT data",
+            LutError::InvalidSize => "missing or invalid LUT size",
+            LutError::EntryCount(_, _) => "wrong number of LUT entries"
+        }
+    }
+}
+
+/// The method used to interpolate between the entries of a 3D lookup
+/// table.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum LutInterpolation {
+    /// Blend the eight surrounding entries.
+    Trilinear,
+
+    /// Blend the four entries of the surrounding tetrahedron, which keeps
+    /// neutral colors neutral and is faster.
+    Tetrahedral
+}
+
+/// A color lookup table, as found in Adobe and Resolve `.cube` files.
+/// Tables are either three dimensional, indexed by all three channels at
+/// once, or one dimensional, indexed by each channel separately.
+///
+/// ```rust
+/// use simplecolor::{Lut3d, Rgb};
+///
+/// // A 2×2×2 table which swaps red and blue.
+/// let cube = "LUT_3D_SIZE 2
+/// 0 0 0
+/// 0 0 1
+/// 0 1 0
+/// 0 1 1
+/// 1 0 0
+/// 1 0 1
+/// 1 1 0
+/// 1 1 1";
+/// let grade = Lut3d::parse_cube(cube).unwrap();
+/// let graded = grade.apply(&Rgb::with_components(0.2f32, 0.4, 0.6));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut3d {
+    /// Title of the table, if any
+    title: Option<String>,
+
+    /// Entries along each dimension
+    size: usize,
+
+    /// Whether each channel is looked up separately
+    one_dimensional: bool,
+
+    /// Input values mapped to the first entry
+    domain_min: [f64; 3],
+
+    /// Input values mapped to the last entry
+    domain_max: [f64; 3],
+
+    /// Entries, with red varying fastest
+    table: Vec<[f64; 3]>,
+
+    /// Interpolation between entries of a 3D table
+    interpolation: LutInterpolation
+}
+
+impl Lut3d {
+    /// Create a 3D table of the given size that leaves colors unchanged.
+    pub fn identity(size: usize) -> Lut3d {
+        Lut3d::from_fn(size, |rgb| rgb)
+    }
+
+    /// Create a 3D table of the given size by sampling a function over the
+    /// unit cube. This bakes any of the crate's transforms, or a
+    /// composition of them, into a table.
+    ///
+    /// ```rust
+    /// use simplecolor::{Lut3d, ToneMapOperator, ToneMapper};
+    ///
+    /// let mapper = ToneMapper::new(ToneMapOperator::AcesFitted);
+    /// let lut = Lut3d::from_fn(33, |rgb| mapper.apply(&rgb.to_linear()).to_srgb());
+    /// ```
+    pub fn from_fn<M: Fn(Rgb<f64>) -> Rgb<f64>>(size: usize, f: M) -> Lut3d {
+        assert!(size >= 2, "a lookup table needs at least two entries along each dimension");
+        let step = 1.0 / (size - 1) as f64;
+        let mut table = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let rgb = Rgb::with_components(r as f64 * step, g as f64 * step, b as f64 * step);
+                    table.push(to_array(&f(rgb)));
+                }
+            }
+        }
+        Lut3d::from_parts(size, false, table)
+    }
+
+    /// Create a 3D table from its entries, with red varying fastest and
+    /// blue slowest.
+    pub fn from_table(size: usize, table: &[Rgb<f64>]) -> Result<Lut3d, LutError> {
+        if size < 2 {
+            return Err(LutError::InvalidSize);
+        }
+        if table.len() != size * size * size {
+            return Err(LutError::EntryCount(size * size * size, table.len()));
+        }
+        Ok(Lut3d::from_parts(size, false, table.iter().map(to_array).collect()))
+    }
+
+    /// Create a 1D table from its entries, each holding the red, green and
+    /// blue outputs for evenly spaced inputs.
+    pub fn from_1d_table(table: &[Rgb<f64>]) -> Result<Lut3d, LutError> {
+        if table.len() < 2 {
+            return Err(LutError::InvalidSize);
+        }
+        Ok(Lut3d::from_parts(table.len(), true, table.iter().map(to_array).collect()))
+    }
+
+    /// Create a 3D table from a Hald CLUT image, given as its pixels in
+    /// row-major order. A Hald image of level L is L³ pixels square and
+    /// holds a table of size L², with red varying fastest.
+    pub fn from_hald<T: Channel>(pixels: &[Rgb<T>]) -> Result<Lut3d, LutError> {
+        let level = try!(hald_level(pixels.len()).ok_or(LutError::InvalidSize));
+        let table: Vec<Rgb<f64>> = pixels.iter().map(|pixel| pixel.convert()).collect();
+        Lut3d::from_table(level * level, &table)
+    }
+
+    /// Render the table as a Hald CLUT image of the given level, returning
+    /// its L³ by L³ pixels in row-major order. Tables of other sizes are
+    /// resampled.
+    pub fn to_hald<T: Channel>(&self, level: usize) -> Vec<Rgb<T>> {
+        assert!(level >= 2, "a Hald CLUT needs a level of at least two");
+        Lut3d::from_fn(level * level, |rgb| self.apply(&rgb)).table.iter()
+            .map(|c| Rgb::with_components(T::from_float(c[0]), T::from_float(c[1]), T::from_float(c[2])))
+            .collect()
+    }
+
+    fn from_parts(size: usize, one_dimensional: bool, table: Vec<[f64; 3]>) -> Lut3d {
+        Lut3d {
+            title: None,
+            size: size,
+            one_dimensional: one_dimensional,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            table: table,
+            interpolation: LutInterpolation::Tetrahedral
+        }
+    }
+
+    /// Parse the text of a `.cube` file. Both `LUT_1D_SIZE` and
+    /// `LUT_3D_SIZE` tables are supported, with an optional `TITLE` and
+    /// `DOMAIN_MIN` and `DOMAIN_MAX`, or Resolve's `LUT_1D_INPUT_RANGE`
+    /// and `LUT_3D_INPUT_RANGE`. Other keywords are ignored.
+    pub fn parse_cube(text: &str) -> Result<Lut3d, LutError> {
+        let (mut title, mut size_1d, mut size_3d) = (None, None, None);
+        let (mut domain_min, mut domain_max) = ([0.0; 3], [1.0; 3]);
+        let mut table = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or("");
+            if !keyword.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                table.push(try!(parse_triple(line, number)));
+                continue;
+            }
+            if !table.is_empty() {
+                return Err(LutError::InvalidLine(number));
+            }
+            let rest = line[keyword.len()..].trim();
+            match keyword {
+                "TITLE" => {
+                    if rest.len() < 2 || !rest.starts_with('"') || !rest.ends_with('"') {
+                        return Err(LutError::InvalidLine(number));
+                    }
+                    title = Some(rest[1..rest.len() - 1].to_string());
+                }
+                "LUT_1D_SIZE" => size_1d = Some(try!(parse_size(rest, number))),
+                "LUT_3D_SIZE" => size_3d = Some(try!(parse_size(rest, number))),
+                "DOMAIN_MIN" => domain_min = try!(parse_triple(rest, number)),
+                "DOMAIN_MAX" => domain_max = try!(parse_triple(rest, number)),
+                "LUT_1D_INPUT_RANGE" | "LUT_3D_INPUT_RANGE" => {
+                    let range: Vec<f64> = try!(parse_numbers(rest, number));
+                    if range.len() != 2 {
+                        return Err(LutError::InvalidLine(number));
+                    }
+                    domain_min = [range[0]; 3];
+                    domain_max = [range[1]; 3];
+                }
+                _ => {}
+            }
+        }
+
+        let (size, one_dimensional, expected) = match (size_1d, size_3d) {
+            (Some(size), None) if size <= 65536 => (size, true, size),
+            (None, Some(size)) if size <= 256 => (size, false, size * size * size),
+            _ => return Err(LutError::InvalidSize)
+        };
+        if table.len() != expected {
+            return Err(LutError::EntryCount(expected, table.len()));
+        }
+        for i in 0..3 {
+            if domain_max[i] <= domain_min[i] {
+                return Err(LutError::InvalidSize);
+            }
+        }
+
+        let mut lut = Lut3d::from_parts(size, one_dimensional, table);
+        lut.title = title;
+        lut.domain_min = domain_min;
+        lut.domain_max = domain_max;
+        Ok(lut)
+    }
+
+    /// Write the table as the text of a `.cube` file.
+    pub fn to_cube(&self) -> String {
+        let mut text = String::new();
+        if let Some(ref title) = self.title {
+            text.push_str(&format!("TITLE \"{}\"\n", title));
+        }
+        let dimensions = if self.one_dimensional { 1 } else { 3 };
+        text.push_str(&format!("LUT_{}D_SIZE {}\n", dimensions, self.size));
+        if self.domain_min != [0.0; 3] || self.domain_max != [1.0; 3] {
+            let (min, max) = (self.domain_min, self.domain_max);
+            text.push_str(&format!("DOMAIN_MIN {} {} {}\n", min[0], min[1], min[2]));
+            text.push_str(&format!("DOMAIN_MAX {} {} {}\n", max[0], max[1], max[2]));
+        }
+        for entry in &self.table {
+            text.push_str(&format!("{:.6} {:.6} {:.6}\n", entry[0], entry[1], entry[2]));
+        }
+        text
+    }
+
+    /// Returns the title of the table.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_ref().map(|title| &title[..])
+    }
+
+    /// Set the title written to `.cube` files.
+    pub fn set_title(&mut self, title: &str) { self.title = Some(title.to_string()); }
+
+    /// Returns the number of entries along each dimension.
+    #[inline]
+    pub fn size(&self) -> usize { self.size }
+
+    /// Returns true if each channel is looked up separately.
+    #[inline]
+    pub fn is_1d(&self) -> bool { self.one_dimensional }
+
+    /// Returns the input values mapped to the first entry.
+    #[inline]
+    pub fn domain_min(&self) -> [f64; 3] { self.domain_min }
+
+    /// Returns the input values mapped to the last entry.
+    #[inline]
+    pub fn domain_max(&self) -> [f64; 3] { self.domain_max }
+
+    /// Set the range of input values covered by the table. Inputs outside
+    /// of the domain are clamped to it. Panics if a minimum is not below
+    /// its maximum.
+    pub fn set_domain(&mut self, min: [f64; 3], max: [f64; 3]) {
+        for i in 0..3 {
+            assert!(min[i] < max[i], "the domain minimum must be below its maximum");
+        }
+        self.domain_min = min;
+        self.domain_max = max;
+    }
+
+    /// Returns the interpolation used by 3D tables.
+    #[inline]
+    pub fn interpolation(&self) -> LutInterpolation { self.interpolation }
+
+    /// Set the interpolation used by 3D tables. The default is tetrahedral.
+    #[inline]
+    pub fn set_interpolation(&mut self, interpolation: LutInterpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Returns the entry of a 3D table at the given grid position, or of a
+    /// 1D table at the red index.
+    pub fn entry(&self, r: usize, g: usize, b: usize) -> Rgb<f64> {
+        let i = if self.one_dimensional { r } else { r + self.size * (g + self.size * b) };
+        let e = self.table[i];
+        Rgb::with_components(e[0], e[1], e[2])
+    }
+
+    /// Look up a color in the table.
+    pub fn apply<T: Channel>(&self, rgb: &Rgb<T>) -> Rgb<T> {
+        let input: [f64; 3] = [rgb.r().to_float(), rgb.g().to_float(), rgb.b().to_float()];
+        let c = self.lookup(input);
+        Rgb::with_components(T::from_float(c[0]), T::from_float(c[1]), T::from_float(c[2]))
+    }
+
+    /// Look up a buffer of colors in place.
+    pub fn apply_in_place<T: Channel>(&self, pixels: &mut [Rgb<T>]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+
+    /// Returns a 3D table which applies this table and then another, with
+    /// the size and domain of this table, or of the other table if this one
+    /// is one dimensional.
+    pub fn then(&self, next: &Lut3d) -> Lut3d {
+        let source = if self.one_dimensional { next } else { self };
+        let (min, max) = (source.domain_min, source.domain_max);
+        let mut lut = Lut3d::from_fn(source.size, |rgb| {
+            let c = to_array(&rgb);
+            let x = [min[0] + c[0] * (max[0] - min[0]),
+                     min[1] + c[1] * (max[1] - min[1]),
+                     min[2] + c[2] * (max[2] - min[2])];
+            let y = next.lookup(self.lookup(x));
+            Rgb::with_components(y[0], y[1], y[2])
+        });
+        lut.domain_min = min;
+        lut.domain_max = max;
+        lut.interpolation = self.interpolation;
+        lut
+    }
+
+    fn lookup(&self, input: [f64; 3]) -> [f64; 3] {
+        let scale = (self.size - 1) as f64;
+        let mut index = [0; 3];
+        let mut fraction = [0.0; 3];
+        for i in 0..3 {
+            let x = (input[i] - self.domain_min[i]) / (self.domain_max[i] - self.domain_min[i]);
+            let position = clamp(x, 0.0, 1.0) * scale;
+            index[i] = (position.floor() as usize).min(self.size - 2);
+            fraction[i] = position - index[i] as f64;
+        }
+
+        if self.one_dimensional {
+            let mut out = [0.0; 3];
+            for i in 0..3 {
+                let (a, b) = (self.table[index[i]][i], self.table[index[i] + 1][i]);
+                out[i] = a + (b - a) * fraction[i];
+            }
+            return out;
+        }
+
+        let (size, table) = (self.size, &self.table);
+        let at = |r: usize, g: usize, b: usize| {
+            table[index[0] + r + size * (index[1] + g + size * (index[2] + b))]
+        };
+        let (fr, fg, fb) = (fraction[0], fraction[1], fraction[2]);
+        match self.interpolation {
+            LutInterpolation::Trilinear => {
+                let mut out = [0.0; 3];
+                for corner in 0..8 {
+                    let (r, g, b) = (corner & 1, (corner >> 1) & 1, corner >> 2);
+                    let weight = (if r == 1 { fr } else { 1.0 - fr }) *
+                                 (if g == 1 { fg } else { 1.0 - fg }) *
+                                 (if b == 1 { fb } else { 1.0 - fb });
+                    let value = at(r, g, b);
+                    for i in 0..3 {
+                        out[i] += weight * value[i];
+                    }
+                }
+                out
+            }
+            LutInterpolation::Tetrahedral => {
+                // Walk from the origin of the cell to its far corner along
+                // the edges in order of decreasing fraction.
+                let (first, second, weights) = if fr >= fg {
+                    if fg >= fb {
+                        ((1, 0, 0), (1, 1, 0), [1.0 - fr, fr - fg, fg - fb, fb])
+                    } else if fr >= fb {
+                        ((1, 0, 0), (1, 0, 1), [1.0 - fr, fr - fb, fb - fg, fg])
+                    } else {
+                        ((0, 0, 1), (1, 0, 1), [1.0 - fb, fb - fr, fr - fg, fg])
+                    }
+                } else if fb >= fg {
+                    ((0, 0, 1), (0, 1, 1), [1.0 - fb, fb - fg, fg - fr, fr])
+                } else if fb >= fr {
+                    ((0, 1, 0), (0, 1, 1), [1.0 - fg, fg - fb, fb - fr, fr])
+                } else {
+                    ((0, 1, 0), (1, 1, 0), [1.0 - fg, fg - fr, fr - fb, fb])
+                };
+                let corners = [at(0, 0, 0), at(first.0, first.1, first.2),
+                               at(second.0, second.1, second.2), at(1, 1, 1)];
+                let mut out = [0.0; 3];
+                for (corner, weight) in corners.iter().zip(&weights) {
+                    for i in 0..3 {
+                        out[i] += weight * corner[i];
+                    }
+                }
+                out
+            }
+        }
+    }
+}
+
+/// Create the identity Hald CLUT image of the given level, which is L³
+/// pixels square. Grading a copy of it in an image editor and reading it
+/// back with `Lut3d::from_hald` captures the grade as a table.
+pub fn hald_identity<T: Channel>(level: usize) -> Vec<Rgb<T>> {
+    Lut3d::identity(2).to_hald(level)
+}
+
+/// The level of a Hald CLUT image with the given number of pixels.
+fn hald_level(pixels: usize) -> Option<usize> {
+    (2..17).find(|&level: &usize| level.pow(6) == pixels)
+}
+
+fn to_array(rgb: &Rgb<f64>) -> [f64; 3] {
+    [rgb.r(), rgb.g(), rgb.b()]
+}
+
+fn parse_numbers(text: &str, line: usize) -> Result<Vec<f64>, LutError> {
+    text.split_whitespace()
+        .map(|word| word.parse().map_err(|_| LutError::InvalidLine(line)))
+        .collect()
+}
+
+fn parse_triple(text: &str, line: usize) -> Result<[f64; 3], LutError> {
+    let numbers = try!(parse_numbers(text, line));
+    if numbers.len() != 3 {
+        return Err(LutError::InvalidLine(line));
+    }
+    Ok([numbers[0], numbers[1], numbers[2]])
+}
+
+fn parse_size(text: &str, line: usize) -> Result<usize, LutError> {
+    match text.parse() {
+        Ok(size) if size >= 2 => Ok(size),
+        Ok(_) => Err(LutError::InvalidSize),
+        Err(_) => Err(LutError::InvalidLine(line))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+    use ::test::assert_near;
+
+    #[test]
+    fn identity_leaves_colors_unchanged() {
+        let mut lut = Lut3d::identity(17);
+        let col = Rgb::with_components(0.13f64, 0.57, 0.91);
+        assert_near(lut.apply(&col), (0.13, 0.57, 0.91), 1e-12);
+        lut.set_interpolation(LutInterpolation::Trilinear);
+        assert_near(lut.apply(&col), (0.13, 0.57, 0.91), 1e-12);
+        assert_eq!(lut.apply(&Rgb::with_components(12u8, 200, 255)).components(), (12, 200, 255));
+    }
+
+    #[test]
+    fn parses_3d_cube() {
+        let text = "# A comment\nTITLE \"Swap\"\nLUT_3D_SIZE 2\n\n\
+                    0 0 0\n0 0 1\n0 1 0\n0 1 1\n1 0 0\n1 0 1\n1 1 0\n1 1 1\n";
+        let lut = Lut3d::parse_cube(text).unwrap();
+        assert_eq!(lut.title(), Some("Swap"));
+        assert_eq!(lut.size(), 2);
+        assert!(!lut.is_1d());
+        assert_near(lut.apply(&Rgb::with_components(0.2f64, 0.5, 0.7)), (0.7, 0.5, 0.2), 1e-12);
+    }
+
+    #[test]
+    fn parses_1d_cube_with_domain() {
+        let text = "LUT_1D_SIZE 3\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n0 0 0\n0.25 0.5 1\n1 1 1\n";
+        let lut = Lut3d::parse_cube(text).unwrap();
+        assert!(lut.is_1d());
+        assert_eq!(lut.domain_max(), [2.0; 3]);
+        assert_near(lut.apply(&Rgb::with_components(0.5f64, 1.0, 3.0)), (0.125, 0.5, 1.0), 1e-12);
+    }
+
+    #[test]
+    fn trilinear_and_tetrahedral_agree_on_linear_tables() {
+        let f = |rgb: Rgb<f64>| Rgb::with_components(0.5 * rgb.r() + 0.25 * rgb.b(), rgb.g(), 1.0 - rgb.b());
+        let mut lut = Lut3d::from_fn(5, f);
+        let col = Rgb::with_components(0.31f64, 0.77, 0.42);
+        let expected = f(col).components();
+        assert_near(lut.apply(&col), expected, 1e-12);
+        lut.set_interpolation(LutInterpolation::Trilinear);
+        assert_near(lut.apply(&col), expected, 1e-12);
+    }
+
+    #[test]
+    fn tetrahedral_keeps_greys_neutral() {
+        let lut = Lut3d::from_fn(3, |rgb| Rgb::with_components(rgb.r() * rgb.r(), rgb.g() * rgb.g(),
+                                                               rgb.b() * rgb.b()));
+        let (r, g, b) = lut.apply(&Rgb::with_components(0.3f64, 0.3, 0.3)).components();
+        assert_eq!(r, g);
+        assert_eq!(g, b);
+    }
+
+    #[test]
+    fn cube_round_trip() {
+        let mut lut = Lut3d::from_fn(4, |rgb| Rgb::with_components(rgb.g(), rgb.b() * 0.5, rgb.r()));
+        lut.set_title("Round trip");
+        lut.set_domain([0.0; 3], [4.0; 3]);
+        let parsed = Lut3d::parse_cube(&lut.to_cube()).unwrap();
+        assert_eq!(parsed.title(), Some("Round trip"));
+        assert_eq!(parsed.domain_max(), [4.0; 3]);
+        assert_eq!(parsed.size(), 4);
+        for i in 0..4 {
+            let (a, b) = (lut.entry(i, 3 - i, i), parsed.entry(i, 3 - i, i));
+            assert_near(a, b.components(), 1e-6);
+        }
+    }
+
+    #[test]
+    fn composition_applies_both_tables() {
+        let invert = Lut3d::from_fn(9, |rgb| Rgb::with_components(1.0 - rgb.r(), 1.0 - rgb.g(), 1.0 - rgb.b()));
+        let halve = Lut3d::parse_cube("LUT_1D_SIZE 2\n0 0 0\n0.5 0.5 0.5\n").unwrap();
+        let composed = invert.then(&halve);
+        assert!(!composed.is_1d());
+        assert_near(composed.apply(&Rgb::with_components(0.25f64, 0.5, 1.0)), (0.375, 0.25, 0.0), 1e-12);
+        assert_near(halve.then(&invert).apply(&Rgb::with_components(0.5f64, 0.0, 1.0)), (0.75, 1.0, 0.5), 1e-12);
+    }
+
+    #[test]
+    fn hald_identity_layout() {
+        let hald: Vec<Rgb<u8>> = hald_identity(2);
+        assert_eq!(hald.len(), 64);
+        assert_eq!(hald[0].components(), (0, 0, 0));
+        assert_eq!(hald[1].components(), (85, 0, 0));
+        assert_eq!(hald[4].components(), (0, 85, 0));
+        assert_eq!(hald[16].components(), (0, 0, 85));
+        assert_eq!(hald[63].components(), (255, 255, 255));
+        assert_eq!(hald_identity::<u16>(4).len(), 4096);
+    }
+
+    #[test]
+    fn hald_round_trip() {
+        let lut = Lut3d::from_fn(9, |rgb| Rgb::with_components(rgb.g(), rgb.b(), rgb.r() * 0.5));
+        let hald: Vec<Rgb<u16>> = lut.to_hald(3);
+        let back = Lut3d::from_hald(&hald).unwrap();
+        assert_eq!(back.size(), 9);
+        let col = Rgb::with_components(0.2f64, 0.6, 0.9);
+        assert_near(back.apply(&col), lut.apply(&col).components(), 1e-4);
+    }
+
+    #[test]
+    fn applies_graded_hald_image() {
+        let mut hald: Vec<Rgb<u8>> = hald_identity(4);
+        for pixel in hald.iter_mut() {
+            *pixel = Rgb::with_components(255 - pixel.r(), 255 - pixel.g(), 255 - pixel.b());
+        }
+        let lut = Lut3d::from_hald(&hald).unwrap();
+        assert_eq!(lut.apply(&Rgb::with_components(10u8, 100, 250)).components(), (245, 155, 5));
+        assert_eq!(Lut3d::from_hald(&hald[1..]), Err(LutError::InvalidSize));
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        assert_eq!(Lut3d::parse_cube("0 0 0\n1 1 1\n"), Err(LutError::InvalidSize));
+        assert_eq!(Lut3d::parse_cube("LUT_3D_SIZE 2\n0 0 0\n"), Err(LutError::EntryCount(8, 1)));
+        assert_eq!(Lut3d::parse_cube("LUT_1D_SIZE 2\n0 0 0\n1 x 1\n"), Err(LutError::InvalidLine(3)));
+        assert_eq!(Lut3d::parse_cube("LUT_1D_SIZE 2\n0 0 0\nLUT_3D_SIZE 2\n1 1 1\n"),
+                   Err(LutError::InvalidLine(3)));
+        assert_eq!(Lut3d::from_table(2, &[Rgb::new(); 7]), Err(LutError::EntryCount(8, 7)));
+    }
+}
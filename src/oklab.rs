@@ -0,0 +1,163 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{clamp_to_zero_one, float, reduce_chroma_to_gamut, wrap_hue, Channel, Color, Rgb};
+
+/// A color in Björn Ottosson's Oklab color space. L is the perceptual
+/// lightness between 0 and 1, and a and b are the green-red and
+/// blue-yellow opponent axes. Oklab predicts hue and lightness more
+/// uniformly than CIE Lab.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Oklab<F: Float> {
+    /// Lightness component
+    l: F,
+
+    /// Green-red component
+    a: F,
+
+    /// Blue-yellow component
+    b: F
+}
+
+impl<F: Float> Oklab<F> {
+    /// Construct an Oklab color piecewise from individual components.
+    pub fn with_components(l: F, a: F, b: F) -> Oklab<F> {
+        Oklab {
+            l: l,
+            a: a,
+            b: b
+        }
+    }
+
+    /// Construct an Oklab color from its cylindrical Oklch representation,
+    /// with the hue given in degrees.
+    pub fn from_lch(l: F, c: F, h: F) -> Oklab<F> {
+        let h = h.to_radians();
+        Oklab::with_components(l, c * h.cos(), c * h.sin())
+    }
+
+    /// Returns the lightness component.
+    #[inline]
+    pub fn l(&self) -> F { self.l }
+
+    /// Returns the green-red component.
+    #[inline]
+    pub fn a(&self) -> F { self.a }
+
+    /// Returns the blue-yellow component.
+    #[inline]
+    pub fn b(&self) -> F { self.b }
+
+    /// Return each component in a 3-element tuple. Useful for destructuring.
+    pub fn components(&self) -> (F, F, F) {
+        (self.l, self.a, self.b)
+    }
+
+    /// Returns the chroma (colorfulness) of the color.
+    pub fn chroma(&self) -> F {
+        self.a.hypot(self.b)
+    }
+
+    /// Returns the hue angle of the color in degrees, between 0 and 360.
+    pub fn hue(&self) -> F {
+        wrap_hue(self.b.atan2(self.a).to_degrees())
+    }
+}
+
+impl<F: Channel + Float> Oklab<F> {
+    /// Convert an sRGB encoded color to Oklab.
+    pub fn from_rgb(rgb: &Rgb<F>) -> Oklab<F> {
+        Oklab::from_linear_rgb(&rgb.to_linear())
+    }
+
+    /// Convert a linear light sRGB color to Oklab.
+    pub fn from_linear_rgb(rgb: &Rgb<F>) -> Oklab<F> {
+        let (r, g, b) = rgb.components();
+        let l = (r * float(0.4122214708) + g * float(0.5363325363) + b * float(0.0514459929)).cbrt();
+        let m = (r * float(0.2119034982) + g * float(0.6806995451) + b * float(0.1073969566)).cbrt();
+        let s = (r * float(0.0883024619) + g * float(0.2817188376) + b * float(0.6299787005)).cbrt();
+        Oklab::with_components(
+            l * float(0.2104542553) + m * float(0.7936177850) - s * float(0.0040720468),
+            l * float(1.9779984951) - m * float(2.4285922050) + s * float(0.4505937099),
+            l * float(0.0259040371) + m * float(0.7827717662) - s * float(0.8086757660))
+    }
+
+    /// Convert this color to an sRGB encoded color. Colors outside of the
+    /// sRGB gamut will have channels outside of [0, 1].
+    pub fn to_rgb(&self) -> Rgb<F> {
+        self.to_linear_rgb().to_srgb()
+    }
+
+    /// Convert this color to an sRGB encoded color, reducing the chroma
+    /// until it lies within the sRGB gamut. The lightness is clamped between
+    /// 0 and 1, and the hue is preserved.
+    pub fn to_rgb_in_gamut(&self) -> Rgb<F> {
+        let l = clamp_to_zero_one(self.l);
+        let (chroma, hue) = (self.chroma(), self.hue());
+        match reduce_chroma_to_gamut(chroma, |c| Oklab::from_lch(l, c, hue).to_linear_rgb()) {
+            Some(c) => Oklab::from_lch(l, c, hue).to_rgb().normalise(),
+            None => Oklab::with_components(l, self.a, self.b).to_rgb().normalise()
+        }
+    }
+
+    /// Convert this color to a linear light sRGB color.
+    pub fn to_linear_rgb(&self) -> Rgb<F> {
+        let l = self.l + self.a * float(0.3963377774) + self.b * float(0.2158037573);
+        let m = self.l - self.a * float(0.1055613458) - self.b * float(0.0638541728);
+        let s = self.l - self.a * float(0.0894841775) - self.b * float(1.2914855480);
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        Rgb::with_components(
+            l * float(4.0767416621) - m * float(3.3077115913) + s * float(0.2309699292),
+            m * float(2.6097574011) - l * float(1.2684380046) - s * float(0.3413193965),
+            s * float(1.7076147010) - l * float(0.0041960863) - m * float(0.7034186147))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn white_has_unit_lightness_and_no_chroma() {
+        let white = Oklab::from_rgb(&Rgb::with_components(1.0f64, 1.0, 1.0));
+        assert!((white.l() - 1.0).abs() < 1e-4);
+        assert!(white.chroma() < 1e-4);
+    }
+
+    #[test]
+    fn round_trip_through_rgb() {
+        let col = Rgb::with_components(0.9f64, 0.4, 0.05);
+        let (r, g, b) = Oklab::from_rgb(&col).to_rgb().components();
+        assert!((r - 0.9).abs() < 1e-6);
+        assert!((g - 0.4).abs() < 1e-6);
+        assert!((b - 0.05).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gamut_mapping_preserves_lightness() {
+        let vivid = Oklab::from_lch(0.7f64, 0.4, 150.0);
+        let mapped = Oklab::from_rgb(&vivid.to_rgb_in_gamut());
+        assert!((mapped.l() - 0.7).abs() < 1e-4);
+        assert!((mapped.hue() - 150.0).abs() < 0.5);
+        assert!(mapped.chroma() < 0.4);
+    }
+}
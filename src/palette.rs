@@ -0,0 +1,315 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp::Ordering;
+use std::ops::Index;
+use std::slice::Iter;
+
+use super::{distance_squared, Lab, QuantizeColor, Rgb};
+
+/// The measure of distance used to find the nearest color in a `Palette`.
+/// Alpha, where present, is compared as a fourth channel on the same scale.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Euclidean distance between the sRGB encoded channels.
+    Rgb,
+
+    /// Euclidean distance between the sRGB encoded channels, with the
+    /// squared differences of red, green and blue weighted 2:4:3 to
+    /// approximate the eye's sensitivity to each channel.
+    WeightedRgb,
+
+    /// Euclidean distance in CIE `Lab`, also known as CIE76 ΔE\*ab.
+    Lab
+}
+
+impl DistanceMetric {
+    /// Convert sRGB and alpha components to a point in which this metric is
+    /// the Euclidean distance.
+    fn to_point(self, c: [f64; 4]) -> [f64; 4] {
+        match self {
+            DistanceMetric::Rgb => c,
+            DistanceMetric::WeightedRgb => {
+                let (r, g, b) = (2.0f64.sqrt(), 2.0, 3.0f64.sqrt());
+                [c[0] * r, c[1] * g, c[2] * b, c[3] * g]
+            }
+            DistanceMetric::Lab => {
+                let (l, a, b) = Lab::from_rgb(&Rgb::with_components(c[0], c[1], c[2])).components();
+                [l, a, b, c[3] * 100.0]
+            }
+        }
+    }
+}
+
+/// An ordered set of colors, such as the color table of an indexed image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette<C> {
+    /// The colors of the palette
+    colors: Vec<C>
+}
+
+impl<C> Palette<C> {
+    /// Create a palette from a list of colors.
+    pub fn new(colors: Vec<C>) -> Palette<C> {
+        Palette {
+            colors: colors
+        }
+    }
+
+    /// Returns the colors of the palette.
+    #[inline]
+    pub fn colors(&self) -> &[C] { &self.colors }
+
+    /// Returns the number of colors in the palette.
+    #[inline]
+    pub fn len(&self) -> usize { self.colors.len() }
+
+    /// Returns true if the palette has no colors.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.colors.is_empty() }
+
+    /// Returns the color at an index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&C> { self.colors.get(index) }
+
+    /// Returns an iterator over the colors of the palette.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, C> { self.colors.iter() }
+
+    /// Consume the palette, returning its colors.
+    #[inline]
+    pub fn into_colors(self) -> Vec<C> { self.colors }
+}
+
+impl<C: QuantizeColor> Palette<C> {
+    /// Returns the index of the palette color nearest to `color`, or `None`
+    /// if the palette is empty. If several colors are equally near, the
+    /// lowest index is returned. As with `NearestSearch`, distances that
+    /// cannot be compared are skipped, falling back to index 0.
+    pub fn nearest_index(&self, color: &C, metric: DistanceMetric) -> Option<usize> {
+        if self.colors.is_empty() {
+            return None;
+        }
+        let target = metric.to_point(color.to_rgba_components());
+        let mut best = (0, 1.0 / 0.0);
+        for (i, c) in self.colors.iter().enumerate() {
+            let d = distance_squared(&metric.to_point(c.to_rgba_components()), &target);
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        Some(best.0)
+    }
+
+    /// Returns the palette color nearest to `color`, or `None` if the
+    /// palette is empty.
+    pub fn nearest(&self, color: &C, metric: DistanceMetric) -> Option<&C> {
+        self.nearest_index(color, metric).map(|i| &self.colors[i])
+    }
+
+    /// Map each color to the index of its nearest palette color, as used by
+    /// indexed image formats. A k-d tree of the palette is built once, so
+    /// this is much faster than calling `nearest_index` for every color.
+    /// Panics if the palette is empty and `colors` is not.
+    pub fn index_colors(&self, colors: &[C], metric: DistanceMetric) -> Vec<usize> {
+        if colors.is_empty() {
+            return Vec::new();
+        }
+        assert!(!self.is_empty());
+        let search = NearestSearch::new(self, metric);
+        colors.iter().map(|c| search.nearest(c.to_rgba_components())).collect()
+    }
+}
+
+impl<C> Index<usize> for Palette<C> {
+    type Output = C;
+
+    fn index(&self, index: usize) -> &C {
+        &self.colors[index]
+    }
+}
+
+impl<'a, C> IntoIterator for &'a Palette<C> {
+    type Item = &'a C;
+    type IntoIter = Iter<'a, C>;
+
+    fn into_iter(self) -> Iter<'a, C> {
+        self.colors.iter()
+    }
+}
+
+/// A reusable nearest color search over the colors of a palette.
+pub struct NearestSearch {
+    tree: KdTree,
+    metric: DistanceMetric
+}
+
+impl NearestSearch {
+    /// Build a search over a palette. Panics if the palette is empty.
+    pub fn new<C: QuantizeColor>(palette: &Palette<C>, metric: DistanceMetric) -> NearestSearch {
+        assert!(!palette.is_empty());
+        NearestSearch {
+            tree: KdTree::new(palette.iter().map(|c| metric.to_point(c.to_rgba_components())).collect()),
+            metric: metric
+        }
+    }
+
+    /// Returns the index of the palette color nearest to some sRGB and
+    /// alpha components. If no distance to `color` can be compared, as when
+    /// it has a `NaN` component, index 0 is returned.
+    pub fn nearest(&self, color: [f64; 4]) -> usize {
+        self.tree.nearest(&self.metric.to_point(color))
+    }
+}
+
+/// A node of a `KdTree`.
+struct KdNode {
+    point: [f64; 4],
+    index: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>
+}
+
+/// A 4-dimensional k-d tree for nearest neighbour searches.
+struct KdTree {
+    nodes: Vec<KdNode>,
+    root: Option<usize>
+}
+
+impl KdTree {
+    fn new(points: Vec<[f64; 4]>) -> KdTree {
+        let mut entries: Vec<_> = points.into_iter().enumerate().map(|(i, p)| (p, i)).collect();
+        let mut tree = KdTree {
+            nodes: Vec::with_capacity(entries.len()),
+            root: None
+        };
+        tree.root = tree.build(&mut entries);
+        tree
+    }
+
+    /// Build a subtree from some points, splitting on the median of the
+    /// axis with the widest range.
+    fn build(&mut self, entries: &mut [([f64; 4], usize)]) -> Option<usize> {
+        if entries.is_empty() {
+            return None;
+        }
+
+        let axis = (0..4).map(|axis| {
+            let min = entries.iter().fold(1.0 / 0.0, |m: f64, e| m.min(e.0[axis]));
+            let max = entries.iter().fold(-1.0 / 0.0, |m: f64, e| m.max(e.0[axis]));
+            (axis, max - min)
+        }).fold((0, -1.0), |widest, next| if next.1 > widest.1 { next } else { widest }).0;
+        entries.sort_by(|a, b| a.0[axis].partial_cmp(&b.0[axis]).unwrap_or(Ordering::Equal));
+
+        let mid = entries.len() / 2;
+        let node = self.nodes.len();
+        self.nodes.push(KdNode {
+            point: entries[mid].0,
+            index: entries[mid].1,
+            axis: axis,
+            left: None,
+            right: None
+        });
+        let (lower, upper) = entries.split_at_mut(mid);
+        self.nodes[node].left = self.build(lower);
+        self.nodes[node].right = self.build(&mut upper[1..]);
+        Some(node)
+    }
+
+    /// Returns the index of the nearest point, preferring the lowest index
+    /// among equally near points. Falls back to index 0 when no distance is
+    /// less than infinity, such as for a `NaN` target.
+    fn nearest(&self, target: &[f64; 4]) -> usize {
+        let mut best = (0, 1.0 / 0.0);
+        self.search(self.root, target, &mut best);
+        best.0
+    }
+
+    fn search(&self, node: Option<usize>, target: &[f64; 4], best: &mut (usize, f64)) {
+        let node = match node {
+            Some(i) => &self.nodes[i],
+            None => return
+        };
+
+        let d = distance_squared(&node.point, target);
+        if d < best.1 || (d == best.1 && node.index < best.0) {
+            *best = (node.index, d);
+        }
+
+        let offset = target[node.axis] - node.point[node.axis];
+        let (near, far) = if offset < 0.0 { (node.left, node.right) } else { (node.right, node.left) };
+        self.search(near, target, best);
+        if offset * offset <= best.1 {
+            self.search(far, target, best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, XorShift};
+
+    fn random_colors(rng: &mut XorShift, n: usize) -> Vec<Rgb<u8>> {
+        (0..n).map(|_| Rgb::with_components(rng.below(256) as u8, rng.below(256) as u8, rng.below(256) as u8))
+              .collect()
+    }
+
+    #[test]
+    fn nearest_finds_the_closest_color() {
+        let palette = Palette::new(vec![Rgb::with_components(0u8, 0, 0),
+                                        Rgb::with_components(255u8, 255, 255),
+                                        Rgb::with_components(255u8, 0, 0)]);
+        let pink = Rgb::with_components(230u8, 40, 60);
+        assert_eq!(palette.nearest_index(&pink, DistanceMetric::Rgb), Some(2));
+        assert_eq!(palette.nearest(&pink, DistanceMetric::Lab), Some(&palette[2]));
+        assert_eq!(Palette::new(Vec::new()).nearest_index(&pink, DistanceMetric::Rgb), None);
+    }
+
+    #[test]
+    fn k_d_tree_agrees_with_linear_search() {
+        let mut rng = XorShift::new(42);
+        let palette = Palette::new(random_colors(&mut rng, 64));
+        let pixels = random_colors(&mut rng, 500);
+        for &metric in &[DistanceMetric::Rgb, DistanceMetric::WeightedRgb, DistanceMetric::Lab] {
+            let indices = palette.index_colors(&pixels, metric);
+            for (pixel, &index) in pixels.iter().zip(&indices) {
+                assert_eq!(Some(index), palette.nearest_index(pixel, metric));
+            }
+        }
+    }
+
+    #[test]
+    fn nan_query_falls_back_to_the_first_color() {
+        let palette = Palette::new(vec![Rgb::with_components(0.0, 0.0, 0.0),
+                                        Rgb::with_components(1.0, 1.0, 1.0)]);
+        let nan = Rgb::with_components(::std::f64::NAN, 0.5, 0.5);
+        assert_eq!(palette.nearest_index(&nan, DistanceMetric::Rgb), Some(0));
+        assert_eq!(palette.index_colors(&[nan], DistanceMetric::Rgb), vec![0]);
+        let search = NearestSearch::new(&palette, DistanceMetric::Rgb);
+        assert_eq!(search.nearest([::std::f64::NAN, 0.5, 0.5, 1.0]), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn search_over_an_empty_palette_panics() {
+        NearestSearch::new(&Palette::<Rgb<u8>>::new(Vec::new()), DistanceMetric::Rgb);
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp::Ordering;
+use std::usize;
+
+use super::{clamp, distance_squared, Channel, Lab, Oklab, Palette, Rgb, Rgba, XorShift};
+
+/// A color which can be reduced to a `Palette` by a `Quantizer`.
+pub trait QuantizeColor: Copy {
+    /// Returns the red, green, blue and alpha channels as values between 0 and 1.
+    fn to_rgba_components(&self) -> [f64; 4];
+
+    /// Create a color from red, green, blue and alpha values between 0 and 1.
+    fn from_rgba_components(c: [f64; 4]) -> Self;
+}
+
+impl<T: Channel> QuantizeColor for Rgb<T> {
+    fn to_rgba_components(&self) -> [f64; 4] {
+        [self.r().to_float(), self.g().to_float(), self.b().to_float(), 1.0]
+    }
+
+    fn from_rgba_components(c: [f64; 4]) -> Rgb<T> {
+        Rgb::with_components(T::from_float(c[0]), T::from_float(c[1]), T::from_float(c[2]))
+    }
+}
+
+impl<T: Channel> QuantizeColor for Rgba<T> {
+    fn to_rgba_components(&self) -> [f64; 4] {
+        [self.r().to_float(), self.g().to_float(), self.b().to_float(), self.a().to_float()]
+    }
+
+    fn from_rgba_components(c: [f64; 4]) -> Rgba<T> {
+        Rgba::with_components(T::from_float(c[0]), T::from_float(c[1]),
+                              T::from_float(c[2]), T::from_float(c[3]))
+    }
+}
+
+/// The color space in which k-means clustering measures distances.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum QuantizeSpace {
+    /// Cluster the sRGB encoded channels directly.
+    Rgb,
+
+    /// Cluster in CIE `Lab`.
+    Lab,
+
+    /// Cluster in `Oklab`.
+    Oklab
+}
+
+impl QuantizeSpace {
+    /// Convert sRGB and alpha components to coordinates in this space. All
+    /// coordinates are scaled to a similar range, so that alpha is weighted
+    /// comparably to the color.
+    fn to_point(self, c: [f64; 4]) -> [f64; 4] {
+        let rgb = Rgb::with_components(c[0], c[1], c[2]);
+        match self {
+            QuantizeSpace::Rgb => c,
+            QuantizeSpace::Lab => {
+                let (l, a, b) = Lab::from_rgb(&rgb).components();
+                [l / 100.0, a / 100.0, b / 100.0, c[3]]
+            }
+            QuantizeSpace::Oklab => {
+                let (l, a, b) = Oklab::from_rgb(&rgb).components();
+                [l, a, b, c[3]]
+            }
+        }
+    }
+
+    /// Convert coordinates in this space back to sRGB and alpha components.
+    fn to_components(self, p: [f64; 4]) -> [f64; 4] {
+        let rgb = match self {
+            QuantizeSpace::Rgb => return p,
+            QuantizeSpace::Lab => Lab::with_components(p[0] * 100.0, p[1] * 100.0, p[2] * 100.0).to_rgb(),
+            QuantizeSpace::Oklab => Oklab::with_components(p[0], p[1], p[2]).to_rgb()
+        };
+        [rgb.r(), rgb.g(), rgb.b(), p[3]]
+    }
+}
+
+/// An algorithm which reduces a list of colors to a small `Palette`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quantizer {
+    /// Heckbert's median cut, which repeatedly splits the box of colors
+    /// with the largest range at its median.
+    MedianCut,
+
+    /// Octree quantization, which merges the least common colors which
+    /// share the longest prefix of bits.
+    Octree,
+
+    /// K-means clustering, initialised with k-means++. The same seed always
+    /// produces the same palette.
+    KMeans {
+        /// The space distances are measured in
+        space: QuantizeSpace,
+
+        /// The maximum number of refinement iterations
+        max_iterations: usize,
+
+        /// The seed for the initial cluster centers
+        seed: u64
+    }
+}
+
+impl Quantizer {
+    /// Reduce a list of colors to a palette of at most `n` colors. The
+    /// palette may have fewer colors if there are fewer distinct colors
+    /// in the input.
+    pub fn quantize<C: QuantizeColor>(&self, colors: &[C], n: usize) -> Palette<C> {
+        let components: Vec<_> = colors.iter().map(|c| c.to_rgba_components()).collect();
+        let result = match *self {
+            Quantizer::MedianCut => median_cut(components, n),
+            Quantizer::Octree => octree(&components, n),
+            Quantizer::KMeans { space, max_iterations, seed } => {
+                let points: Vec<_> = components.iter().map(|&c| space.to_point(c)).collect();
+                k_means(&points, n, max_iterations, seed)
+                    .into_iter()
+                    .map(|p| space.to_components(p))
+                    .collect()
+            }
+        };
+        Palette::new(result.into_iter()
+                           .map(|c| C::from_rgba_components([clamp(c[0], 0.0, 1.0),
+                                                             clamp(c[1], 0.0, 1.0),
+                                                             clamp(c[2], 0.0, 1.0),
+                                                             clamp(c[3], 0.0, 1.0)]))
+                           .collect())
+    }
+}
+
+fn median_cut(points: Vec<[f64; 4]>, n: usize) -> Vec<[f64; 4]> {
+    if points.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![points];
+    while boxes.len() < n {
+        let mut widest = (0, 0, 0.0);
+        for (i, b) in boxes.iter().enumerate() {
+            let (axis, range) = widest_axis(b);
+            if range > widest.2 {
+                widest = (i, axis, range);
+            }
+        }
+
+        let (index, axis, range) = widest;
+        if range <= 0.0 {
+            break;
+        }
+
+        let mut lower = boxes.swap_remove(index);
+        lower.sort_by(|a, b| a[axis].partial_cmp(&b[axis]).unwrap_or(Ordering::Equal));
+        // Split at the median value, keeping equal values in the same box.
+        let pivot = lower[lower.len() / 2][axis];
+        let split = match lower.iter().position(|p| p[axis] >= pivot) {
+            Some(0) | None => lower.iter().position(|p| p[axis] > pivot).unwrap_or(lower.len() / 2),
+            Some(i) => i
+        };
+        let upper = lower.split_off(split);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+    boxes.iter().map(|b| mean(b)).collect()
+}
+
+/// Returns the axis with the largest range of values, and that range.
+fn widest_axis(points: &[[f64; 4]]) -> (usize, f64) {
+    let mut best = (0, 0.0);
+    for axis in 0..4 {
+        let (min, max) = points.iter().fold((1.0 / 0.0, -1.0 / 0.0), |(min, max): (f64, f64), p| {
+            (min.min(p[axis]), max.max(p[axis]))
+        });
+        if max - min > best.1 {
+            best = (axis, max - min);
+        }
+    }
+    best
+}
+
+fn mean(points: &[[f64; 4]]) -> [f64; 4] {
+    let mut sum = [0.0; 4];
+    for p in points {
+        for i in 0..4 { sum[i] += p[i]; }
+    }
+    let count = points.len() as f64;
+    [sum[0] / count, sum[1] / count, sum[2] / count, sum[3] / count]
+}
+
+/// The number of bits used for each channel in the octree.
+const OCTREE_DEPTH: usize = 8;
+
+/// A node of an octree. Every node accumulates the colors inserted beneath
+/// it, so merging a node's children into it is a matter of dropping them.
+struct OctreeNode {
+    children: [Option<usize>; 16],
+    sum: [f64; 4],
+    count: usize,
+    level: usize
+}
+
+impl OctreeNode {
+    fn new(level: usize) -> OctreeNode {
+        OctreeNode {
+            children: [None; 16],
+            sum: [0.0; 4],
+            count: 0,
+            level: level
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.iter().all(|c| c.is_none())
+    }
+}
+
+fn octree(points: &[[f64; 4]], n: usize) -> Vec<[f64; 4]> {
+    if points.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    let mut nodes = vec![OctreeNode::new(0)];
+    let mut leaves = 0;
+    for p in points {
+        let bits: Vec<u32> = p.iter().map(|&c| (clamp(c, 0.0, 1.0) * 255.0).round() as u32).collect();
+        let mut node = 0;
+        for level in 0..OCTREE_DEPTH + 1 {
+            for (sum, c) in nodes[node].sum.iter_mut().zip(p) { *sum += *c; }
+            nodes[node].count += 1;
+            if level == OCTREE_DEPTH {
+                break;
+            }
+
+            let shift = OCTREE_DEPTH - 1 - level;
+            let child = bits.iter().fold(0, |index, b| (index << 1) | ((b >> shift) & 1) as usize);
+            node = match nodes[node].children[child] {
+                Some(next) => next,
+                None => {
+                    nodes.push(OctreeNode::new(level + 1));
+                    let next = nodes.len() - 1;
+                    nodes[node].children[child] = Some(next);
+                    if level + 1 == OCTREE_DEPTH { leaves += 1; }
+                    next
+                }
+            };
+        }
+    }
+
+    // Merge the least common nodes from the deepest level upwards.
+    for level in (0..OCTREE_DEPTH).rev() {
+        if leaves <= n {
+            break;
+        }
+        let mut reducible: Vec<usize> = (0..nodes.len())
+            .filter(|&i| nodes[i].level == level && !nodes[i].is_leaf())
+            .collect();
+        reducible.sort_by(|&a, &b| nodes[a].count.cmp(&nodes[b].count));
+        for i in reducible {
+            if leaves <= n {
+                break;
+            }
+            let children = nodes[i].children.iter().filter(|c| c.is_some()).count();
+            nodes[i].children = [None; 16];
+            leaves -= children - 1;
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut stack = vec![0];
+    while let Some(i) = stack.pop() {
+        let node = &nodes[i];
+        if node.is_leaf() {
+            let count = node.count as f64;
+            result.push([node.sum[0] / count, node.sum[1] / count, node.sum[2] / count, node.sum[3] / count]);
+        } else {
+            stack.extend(node.children.iter().rev().filter_map(|&c| c));
+        }
+    }
+    result
+}
+
+fn k_means(points: &[[f64; 4]], n: usize, max_iterations: usize, seed: u64) -> Vec<[f64; 4]> {
+    if points.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    // k-means++ initialisation: pick each new center with a probability
+    // proportional to its squared distance from the nearest chosen center.
+    let mut rng = XorShift::new(seed);
+    let mut centers = vec![points[rng.below(points.len())]];
+    let mut distances: Vec<f64> = points.iter().map(|p| distance_squared(p, &centers[0])).collect();
+    while centers.len() < n {
+        let total = distances.iter().fold(0.0, |sum, d| sum + d);
+        if total <= 0.0 {
+            break;
+        }
+        let mut target = rng.next_f64() * total;
+        let mut chosen = points.len() - 1;
+        for (i, &d) in distances.iter().enumerate() {
+            if target < d {
+                chosen = i;
+                break;
+            }
+            target -= d;
+        }
+        let center = points[chosen];
+        for (d, p) in distances.iter_mut().zip(points) {
+            *d = d.min(distance_squared(p, &center));
+        }
+        centers.push(center);
+    }
+
+    let mut assignments = vec![usize::MAX; points.len()];
+    for _ in 0..max_iterations {
+        let mut changed = false;
+        for (assignment, p) in assignments.iter_mut().zip(points) {
+            let nearest = nearest_center(&centers, p);
+            if *assignment != nearest {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![([0.0; 4], 0usize); centers.len()];
+        for (&assignment, p) in assignments.iter().zip(points) {
+            let &mut (ref mut sum, ref mut count) = &mut sums[assignment];
+            for i in 0..4 { sum[i] += p[i]; }
+            *count += 1;
+        }
+        for (center, &(sum, count)) in centers.iter_mut().zip(&sums) {
+            if count > 0 {
+                let count = count as f64;
+                *center = [sum[0] / count, sum[1] / count, sum[2] / count, sum[3] / count];
+            }
+        }
+    }
+    centers
+}
+
+fn nearest_center(centers: &[[f64; 4]], p: &[f64; 4]) -> usize {
+    let mut best = (0, 1.0 / 0.0);
+    for (i, c) in centers.iter().enumerate() {
+        let d = distance_squared(c, p);
+        if d < best.1 {
+            best = (i, d);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, Rgba};
+
+    fn two_clusters() -> Vec<Rgb<u8>> {
+        let mut colors = Vec::new();
+        for i in 0..10 {
+            colors.push(Rgb::with_components(250 - i, 10, 10));
+            colors.push(Rgb::with_components(10, 10, 240 + i));
+        }
+        colors
+    }
+
+    fn assert_has_clusters(palette: &Palette<Rgb<u8>>) {
+        assert_eq!(palette.len(), 2);
+        assert!(palette.iter().any(|c| c.r() > 200 && c.b() < 50));
+        assert!(palette.iter().any(|c| c.b() > 200 && c.r() < 50));
+    }
+
+    #[test]
+    fn median_cut_finds_clusters() {
+        assert_has_clusters(&Quantizer::MedianCut.quantize(&two_clusters(), 2));
+    }
+
+    #[test]
+    fn octree_finds_clusters() {
+        assert_has_clusters(&Quantizer::Octree.quantize(&two_clusters(), 2));
+    }
+
+    #[test]
+    fn k_means_finds_clusters_reproducibly() {
+        let quantizer = Quantizer::KMeans { space: QuantizeSpace::Oklab, max_iterations: 20, seed: 7 };
+        let palette = quantizer.quantize(&two_clusters(), 2);
+        assert_has_clusters(&palette);
+        assert_eq!(palette, quantizer.quantize(&two_clusters(), 2));
+    }
+
+    #[test]
+    fn few_distinct_colors_are_preserved() {
+        let colors = [Rgb::with_components(1u8, 2, 3), Rgb::with_components(200u8, 100, 50),
+                      Rgb::with_components(1u8, 2, 3)];
+        for quantizer in &[Quantizer::MedianCut, Quantizer::Octree,
+                           Quantizer::KMeans { space: QuantizeSpace::Lab, max_iterations: 10, seed: 1 }] {
+            let palette = quantizer.quantize(&colors, 16);
+            assert_eq!(palette.len(), 2);
+            assert!(palette.iter().any(|c| *c == colors[0]));
+            assert!(palette.iter().any(|c| *c == colors[1]));
+        }
+    }
+
+    #[test]
+    fn alpha_is_quantized() {
+        let colors = [Rgba::with_components(0.5f64, 0.5, 0.5, 0.0), Rgba::with_components(0.5f64, 0.5, 0.5, 1.0)];
+        let palette = Quantizer::MedianCut.quantize(&colors, 2);
+        assert_eq!(palette.len(), 2);
+        assert!(palette.iter().any(|c| c.a() == 0.0));
+        assert!(palette.iter().any(|c| c.a() == 1.0));
+    }
+}
@@ -18,6 +18,7 @@
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 use num::{Float, NumCast, PrimInt, Unsigned, Zero};
+use num::traits::cast;
 use std::default::Default;
 use std::ops::{Add, Div, Mul, Sub};
 
@@ -25,6 +26,9 @@ use channel::Channel;
 use super::{
     clamp,
     integral_to_float,
+    lerp_channel,
+    linear_to_srgb,
+    srgb_to_linear,
     Rgba,
     Color
 };
@@ -113,6 +117,25 @@ impl<T: Channel> Rgb<T> {
     pub const fn components(&self) -> (T, T, T) {
         (self.r, self.g, self.b)
     }
+
+    /// Convert this color to a color with a different channel type. Each
+    /// channel is scaled as described in `Channel::to_float` and
+    /// `Channel::from_float`.
+    pub fn convert<U: Channel>(&self) -> Rgb<U> {
+        Rgb::with_components(
+            U::from_float::<f64>(self.r.to_float()),
+            U::from_float::<f64>(self.g.to_float()),
+            U::from_float::<f64>(self.b.to_float()))
+    }
+
+    /// Returns the relative luminance of the color as defined by WCAG 2.x:
+    /// the weighted sum of the linearised sRGB components, ranging from zero
+    /// for black to one for white.
+    pub fn relative_luminance(&self) -> f64 {
+        0.2126 * srgb_to_linear(self.r.to_float::<f64>()) +
+        0.7152 * srgb_to_linear(self.g.to_float::<f64>()) +
+        0.0722 * srgb_to_linear(self.b.to_float::<f64>())
+    }
 }
 
 impl<F: Channel + Float + NumCast> Rgb<F> {
@@ -135,6 +158,22 @@ impl<F: Channel + Float + NumCast> Rgb<F> {
             integral_to_float(col[1]),
             integral_to_float(col[2]))
     }
+
+    /// Decode an sRGB encoded color into linear light.
+    pub fn to_linear(&self) -> Rgb<F> {
+        Rgb::with_components(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b))
+    }
+
+    /// Encode a linear light color with the sRGB transfer function.
+    pub fn to_srgb(&self) -> Rgb<F> {
+        Rgb::with_components(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b))
+    }
 }
 
 impl<T: Channel> Color<T> for Rgb<T> {
@@ -171,9 +210,9 @@ impl<T: Channel> Color<T> for Rgb<T> {
             self.b.inverted())
     }
 
-    /// Get the relative brightness of a color.
+    /// Get the relative brightness of a color. See `relative_luminance`.
     fn luminance(&self) -> T {
-        T::zero() // TODO: implement
+        T::from_float(self.relative_luminance())
     }
 
     /// Mix two colors together using the standard Rgb
@@ -186,6 +225,35 @@ impl<T: Channel> Color<T> for Rgb<T> {
     fn to_greyscale(&self) -> Rgb<T> {
         self.clone() // TODO: implementt
     }
+
+    /// Linearly interpolate between this color and another color.
+    fn lerp<F: Float>(&self, other: &Rgb<T>, t: F) -> Rgb<T> {
+        let t = cast(t).unwrap();
+        Rgb::with_components(
+            lerp_channel(self.r, other.r, t),
+            lerp_channel(self.g, other.g, t),
+            lerp_channel(self.b, other.b, t))
+    }
+
+    /// Mix a list of colors together, weighting each color by its
+    /// corresponding weight.
+    fn mix_weighted<F: Float>(colors: &[(Rgb<T>, F)]) -> Rgb<T> {
+        let (mut sum, mut total) = ([0.0f64; 3], 0.0f64);
+        for &(ref col, weight) in colors {
+            let weight: f64 = cast(weight).unwrap();
+            sum[0] += weight * col.r.to_float::<f64>();
+            sum[1] += weight * col.g.to_float::<f64>();
+            sum[2] += weight * col.b.to_float::<f64>();
+            total += weight;
+        }
+        if total == 0.0 {
+            return Rgb::default();
+        }
+        Rgb::with_components(
+            T::from_float(sum[0] / total),
+            T::from_float(sum[1] / total),
+            T::from_float(sum[2] / total))
+    }
 }
 
 impl<T: Channel> Default for Rgb<T> {
@@ -447,4 +515,44 @@ mod test {
         quickcheck(prop_color_components_clamped_to_color_is_no_higher_than_other_color_components::<u8>
                    as fn(Rgb<u8>, Rgb<u8>, Rgb<u8>) -> TestResult);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_color_lerp_endpoints_are_the_colors() {
+        fn prop_lerp_endpoints_are_the_colors<T: Channel>(col1: Rgb<T>, col2: Rgb<T>) -> TestResult {
+            // NaN channels never compare equal to themselves.
+            let finite = |c: &Rgb<T>| c.to_slice().iter().all(|x| x.to_float::<f64>().is_finite());
+            if !finite(&col1) || !finite(&col2) {
+                return TestResult::discard();
+            }
+            TestResult::from_bool(
+                col1.lerp(&col2, 0.0f64) == col1 &&
+                col1.lerp(&col2, 1.0f64) == col2)
+        }
+        quickcheck(prop_lerp_endpoints_are_the_colors::<f64> as fn(Rgb<f64>, Rgb<f64>) -> TestResult);
+        quickcheck(prop_lerp_endpoints_are_the_colors::<u8> as fn(Rgb<u8>, Rgb<u8>) -> TestResult);
+        quickcheck(prop_lerp_endpoints_are_the_colors::<u64> as fn(Rgb<u64>, Rgb<u64>) -> TestResult);
+    }
+
+    #[test]
+    fn test_integral_lerp_rounds_to_nearest() {
+        let black = Rgb::with_components(0u8, 0, 0);
+        let white = Rgb::with_components(255u8, 255, 255);
+        assert_col_components_are!(black.lerp(&white, 0.5f32) => (128, 128, 128));
+        assert_col_components_are!(black.lerp(&white, 0.25f32) => (64, 64, 64));
+    }
+
+    #[test]
+    fn test_weighted_mix_does_not_overflow() {
+        let mixed = Rgb::mix_weighted(&[(Rgb::with_components(200u8, 100, 0), 3.0f64),
+                                        (Rgb::with_components(100u8, 200, 0), 1.0)]);
+        assert_col_components_are!(mixed => (175, 125, 0));
+    }
+
+    #[test]
+    fn test_relative_luminance() {
+        assert_eq!(Rgb::with_components(0u8, 0, 0).relative_luminance(), 0.0);
+        assert!((Rgb::with_components(255u8, 255, 255).relative_luminance() - 1.0).abs() < 1e-12);
+        assert!((Rgb::with_components(0.0f64, 1.0, 0.0).relative_luminance() - 0.7152).abs() < 1e-12);
+        assert_eq!(Rgb::with_components(255u8, 255, 255).luminance(), 255);
+    }
+}
@@ -18,12 +18,15 @@
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 use num::{Float, NumCast, PrimInt, Unsigned};
+use num::traits::cast;
 use std::default::Default;
 use std::ops::{Add, Div, Mul, Sub};
 
 use super::{
+    clamp,
     integral_to_float,
     Channel,
+    Color,
     Rgb
 };
 
@@ -114,6 +117,13 @@ impl<T: Channel> Rgba<T> {
     pub const fn to_slice(&self) -> [T; 4] {
         [self.r(), self.g(), self.b(), self.a()]
     }
+
+    /// Convert this color to a color with a different channel type. Each
+    /// channel is scaled as described in `Channel::to_float` and
+    /// `Channel::from_float`.
+    pub fn convert<U: Channel>(&self) -> Rgba<U> {
+        self.rgb.convert().rgba(U::from_float::<f64>(self.a.to_float()))
+    }
 }
 
 impl<T: Channel> Default for Rgba<T> {
@@ -145,6 +155,86 @@ impl<F: Channel + Float + NumCast> Rgba<F> {
     }
 }
 
+impl<T: Channel> Color<T> for Rgba<T> {
+    /// Clamp each component between two scalar values.
+    fn clamp_scalar(&self, min: T, max: T) -> Rgba<T> {
+        self.rgb.clamp_scalar(min, max).rgba(clamp(self.a, min, max))
+    }
+
+    /// Clamp each component piecewise between zero, and the
+    /// corresponding channel for the other color.
+    fn clamp_color(&self, min: &Rgba<T>, max: &Rgba<T>) -> Rgba<T> {
+        self.rgb.clamp_color(&min.rgb, &max.rgb).rgba(clamp(self.a, min.a, max.a))
+    }
+
+    /// Normalise each component, including the alpha.
+    fn normalise(&self) -> Rgba<T> {
+        self.rgb.normalise().rgba(self.a.normalised())
+    }
+
+    /// Invert the color. The alpha is left unchanged.
+    fn invert(&self) -> Rgba<T> {
+        self.rgb.invert().rgba(self.a)
+    }
+
+    /// Get the relative brightness of a color, ignoring the alpha.
+    fn luminance(&self) -> T {
+        self.rgb.luminance()
+    }
+
+    /// Mix two colors together using the standard Rgb
+    /// color model.
+    fn mix(&self, other: &Rgba<T>) -> Rgba<T> {
+        self + other
+    }
+
+    /// Convert a color to greyscale. The alpha is left unchanged.
+    fn to_greyscale(&self) -> Rgba<T> {
+        self.rgb.to_greyscale().rgba(self.a)
+    }
+
+    /// Linearly interpolate between this color and another color, using
+    /// premultiplied alpha so that transparent colors do not tint the result.
+    fn lerp<F: Float>(&self, other: &Rgba<T>, t: F) -> Rgba<T> {
+        let t: f64 = cast(t).unwrap();
+        if t == 0.0 {
+            return *self;
+        }
+        if t == 1.0 {
+            return *other;
+        }
+        Rgba::mix_weighted(&[(*self, 1.0 - t), (*other, t)])
+    }
+
+    /// Mix a list of colors together, weighting each color by its
+    /// corresponding weight. Colors are mixed with premultiplied alpha.
+    fn mix_weighted<F: Float>(colors: &[(Rgba<T>, F)]) -> Rgba<T> {
+        let (mut sum, mut alpha, mut total) = ([0.0f64; 3], 0.0f64, 0.0f64);
+        for &(ref col, weight) in colors {
+            let weight: f64 = cast(weight).unwrap();
+            let coverage = weight * col.a.to_float::<f64>();
+            sum[0] += coverage * col.r().to_float::<f64>();
+            sum[1] += coverage * col.g().to_float::<f64>();
+            sum[2] += coverage * col.b().to_float::<f64>();
+            alpha += coverage;
+            total += weight;
+        }
+        if total == 0.0 {
+            return Rgba::default();
+        }
+        if alpha == 0.0 {
+            // Every color is fully transparent, so mix the colors directly.
+            let rgb: Vec<_> = colors.iter().map(|&(col, weight)| (col.rgb, weight)).collect();
+            return Rgb::mix_weighted(&rgb).rgba(T::zero());
+        }
+        Rgba::with_components(
+            T::from_float(sum[0] / alpha),
+            T::from_float(sum[1] / alpha),
+            T::from_float(sum[2] / alpha),
+            T::from_float(alpha / total))
+    }
+}
+
 impl_arith_operator! {
     impl<T: Channel> Add for Rgba<T>, where Output = Rgba<T> {
         #[doc = "Piecewise addition of each component. Each channel
@@ -193,6 +283,7 @@ impl_arith_operator!{
 #[cfg(test)]
 mod test {
     use super::*;
+    use ::Color;
 
     #[test]
     fn color_creation_no_clamping() {
@@ -207,4 +298,25 @@ mod test {
         let col_c = col_a + col_b;
         assert_col_components_are!(col_c => (0.5, 0.5, 0.5, 0.5));
     }
+
+    #[test]
+    fn lerp_uses_premultiplied_alpha() {
+        let clear_red = Rgba::with_components(1.0f64, 0.0, 0.0, 0.0);
+        let blue = Rgba::with_components(0.0f64, 0.0, 1.0, 1.0);
+        assert_col_components_are!(clear_red.lerp(&blue, 0.5f64) => (0.0, 0.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn integral_lerp_rounds_to_nearest() {
+        let col_a = Rgba::with_components(0u8, 0, 0, 255);
+        let col_b = Rgba::with_components(255u8, 255, 255, 255);
+        assert_col_components_are!(col_a.lerp(&col_b, 0.5f32) => (128, 128, 128, 255));
+    }
+
+    #[test]
+    fn weighted_mix_of_transparent_colors() {
+        let mixed = Rgba::mix_weighted(&[(Rgba::with_components(1.0f64, 0.0, 0.0, 0.0), 1.0f64),
+                                         (Rgba::with_components(0.0f64, 1.0, 0.0, 0.0), 1.0)]);
+        assert_col_components_are!(mixed => (0.5, 0.5, 0.0, 0.0));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,188 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::f64::consts::PI;
+
+use super::{clamp, Channel, Colormap, Oklab, Rgb};
+
+/// Lightness of the lightest color in sequential and diverging schemes.
+const LIGHTEST: f64 = 0.97;
+
+/// Lightness of the darkest color in sequential and diverging schemes.
+const DARKEST: f64 = 0.3;
+
+/// Dave Green's cubehelix colormap, which spirals around the color cube
+/// from black to white with monotonically increasing luminance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cubehelix {
+    /// Starting hue, between 0 and 3 (red is 1, green is 2 and blue is 3)
+    start: f64,
+
+    /// Number of rotations through red, green and blue
+    rotations: f64,
+
+    /// Saturation of the colors
+    hue: f64,
+
+    /// Exponent applied to the intensity
+    gamma: f64
+}
+
+impl Cubehelix {
+    /// Create a cubehelix colormap. `start` is the starting hue between 0 and
+    /// 3, `rotations` is the number of red, green, blue rotations (which may
+    /// be negative), `hue` controls the saturation, and `gamma` emphasises
+    /// low (less than 1) or high (greater than 1) intensities.
+    pub fn new(start: f64, rotations: f64, hue: f64, gamma: f64) -> Cubehelix {
+        Cubehelix {
+            start: start,
+            rotations: rotations,
+            hue: hue,
+            gamma: gamma
+        }
+    }
+
+    /// Returns the starting hue.
+    #[inline]
+    pub fn start(&self) -> f64 { self.start }
+
+    /// Returns the number of rotations.
+    #[inline]
+    pub fn rotations(&self) -> f64 { self.rotations }
+
+    /// Returns the saturation.
+    #[inline]
+    pub fn hue(&self) -> f64 { self.hue }
+
+    /// Returns the intensity exponent.
+    #[inline]
+    pub fn gamma(&self) -> f64 { self.gamma }
+}
+
+impl Default for Cubehelix {
+    /// The default scheme from Green's paper: a start of 0.5, -1.5 rotations,
+    /// a hue of 1 and a gamma of 1.
+    fn default() -> Cubehelix { Cubehelix::new(0.5, -1.5, 1.0, 1.0) }
+}
+
+impl Colormap for Cubehelix {
+    fn lookup(&self, t: f64) -> Rgb<f64> {
+        let intensity = clamp(t, 0.0, 1.0).powf(self.gamma);
+        let amplitude = self.hue * intensity * (1.0 - intensity) / 2.0;
+        let phi = 2.0 * PI * (self.start / 3.0 + 1.0 + self.rotations * t);
+        let (cos, sin) = (phi.cos(), phi.sin());
+        Rgb::with_components(
+            clamp(intensity + amplitude * (-0.14861 * cos + 1.78277 * sin), 0.0, 1.0),
+            clamp(intensity + amplitude * (-0.29227 * cos - 0.90649 * sin), 0.0, 1.0),
+            clamp(intensity + amplitude * (1.97294 * cos), 0.0, 1.0))
+    }
+}
+
+/// Generate `n` colors of a single hue, running from light to dark. The
+/// colors are spaced evenly in Oklab lightness, so lightness is strictly
+/// decreasing, and `chroma` is the peak Oklch chroma reached in the middle
+/// of the scheme (0.15 is vivid). Chroma is reduced where needed to stay
+/// within the sRGB gamut.
+pub fn sequential_scheme<T: Channel>(n: usize, hue: f64, chroma: f64) -> Vec<Rgb<T>> {
+    let last = if n > 1 { (n - 1) as f64 } else { 1.0 };
+    (0..n).map(|i| sequential_color(i as f64 / last, hue, chroma)).collect()
+}
+
+/// Generate `n` colors diverging from a light neutral midpoint towards two
+/// hues, with `low_hue` used for the first half and `high_hue` for the
+/// second half. Lightness decreases monotonically from the midpoint towards
+/// both ends, and both halves are symmetric in lightness. `chroma` is the
+/// peak Oklch chroma of each half, and falls to zero at the midpoint.
+pub fn diverging_scheme<T: Channel>(n: usize, low_hue: f64, high_hue: f64, chroma: f64) -> Vec<Rgb<T>> {
+    let last = if n > 1 { (n - 1) as f64 } else { 1.0 };
+    (0..n).map(|i| {
+        let t = i as f64 / last * 2.0 - 1.0;
+        let hue = if t < 0.0 { low_hue } else { high_hue };
+        let t = t.abs();
+        scheme_color(t, hue, chroma * (PI * 0.9 * t).sin())
+    }).collect()
+}
+
+/// Generate `n` distinct colors for categorical data. The hues are spaced
+/// evenly around the Oklch hue circle starting from `start_hue`, and every
+/// color shares the same Oklab `lightness` and (gamut permitting) `chroma`.
+pub fn qualitative_scheme<T: Channel>(n: usize, start_hue: f64, lightness: f64, chroma: f64) -> Vec<Rgb<T>> {
+    (0..n).map(|i| {
+        let hue = start_hue + 360.0 * i as f64 / n as f64;
+        Oklab::from_lch(lightness, chroma, hue).to_rgb_in_gamut().convert()
+    }).collect()
+}
+
+/// A color at position `t` of a sequential scheme, where 0 is the lightest
+/// color and 1 is the darkest.
+fn sequential_color<T: Channel>(t: f64, hue: f64, chroma: f64) -> Rgb<T> {
+    scheme_color(t, hue, chroma * (PI * (0.1 + 0.8 * t)).sin())
+}
+
+/// A color at position `t` of the lightness ramp, with the given chroma.
+fn scheme_color<T: Channel>(t: f64, hue: f64, chroma: f64) -> Rgb<T> {
+    let lightness = LIGHTEST + (DARKEST - LIGHTEST) * t;
+    Oklab::from_lch(lightness, chroma, hue).to_rgb_in_gamut().convert()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Colormap, Oklab, Rgb};
+
+    fn lightness(col: &Rgb<f64>) -> f64 {
+        Oklab::from_rgb(col).l()
+    }
+
+    #[test]
+    fn cubehelix_runs_from_black_to_white() {
+        let helix = Cubehelix::default();
+        assert_eq!(helix.map::<u8>(0.0), Rgb::with_components(0, 0, 0));
+        assert_eq!(helix.map::<u8>(1.0), Rgb::with_components(255, 255, 255));
+    }
+
+    #[test]
+    fn sequential_lightness_is_monotonic() {
+        let colors = sequential_scheme::<f64>(9, 250.0, 0.15);
+        assert_eq!(colors.len(), 9);
+        for pair in colors.windows(2) {
+            assert!(lightness(&pair[0]) > lightness(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn diverging_is_symmetric_in_lightness() {
+        let colors = diverging_scheme::<f64>(7, 30.0, 250.0, 0.15);
+        assert!((lightness(&colors[3]) - LIGHTEST).abs() < 1e-3);
+        assert!(Oklab::from_rgb(&colors[3]).chroma() < 1e-6);
+        for i in 0..3 {
+            assert!(lightness(&colors[i]) < lightness(&colors[i + 1]));
+            assert!((lightness(&colors[i]) - lightness(&colors[6 - i])).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn qualitative_colors_share_lightness() {
+        let colors = qualitative_scheme::<f64>(6, 20.0, 0.7, 0.12);
+        assert_eq!(colors.len(), 6);
+        for col in &colors {
+            assert!((lightness(col) - 0.7).abs() < 1e-3);
+        }
+    }
+}
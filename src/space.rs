@@ -0,0 +1,321 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, mat3_apply, mat3_inverse, mat3_mul, AdaptationMethod, Channel,
+            ChromaticAdaptation, Matrix3, Rgb, TransferFunction, Xyz, IDENTITY3};
+
+/// The xy chromaticity of the D65 white point, as used by sRGB and most
+/// video standards.
+const D65: (f64, f64) = (0.3127, 0.3290);
+
+/// The xy chromaticity of the D50 white point, as used by ProPhoto RGB.
+const D50: (f64, f64) = (0.3457, 0.3585);
+
+/// An RGB color space, described by the xy chromaticities of its red,
+/// green and blue primaries and its white point, and by the transfer
+/// function used to encode its components.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RgbSpace {
+    /// Red primary
+    red: (f64, f64),
+
+    /// Green primary
+    green: (f64, f64),
+
+    /// Blue primary
+    blue: (f64, f64),
+
+    /// White point
+    white: (f64, f64),
+
+    /// Transfer function
+    transfer: TransferFunction
+}
+
+impl RgbSpace {
+    /// Describe an RGB space from the xy chromaticities of its primaries and
+    /// white point.
+    pub const fn new(red: (f64, f64), green: (f64, f64), blue: (f64, f64),
+                     white: (f64, f64), transfer: TransferFunction) -> RgbSpace {
+        RgbSpace {
+            red: red,
+            green: green,
+            blue: blue,
+            white: white,
+            transfer: transfer
+        }
+    }
+
+    /// The sRGB color space (IEC 61966-2-1).
+    pub const fn srgb() -> RgbSpace {
+        RgbSpace::new((0.64, 0.33), (0.30, 0.60), (0.15, 0.06), D65, TransferFunction::Srgb)
+    }
+
+    /// The Display P3 color space: the DCI-P3 primaries with a D65 white
+    /// point and the sRGB transfer function.
+    pub const fn display_p3() -> RgbSpace {
+        RgbSpace::new((0.680, 0.320), (0.265, 0.690), (0.150, 0.060), D65, TransferFunction::Srgb)
+    }
+
+    /// The ITU-R BT.2020 color space, with its standard dynamic range
+    /// transfer function.
+    pub const fn rec2020() -> RgbSpace {
+        RgbSpace::new((0.708, 0.292), (0.170, 0.797), (0.131, 0.046), D65, TransferFunction::Rec709)
+    }
+
+    /// The ITU-R BT.2100 color space for HDR10, with BT.2020 primaries and
+    /// the PQ transfer function. A linear component of one is 10000 cd/m².
+    pub const fn rec2100_pq() -> RgbSpace {
+        RgbSpace::rec2020().with_transfer(TransferFunction::Pq { reference: 10000.0 })
+    }
+
+    /// The ITU-R BT.2100 color space with BT.2020 primaries and the HLG
+    /// transfer function. Linear components are scene light.
+    pub const fn rec2100_hlg() -> RgbSpace {
+        RgbSpace::rec2020().with_transfer(TransferFunction::Hlg)
+    }
+
+    /// The Adobe RGB (1998) color space.
+    pub const fn adobe_rgb() -> RgbSpace {
+        RgbSpace::new((0.64, 0.33), (0.21, 0.71), (0.15, 0.06), D65,
+                      TransferFunction::Gamma(563.0 / 256.0))
+    }
+
+    /// The ProPhoto RGB (ROMM RGB) color space, with a D50 white point.
+    pub const fn prophoto_rgb() -> RgbSpace {
+        RgbSpace::new((0.7347, 0.2653), (0.1596, 0.8404), (0.0366, 0.0001), D50,
+                      TransferFunction::ProPhoto)
+    }
+
+    /// Returns the xy chromaticity of the red primary.
+    #[inline]
+    pub const fn red(&self) -> (f64, f64) { self.red }
+
+    /// Returns the xy chromaticity of the green primary.
+    #[inline]
+    pub const fn green(&self) -> (f64, f64) { self.green }
+
+    /// Returns the xy chromaticity of the blue primary.
+    #[inline]
+    pub const fn blue(&self) -> (f64, f64) { self.blue }
+
+    /// Returns the xy chromaticity of the white point.
+    #[inline]
+    pub const fn white(&self) -> (f64, f64) { self.white }
+
+    /// Returns the transfer function.
+    #[inline]
+    pub const fn transfer(&self) -> TransferFunction { self.transfer }
+
+    /// Returns a copy of this space using a different transfer function,
+    /// such as a linear light version of the space.
+    pub const fn with_transfer(&self, transfer: TransferFunction) -> RgbSpace {
+        RgbSpace::new(self.red, self.green, self.blue, self.white, transfer)
+    }
+
+    /// Returns the white point as an XYZ color with a Y of one.
+    pub fn white_xyz<F: Float>(&self) -> Xyz<F> {
+        Xyz::from_chromaticity(float(self.white.0), float(self.white.1))
+    }
+
+    /// Returns the matrix from linear RGB in this space to XYZ, relative to
+    /// the space's white point, stored by rows.
+    pub fn to_xyz_matrix(&self) -> [[f64; 3]; 3] {
+        let column = |(x, y): (f64, f64)| [x / y, 1.0, (1.0 - x - y) / y];
+        let (r, g, b) = (column(self.red), column(self.green), column(self.blue));
+        let primaries = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+        let white = column(self.white);
+        let s = mat3_apply(&mat3_inverse(&primaries), white);
+        let mut matrix = primaries;
+        for row in matrix.iter_mut() {
+            for j in 0..3 {
+                row[j] *= s[j];
+            }
+        }
+        matrix
+    }
+
+    /// Returns the matrix from XYZ to linear RGB in this space, stored by
+    /// rows.
+    pub fn from_xyz_matrix(&self) -> [[f64; 3]; 3] {
+        mat3_inverse(&self.to_xyz_matrix())
+    }
+
+    /// Returns the matrix from linear RGB in this space to linear RGB in
+    /// another space. If the white points differ, colors are adapted with
+    /// the given method.
+    pub fn conversion_matrix(&self, destination: &RgbSpace,
+                             method: AdaptationMethod) -> [[f64; 3]; 3] {
+        let adaptation = if self.white == destination.white {
+            ChromaticAdaptation::from_matrix(IDENTITY3)
+        } else {
+            ChromaticAdaptation::new(method, &self.white_xyz::<f64>(), &destination.white_xyz())
+        };
+        mat3_mul(&destination.from_xyz_matrix(),
+                 &mat3_mul(&adaptation.matrix(), &self.to_xyz_matrix()))
+    }
+
+    /// Decode a color in this space to linear light.
+    pub fn to_linear<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        map_components(rgb, |x| self.transfer.decode(x))
+    }
+
+    /// Encode a linear light color with the transfer function of this space.
+    pub fn from_linear<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        map_components(rgb, |x| self.transfer.encode(x))
+    }
+
+    /// Convert an encoded color in this space to XYZ, relative to the
+    /// space's white point.
+    pub fn to_xyz<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Xyz<F> {
+        let linear = self.to_linear(rgb);
+        let v = mat3_apply(&self.to_xyz_matrix(), to_vector(&linear));
+        Xyz::with_components(float(v[0]), float(v[1]), float(v[2]))
+    }
+
+    /// Convert an XYZ color, relative to the space's white point, to an
+    /// encoded color in this space. Colors outside of the gamut of the
+    /// space will have channels outside of [0, 1].
+    pub fn from_xyz<F: Channel + Float>(&self, xyz: &Xyz<F>) -> Rgb<F> {
+        let (x, y, z) = xyz.components();
+        let v = [x.to_f64().unwrap(), y.to_f64().unwrap(), z.to_f64().unwrap()];
+        self.from_linear(&from_vector(mat3_apply(&self.from_xyz_matrix(), v)))
+    }
+
+    /// Convert an encoded color in this space to an encoded color in
+    /// another space, adapting between white points with the Bradford
+    /// transform. Colors outside of the destination gamut will have
+    /// channels outside of [0, 1].
+    ///
+    /// ```rust
+    /// use simplecolor::{Rgb, RgbSpace};
+    ///
+    /// let p3_red = RgbSpace::srgb().convert(&Rgb::with_components(1.0f64, 0.0, 0.0),
+    ///                                       &RgbSpace::display_p3());
+    /// ```
+    pub fn convert<F: Channel + Float>(&self, rgb: &Rgb<F>, destination: &RgbSpace) -> Rgb<F> {
+        self.convert_with(rgb, destination, AdaptationMethod::Bradford)
+    }
+
+    /// Convert an encoded color in this space to an encoded color in
+    /// another space, adapting between white points with the given method.
+    pub fn convert_with<F: Channel + Float>(&self, rgb: &Rgb<F>, destination: &RgbSpace,
+                                            method: AdaptationMethod) -> Rgb<F> {
+        let matrix = self.conversion_matrix(destination, method);
+        let linear = from_vector(mat3_apply(&matrix, to_vector(&self.to_linear(rgb))));
+        destination.from_linear(&linear)
+    }
+
+    /// Apply a chromatic adaptation to an encoded color in this space,
+    /// converting to XYZ through the space's own matrices in linear light.
+    pub fn adapt<F: Channel + Float>(&self, rgb: &Rgb<F>, adaptation: &ChromaticAdaptation) -> Rgb<F> {
+        let matrix: Matrix3 = mat3_mul(&self.from_xyz_matrix(),
+                                       &mat3_mul(&adaptation.matrix(), &self.to_xyz_matrix()));
+        let linear = from_vector(mat3_apply(&matrix, to_vector(&self.to_linear(rgb))));
+        self.from_linear(&linear)
+    }
+}
+
+impl Default for RgbSpace {
+    fn default() -> RgbSpace {
+        RgbSpace::srgb()
+    }
+}
+
+/// Apply a function to each component of a color, in double precision.
+fn map_components<F, M>(rgb: &Rgb<F>, f: M) -> Rgb<F>
+        where F: Channel + Float, M: Fn(f64) -> f64 {
+    let v = to_vector(rgb);
+    from_vector([f(v[0]), f(v[1]), f(v[2])])
+}
+
+/// The components of a color as a column vector.
+fn to_vector<F: Channel + Float>(rgb: &Rgb<F>) -> [f64; 3] {
+    let (r, g, b) = rgb.components();
+    [r.to_f64().unwrap(), g.to_f64().unwrap(), b.to_f64().unwrap()]
+}
+
+/// A color from a column vector.
+fn from_vector<F: Channel + Float>(v: [f64; 3]) -> Rgb<F> {
+    Rgb::with_components(float(v[0]), float(v[1]), float(v[2]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    fn assert_matrix_near(actual: [[f64; 3]; 3], expected: [[f64; 3]; 3], tolerance: f64) {
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((actual[i][j] - expected[i][j]).abs() < tolerance,
+                        "{:?} != {:?}", actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn srgb_matrix_matches_the_standard() {
+        assert_matrix_near(RgbSpace::srgb().to_xyz_matrix(), [
+            [0.4124, 0.3576, 0.1805],
+            [0.2126, 0.7152, 0.0722],
+            [0.0193, 0.1192, 0.9505]], 1e-4);
+    }
+
+    #[test]
+    fn rec2020_luminance_coefficients() {
+        let y = RgbSpace::rec2020().to_xyz_matrix()[1];
+        assert!((y[0] - 0.2627).abs() < 1e-4);
+        assert!((y[1] - 0.6780).abs() < 1e-4);
+        assert!((y[2] - 0.0593).abs() < 1e-4);
+    }
+
+    #[test]
+    fn srgb_to_display_p3() {
+        // The sRGB red primary, as given in the CSS Color 4 specification.
+        let red = RgbSpace::srgb().convert(&Rgb::with_components(1.0f64, 0.0, 0.0),
+                                           &RgbSpace::display_p3());
+        assert!((red.r() - 0.9175).abs() < 1e-3);
+        assert!((red.g() - 0.2003).abs() < 1e-3);
+        assert!((red.b() - 0.1386).abs() < 1e-3);
+    }
+
+    #[test]
+    fn conversions_round_trip_between_spaces() {
+        let spaces = [RgbSpace::srgb(), RgbSpace::display_p3(), RgbSpace::rec2020(),
+                      RgbSpace::rec2100_pq(), RgbSpace::rec2100_hlg(),
+                      RgbSpace::adobe_rgb(), RgbSpace::prophoto_rgb()];
+        let col = Rgb::with_components(0.25f64, 0.5, 0.75);
+        for a in spaces.iter() {
+            for b in spaces.iter() {
+                let (r, g, bl) = b.convert(&a.convert(&col, b), a).components();
+                assert!((r - 0.25).abs() < 1e-9 && (g - 0.5).abs() < 1e-9 && (bl - 0.75).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn white_is_preserved_between_white_points() {
+        let white = Rgb::with_components(1.0f64, 1.0, 1.0);
+        let (r, g, b) = RgbSpace::srgb().convert(&white, &RgbSpace::prophoto_rgb()).components();
+        assert!((r - 1.0).abs() < 1e-9 && (g - 1.0).abs() < 1e-9 && (b - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{clamp, float, mat3_apply, pq_eotf, pq_inverse_eotf, Channel, Color, Matrix3, Rgb};
+
+/// The Rec. 709 luminance coefficients, used by the Reinhard operators.
+const LUMINANCE: [f64; 3] = [0.2126, 0.7152, 0.0722];
+
+/// The coefficients of Hable's filmic curve from Uncharted 2.
+const HABLE_A: f64 = 0.15;
+const HABLE_B: f64 = 0.50;
+const HABLE_C: f64 = 0.10;
+const HABLE_D: f64 = 0.20;
+const HABLE_E: f64 = 0.02;
+const HABLE_F: f64 = 0.30;
+const HABLE_WHITE: f64 = 11.2;
+
+/// Stephen Hill's fit of the ACES RRT and sRGB ODT: sRGB to the RRT's
+/// working space, and back.
+const ACES_INPUT: Matrix3 = [
+    [0.59719, 0.35458, 0.04823],
+    [0.07600, 0.90834, 0.01566],
+    [0.02840, 0.13383, 0.83777]];
+const ACES_OUTPUT: Matrix3 = [
+    [1.60475, -0.53108, -0.07367],
+    [-0.10208, 1.10813, -0.00605],
+    [-0.00327, -0.07276, 1.07602]];
+
+/// The inset and outset matrices of the AgX base transform, and the range
+/// of its log encoding in stops around middle grey.
+const AGX_INSET: Matrix3 = [
+    [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+    [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+    [0.0423756549057051, 0.0784336, 0.879142973793104]];
+const AGX_OUTSET: Matrix3 = [
+    [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+    [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+    [-0.0529716355144438, -0.0980434501171241, 1.15107367264116]];
+const AGX_MIN_EV: f64 = -12.47393;
+const AGX_MAX_EV: f64 = 4.026069;
+
+/// A curve compressing high dynamic range, linear light colors into the
+/// displayable range of zero to one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToneMapOperator {
+    /// Reinhard's operator, L / (1 + L), applied to luminance.
+    Reinhard,
+
+    /// Reinhard's extended operator, applied to luminance, which maps the
+    /// given white luminance to one instead of only approaching it.
+    ReinhardExtended {
+        /// The smallest luminance mapped to white
+        white: f64
+    },
+
+    /// John Hable's filmic curve from Uncharted 2, applied to each channel,
+    /// with a linear white point of 11.2. The original applied one stop of
+    /// exposure before the curve.
+    Hable,
+
+    /// Stephen Hill's fit of the ACES reference rendering and sRGB output
+    /// transforms.
+    AcesFitted,
+
+    /// The AgX base transform, using the common polynomial approximation of
+    /// its sigmoid. Bright, saturated colors desaturate towards white.
+    Agx,
+
+    /// The ITU-R BT.2390 EETF, which compresses highlights in the PQ domain
+    /// to fit a display with a lower peak luminance than the content. A
+    /// linear component of one is `source_peak` cd/m² on input and
+    /// `target_peak` cd/m² on output. Luminance is left unchanged when the
+    /// target peak is at least the source peak.
+    Bt2390 {
+        /// The peak luminance of the content, in cd/m²
+        source_peak: f64,
+
+        /// The peak luminance of the target display, in cd/m²
+        target_peak: f64
+    }
+}
+
+impl ToneMapOperator {
+    /// Tone map a linear light color in double precision.
+    fn map(self, c: [f64; 3]) -> [f64; 3] {
+        match self {
+            ToneMapOperator::Reinhard => scale_luminance(c, |l| l / (1.0 + l)),
+            ToneMapOperator::ReinhardExtended { white } => {
+                scale_luminance(c, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            ToneMapOperator::Hable => {
+                let white = hable(HABLE_WHITE);
+                [hable(c[0]) / white, hable(c[1]) / white, hable(c[2]) / white]
+            }
+            ToneMapOperator::AcesFitted => {
+                let v = mat3_apply(&ACES_INPUT, c);
+                let fit = |v: f64| (v * (v + 0.0245786) - 0.000090537) /
+                                   (v * (0.983729 * v + 0.4329510) + 0.238081);
+                mat3_apply(&ACES_OUTPUT, [fit(v[0]), fit(v[1]), fit(v[2])])
+            }
+            ToneMapOperator::Agx => {
+                let v = mat3_apply(&AGX_INSET, c);
+                let v = mat3_apply(&AGX_OUTSET, [agx(v[0]), agx(v[1]), agx(v[2])]);
+                [v[0].max(0.0).powf(2.2), v[1].max(0.0).powf(2.2), v[2].max(0.0).powf(2.2)]
+            }
+            ToneMapOperator::Bt2390 { source_peak, target_peak } => {
+                let eetf = |x: f64| bt2390_eetf(x * source_peak, source_peak, target_peak) / target_peak;
+                [eetf(c[0]), eetf(c[1]), eetf(c[2])]
+            }
+        }
+    }
+}
+
+/// Applies a tone mapping operator to linear light colors, after scaling
+/// them by an exposure.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ToneMapper {
+    /// The tone mapping curve
+    operator: ToneMapOperator,
+
+    /// Exposure in stops
+    exposure: f64
+}
+
+impl ToneMapper {
+    /// Create a tone mapper using the given operator, with no exposure
+    /// adjustment.
+    pub fn new(operator: ToneMapOperator) -> ToneMapper {
+        ToneMapper {
+            operator: operator,
+            exposure: 0.0
+        }
+    }
+
+    /// Returns the tone mapping operator.
+    #[inline]
+    pub fn operator(&self) -> ToneMapOperator { self.operator }
+
+    /// Returns the exposure, in stops.
+    #[inline]
+    pub fn exposure(&self) -> f64 { self.exposure }
+
+    /// Set the exposure in stops. Colors are multiplied by two to the power
+    /// of the exposure before tone mapping.
+    #[inline]
+    pub fn set_exposure(&mut self, exposure: f64) { self.exposure = exposure; }
+
+    /// Tone map a linear light color. The result is linear light, with each
+    /// channel between zero and one; encode it with `Rgb::to_srgb` or a
+    /// `TransferFunction` for display.
+    pub fn apply<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        let scale = self.exposure.exp2();
+        let (r, g, b) = rgb.components();
+        let c = [r.to_f64().unwrap() * scale, g.to_f64().unwrap() * scale, b.to_f64().unwrap() * scale];
+        let c = self.operator.map([c[0].max(0.0), c[1].max(0.0), c[2].max(0.0)]);
+        Rgb::with_components(float::<F>(c[0]), float(c[1]), float(c[2])).normalise()
+    }
+
+    /// Tone map a buffer of linear light colors in place.
+    pub fn apply_in_place<F: Channel + Float>(&self, pixels: &mut [Rgb<F>]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+}
+
+/// Scale a color so that its luminance follows a tone curve.
+fn scale_luminance<M: Fn(f64) -> f64>(c: [f64; 3], curve: M) -> [f64; 3] {
+    let luminance = LUMINANCE[0] * c[0] + LUMINANCE[1] * c[1] + LUMINANCE[2] * c[2];
+    if luminance <= 0.0 {
+        return [0.0; 3];
+    }
+    let scale = curve(luminance) / luminance;
+    [c[0] * scale, c[1] * scale, c[2] * scale]
+}
+
+/// Hable's filmic curve, before normalisation by the white point.
+fn hable(x: f64) -> f64 {
+    (x * (HABLE_A * x + HABLE_C * HABLE_B) + HABLE_D * HABLE_E) /
+    (x * (HABLE_A * x + HABLE_B) + HABLE_D * HABLE_F) - HABLE_E / HABLE_F
+}
+
+/// The log encoding and sigmoid of AgX for one channel.
+fn agx(x: f64) -> f64 {
+    let x = clamp(x.max(1e-10).log2(), AGX_MIN_EV, AGX_MAX_EV);
+    let x = (x - AGX_MIN_EV) / (AGX_MAX_EV - AGX_MIN_EV);
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232
+}
+
+/// The BT.2390 EETF, mapping a luminance in cd/m² on a mastering display
+/// with the given peak to a display with the target peak. Both displays
+/// are assumed to have a black level of zero. There is nothing to compress
+/// when the target peak is at least the source peak, so the luminance is
+/// returned unchanged.
+fn bt2390_eetf(luminance: f64, source_peak: f64, target_peak: f64) -> f64 {
+    if target_peak >= source_peak {
+        return luminance;
+    }
+    let (black, white) = (pq_inverse_eotf(0.0), pq_inverse_eotf(source_peak));
+    let normalise = |l: f64| (pq_inverse_eotf(l) - black) / (white - black);
+    let e1 = normalise(luminance.min(source_peak));
+    let max_luminance = normalise(target_peak);
+    let knee = 1.5 * max_luminance - 0.5;
+
+    let e2 = if e1 < knee {
+        e1
+    } else {
+        let t = (e1 - knee) / (1.0 - knee);
+        let (t2, t3) = (t * t, t * t * t);
+        (2.0 * t3 - 3.0 * t2 + 1.0) * knee + (t3 - 2.0 * t2 + t) * (1.0 - knee) +
+        (-2.0 * t3 + 3.0 * t2) * max_luminance
+    };
+    pq_eotf(e2 * (white - black) + black)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+    use ::test::is_between;
+
+    const OPERATORS: [ToneMapOperator; 6] = [
+        ToneMapOperator::Reinhard, ToneMapOperator::ReinhardExtended { white: 4.0 },
+        ToneMapOperator::Hable, ToneMapOperator::AcesFitted, ToneMapOperator::Agx,
+        ToneMapOperator::Bt2390 { source_peak: 4000.0, target_peak: 1000.0 }];
+
+    fn grey(x: f64) -> Rgb<f64> {
+        Rgb::with_components(x, x, x)
+    }
+
+    #[test]
+    fn operators_are_monotonic_and_in_range() {
+        for &operator in OPERATORS.iter() {
+            let mapper = ToneMapper::new(operator);
+            let mut last = -1.0;
+            for i in 0..200 {
+                let out = mapper.apply(&grey(i as f64 * 0.05)).r();
+                assert!(out >= last - 1e-12, "{:?} at {}", operator, i);
+                assert!(is_between(out, 0.0, 1.0));
+                last = out;
+            }
+        }
+    }
+
+    #[test]
+    fn reinhard_curves() {
+        let reinhard = ToneMapper::new(ToneMapOperator::Reinhard);
+        assert!((reinhard.apply(&grey(1.0)).g() - 0.5).abs() < 1e-12);
+        let extended = ToneMapper::new(ToneMapOperator::ReinhardExtended { white: 4.0 });
+        assert!((extended.apply(&grey(4.0)).g() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hable_maps_white_point_to_one() {
+        let hable = ToneMapper::new(ToneMapOperator::Hable);
+        assert!((hable.apply(&grey(11.2)).r() - 1.0).abs() < 1e-12);
+        assert_eq!(hable.apply(&grey(0.0)).r(), 0.0);
+    }
+
+    #[test]
+    fn aces_fitted_reference_values() {
+        let aces = ToneMapper::new(ToneMapOperator::AcesFitted);
+        assert!(aces.apply(&grey(0.0)).r() < 1e-3);
+        assert!((aces.apply(&grey(0.18)).r() - 0.1056).abs() < 1e-3);
+        assert!(aces.apply(&grey(100.0)).r() > 0.99);
+    }
+
+    #[test]
+    fn exposure_scales_input_by_stops() {
+        let mut mapper = ToneMapper::new(ToneMapOperator::Reinhard);
+        mapper.set_exposure(2.0);
+        let mut pixels = [grey(0.25), grey(0.0)];
+        mapper.apply_in_place(&mut pixels);
+        assert!((pixels[0].b() - 0.5).abs() < 1e-12);
+        assert_eq!(pixels[1].b(), 0.0);
+    }
+
+    #[test]
+    fn bt2390_leaves_low_luminance_unchanged() {
+        let eetf = ToneMapper::new(ToneMapOperator::Bt2390 { source_peak: 4000.0, target_peak: 1000.0 });
+        // 100 cd/m² is well below the knee, and 4000 cd/m² reaches the target peak.
+        assert!((eetf.apply(&grey(100.0 / 4000.0)).r() - 0.1).abs() < 1e-6);
+        assert!((eetf.apply(&grey(1.0)).r() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bt2390_with_equal_peaks_keeps_peak_white() {
+        let eetf = ToneMapper::new(ToneMapOperator::Bt2390 { source_peak: 1000.0, target_peak: 1000.0 });
+        let mapped = eetf.apply(&Rgb::with_components(1.0, 0.5, 2.0));
+        assert_eq!(mapped.components(), (1.0, 0.5, 1.0));
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, linear_to_srgb, srgb_to_linear, Channel, Rgb};
+
+/// The curve relating the encoded components of an RGB color space to
+/// linear light. Negative values are mirrored around zero, so that out of
+/// gamut colors survive a round trip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferFunction {
+    /// No encoding: the components are linear light.
+    Linear,
+
+    /// The piecewise sRGB curve (IEC 61966-2-1), also used by Display P3.
+    Srgb,
+
+    /// A pure power law with the given exponent, such as 2.2 or the
+    /// 563/256 of Adobe RGB. Decoding raises to the exponent.
+    Gamma(f64),
+
+    /// The camera curve of ITU-R BT.709 and BT.2020.
+    Rec709,
+
+    /// The curve of ProPhoto RGB (ROMM RGB), a 1.8 power law with a
+    /// linear segment near black.
+    ProPhoto,
+
+    /// The SMPTE ST 2084 perceptual quantizer used by HDR10. A linear
+    /// component of one corresponds to `reference` cd/m², so a reference of
+    /// 10000 uses the full range of the curve and a reference of 203 maps
+    /// one to HDR reference white.
+    Pq {
+        /// The luminance of a linear component of one, in cd/m²
+        reference: f64
+    },
+
+    /// The ARIB STD-B67 hybrid log-gamma OETF, relating the signal to
+    /// scene linear light between zero and one. Use `hlg_eotf` to include
+    /// the display's system gamma.
+    Hlg,
+
+    /// The ITU-R BT.1886 reference display EOTF, for a display with the
+    /// given white and black luminance in cd/m². A linear component of one
+    /// corresponds to the display's white, and a signal of zero decodes to
+    /// the display's black rather than zero.
+    Bt1886 {
+        /// The luminance of the display's white, in cd/m²
+        white: f64,
+
+        /// The luminance of the display's black, in cd/m²
+        black: f64
+    }
+}
+
+/// The constants of the BT.709 and BT.2020 curve, at full precision.
+const REC709_ALPHA: f64 = 1.09929682680944;
+const REC709_BETA: f64 = 0.018053968510807;
+
+/// The constants of the PQ curve, from SMPTE ST 2084.
+const PQ_M1: f64 = 2610.0 / 16384.0;
+const PQ_M2: f64 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f64 = 3424.0 / 4096.0;
+const PQ_C2: f64 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f64 = 2392.0 / 4096.0 * 32.0;
+
+/// The constants of the HLG curve, from ITU-R BT.2100.
+const HLG_A: f64 = 0.17883277;
+const HLG_B: f64 = 0.28466892;
+const HLG_C: f64 = 0.55991073;
+
+/// The luminance coefficients of BT.2020, used by the HLG OOTF.
+const BT2020_LUMINANCE: [f64; 3] = [0.2627, 0.6780, 0.0593];
+
+impl TransferFunction {
+    /// Convert an encoded component to linear light.
+    pub fn decode(self, x: f64) -> f64 {
+        let magnitude = x.abs();
+        let linear = match self {
+            TransferFunction::Linear => magnitude,
+            TransferFunction::Srgb => srgb_to_linear(magnitude),
+            TransferFunction::Gamma(gamma) => magnitude.powf(gamma),
+            TransferFunction::Rec709 => if magnitude < 4.5 * REC709_BETA {
+                magnitude / 4.5
+            } else {
+                ((magnitude + REC709_ALPHA - 1.0) / REC709_ALPHA).powf(1.0 / 0.45)
+            },
+            TransferFunction::ProPhoto => if magnitude < 16.0 / 512.0 {
+                magnitude / 16.0
+            } else {
+                magnitude.powf(1.8)
+            },
+            TransferFunction::Pq { reference } => pq_eotf(magnitude) / reference,
+            TransferFunction::Hlg => hlg_inverse_oetf(magnitude),
+            TransferFunction::Bt1886 { white, black } => bt1886_eotf(magnitude, white, black) / white
+        };
+        if x < 0.0 { -linear } else { linear }
+    }
+
+    /// Convert a linear light component to its encoded value.
+    pub fn encode(self, x: f64) -> f64 {
+        let magnitude = x.abs();
+        let encoded = match self {
+            TransferFunction::Linear => magnitude,
+            TransferFunction::Srgb => linear_to_srgb(magnitude),
+            TransferFunction::Gamma(gamma) => magnitude.powf(1.0 / gamma),
+            TransferFunction::Rec709 => if magnitude < REC709_BETA {
+                magnitude * 4.5
+            } else {
+                REC709_ALPHA * magnitude.powf(0.45) - (REC709_ALPHA - 1.0)
+            },
+            TransferFunction::ProPhoto => if magnitude < 1.0 / 512.0 {
+                magnitude * 16.0
+            } else {
+                magnitude.powf(1.0 / 1.8)
+            },
+            TransferFunction::Pq { reference } => pq_inverse_eotf(magnitude * reference),
+            TransferFunction::Hlg => hlg_oetf(magnitude),
+            TransferFunction::Bt1886 { white, black } => {
+                bt1886_inverse_eotf(magnitude * white, white, black)
+            }
+        };
+        if x < 0.0 { -encoded } else { encoded }
+    }
+}
+
+/// The PQ EOTF: convert a signal between zero and one to a display
+/// luminance in cd/m², between zero and 10000.
+pub fn pq_eotf(signal: f64) -> f64 {
+    let e = signal.max(0.0).powf(1.0 / PQ_M2);
+    10000.0 * ((e - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * e)).powf(1.0 / PQ_M1)
+}
+
+/// The inverse of the PQ EOTF: convert a display luminance in cd/m² to a
+/// signal between zero and one.
+pub fn pq_inverse_eotf(luminance: f64) -> f64 {
+    let y = (luminance / 10000.0).max(0.0).powf(PQ_M1);
+    ((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y)).powf(PQ_M2)
+}
+
+/// The HLG OETF: convert scene linear light between zero and one to a
+/// signal between zero and one.
+pub fn hlg_oetf(scene: f64) -> f64 {
+    let scene = scene.max(0.0);
+    if scene <= 1.0 / 12.0 {
+        (3.0 * scene).sqrt()
+    } else {
+        HLG_A * (12.0 * scene - HLG_B).ln() + HLG_C
+    }
+}
+
+/// The inverse of the HLG OETF: convert a signal between zero and one to
+/// scene linear light.
+pub fn hlg_inverse_oetf(signal: f64) -> f64 {
+    let signal = signal.max(0.0);
+    if signal <= 0.5 {
+        signal * signal / 3.0
+    } else {
+        (((signal - HLG_C) / HLG_A).exp() + HLG_B) / 12.0
+    }
+}
+
+/// The nominal HLG system gamma for a display of the given peak luminance
+/// in cd/m², which is 1.2 at 1000 cd/m².
+pub fn hlg_system_gamma(peak: f64) -> f64 {
+    1.2 + 0.42 * (peak / 1000.0).log10()
+}
+
+/// The HLG EOTF: convert an HLG encoded color to display light in cd/m²
+/// for a display of the given peak luminance, applying the system gamma to
+/// the luminance of the scene.
+pub fn hlg_eotf<F: Channel + Float>(signal: &Rgb<F>, peak: f64, gamma: f64) -> Rgb<F> {
+    let scene = [hlg_inverse_oetf(to_f64(signal.r())),
+                 hlg_inverse_oetf(to_f64(signal.g())),
+                 hlg_inverse_oetf(to_f64(signal.b()))];
+    let luminance = bt2020_luminance(&scene);
+    let scale = if luminance > 0.0 { peak * luminance.powf(gamma - 1.0) } else { 0.0 };
+    Rgb::with_components(float(scene[0] * scale), float(scene[1] * scale), float(scene[2] * scale))
+}
+
+/// The inverse of the HLG EOTF: convert display light in cd/m² to an HLG
+/// encoded color, for a display of the given peak luminance and system
+/// gamma.
+pub fn hlg_inverse_eotf<F: Channel + Float>(display: &Rgb<F>, peak: f64, gamma: f64) -> Rgb<F> {
+    let display = [to_f64(display.r()), to_f64(display.g()), to_f64(display.b())];
+    let luminance = bt2020_luminance(&display) / peak;
+    let scale = if luminance > 0.0 {
+        luminance.powf((1.0 - gamma) / gamma) / peak
+    } else {
+        0.0
+    };
+    Rgb::with_components(float(hlg_oetf(display[0] * scale)),
+                         float(hlg_oetf(display[1] * scale)),
+                         float(hlg_oetf(display[2] * scale)))
+}
+
+/// The BT.1886 EOTF: convert a signal between zero and one to the
+/// luminance in cd/m² of a display with the given white and black levels.
+pub fn bt1886_eotf(signal: f64, white: f64, black: f64) -> f64 {
+    let (a, b) = bt1886_parameters(white, black);
+    a * (signal + b).max(0.0).powf(2.4)
+}
+
+/// The inverse of the BT.1886 EOTF: convert a display luminance in cd/m²
+/// to a signal.
+pub fn bt1886_inverse_eotf(luminance: f64, white: f64, black: f64) -> f64 {
+    let (a, b) = bt1886_parameters(white, black);
+    (luminance.max(0.0) / a).powf(1.0 / 2.4) - b
+}
+
+/// The gain and black lift of the BT.1886 EOTF.
+fn bt1886_parameters(white: f64, black: f64) -> (f64, f64) {
+    let (white, black) = (white.powf(1.0 / 2.4), black.powf(1.0 / 2.4));
+    ((white - black).powf(2.4), black / (white - black))
+}
+
+/// The BT.2020 luminance of linear components.
+#[inline]
+fn bt2020_luminance(c: &[f64; 3]) -> f64 {
+    BT2020_LUMINANCE[0] * c[0] + BT2020_LUMINANCE[1] * c[1] + BT2020_LUMINANCE[2] * c[2]
+}
+
+/// Convert a generic floating point value to f64.
+#[inline]
+fn to_f64<F: Float>(x: F) -> f64 {
+    x.to_f64().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use ::Rgb;
+
+    const FUNCTIONS: [TransferFunction; 9] = [
+        TransferFunction::Linear, TransferFunction::Srgb, TransferFunction::Gamma(2.2),
+        TransferFunction::Rec709, TransferFunction::ProPhoto,
+        TransferFunction::Pq { reference: 10000.0 }, TransferFunction::Pq { reference: 203.0 },
+        TransferFunction::Hlg, TransferFunction::Bt1886 { white: 100.0, black: 0.0 }];
+
+    #[test]
+    fn encoding_round_trips() {
+        for &function in FUNCTIONS.iter() {
+            for i in -10..21 {
+                let x = i as f64 / 20.0;
+                assert!((function.decode(function.encode(x)) - x).abs() < 1e-9, "{:?} {}", function, x);
+            }
+        }
+    }
+
+    #[test]
+    fn curves_are_continuous_at_the_linear_segment() {
+        let rec709 = TransferFunction::Rec709;
+        assert!((rec709.encode(REC709_BETA - 1e-12) - rec709.encode(REC709_BETA)).abs() < 1e-9);
+        let prophoto = TransferFunction::ProPhoto;
+        assert!((prophoto.encode(1.0 / 512.0 - 1e-12) - prophoto.encode(1.0 / 512.0)).abs() < 1e-9);
+        assert!((rec709.encode(1.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn pq_reference_values() {
+        assert!((pq_inverse_eotf(100.0) - 0.5081).abs() < 1e-4);
+        assert!((pq_inverse_eotf(1000.0) - 0.7518).abs() < 1e-4);
+        assert!((pq_inverse_eotf(10000.0) - 1.0).abs() < 1e-12);
+        assert!((pq_eotf(1.0) - 10000.0).abs() < 1e-6);
+        assert_eq!(pq_eotf(0.0), 0.0);
+    }
+
+    #[test]
+    fn hlg_reference_values() {
+        assert!((hlg_oetf(1.0 / 12.0) - 0.5).abs() < 1e-12);
+        assert!((hlg_oetf(1.0) - 1.0).abs() < 1e-6);
+        assert!((hlg_system_gamma(1000.0) - 1.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hlg_eotf_maps_white_to_peak() {
+        let white = hlg_eotf(&Rgb::with_components(1.0f64, 1.0, 1.0), 1000.0, 1.2);
+        assert!((white.r() - 1000.0).abs() < 1e-2 && (white.b() - 1000.0).abs() < 1e-2);
+
+        let col = Rgb::with_components(0.3f64, 0.6, 0.9);
+        let round_trip = hlg_inverse_eotf(&hlg_eotf(&col, 2000.0, hlg_system_gamma(2000.0)),
+                                          2000.0, hlg_system_gamma(2000.0));
+        assert!((round_trip.r() - 0.3).abs() < 1e-9);
+        assert!((round_trip.g() - 0.6).abs() < 1e-9);
+        assert!((round_trip.b() - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bt1886_spans_black_to_white() {
+        assert!((bt1886_eotf(0.0, 100.0, 0.1) - 0.1).abs() < 1e-9);
+        assert!((bt1886_eotf(1.0, 100.0, 0.1) - 100.0).abs() < 1e-9);
+        assert!((bt1886_eotf(0.5, 100.0, 0.0) - 100.0 * 0.5f64.powf(2.4)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,388 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp::Ordering;
+
+use num::Float;
+
+use super::{clamp, float, mat3_apply, mat3_mul, AdaptationMethod, Channel, ChromaticAdaptation,
+            Matrix3, Rgb, RgbSpace, Xyz};
+
+/// The range of correlated color temperatures covered by the Planckian
+/// locus approximation, in kelvin.
+const MIN_CCT: f64 = 1667.0;
+const MAX_CCT: f64 = 25000.0;
+
+/// Returns the xy chromaticity of a correlated color temperature in kelvin
+/// and a tint, given as the signed distance Duv from the Planckian locus in
+/// the CIE 1960 uv diagram. Positive tints lie above the locus, toward
+/// green, and negative tints toward magenta; D65 is about 6504 K with a
+/// tint of 0.0032. Temperatures are clamped between 1667 K and 25000 K, the
+/// range of the Kim et al. locus approximation.
+pub fn cct_to_chromaticity(cct: f64, tint: f64) -> (f64, f64) {
+    let cct = clamp(cct, MIN_CCT, MAX_CCT);
+    let (u, v) = planckian_uv(cct);
+    let (nu, nv) = locus_normal(cct);
+    uv_to_xy(u + tint * nu, v + tint * nv)
+}
+
+/// Returns the correlated color temperature in kelvin and the tint (Duv)
+/// of an xy chromaticity, found by searching the Planckian locus for the
+/// nearest point. The inverse of `cct_to_chromaticity`.
+pub fn chromaticity_to_cct(x: f64, y: f64) -> (f64, f64) {
+    let (u, v) = xy_to_uv(x, y);
+    let distance = |mired: f64| {
+        let (lu, lv) = planckian_uv(1e6 / mired);
+        (u - lu) * (u - lu) + (v - lv) * (v - lv)
+    };
+
+    // Golden section search over the locus, which is evenly spaced in
+    // reciprocal temperature.
+    let ratio = (5.0f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (1e6 / MAX_CCT, 1e6 / MIN_CCT);
+    for _ in 0..80 {
+        let a = high - ratio * (high - low);
+        let b = low + ratio * (high - low);
+        if distance(a) < distance(b) { high = b; } else { low = a; }
+    }
+    let cct = 2e6 / (low + high);
+    let (lu, lv) = planckian_uv(cct);
+    let (nu, nv) = locus_normal(cct);
+    (cct, (u - lu) * nu + (v - lv) * nv)
+}
+
+/// The CIE 1960 uv chromaticity of the Planckian locus, using the cubic
+/// spline approximation of Kim et al.
+fn planckian_uv(cct: f64) -> (f64, f64) {
+    let t = clamp(cct, MIN_CCT, MAX_CCT);
+    let (t1, t2, t3) = (1e3 / t, 1e6 / (t * t), 1e9 / (t * t * t));
+    let x = if t <= 4000.0 {
+        -0.2661239 * t3 - 0.2343589 * t2 + 0.8776956 * t1 + 0.179910
+    } else {
+        -3.0258469 * t3 + 2.1070379 * t2 + 0.2226347 * t1 + 0.240390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+    };
+    xy_to_uv(x, y)
+}
+
+/// The unit normal of the Planckian locus in the uv diagram, pointing
+/// toward green.
+fn locus_normal(cct: f64) -> (f64, f64) {
+    let mired = 1e6 / cct;
+    let (u0, v0) = planckian_uv(1e6 / (mired + 0.5));
+    let (u1, v1) = planckian_uv(1e6 / (mired - 0.5).max(1e6 / MAX_CCT));
+    let (du, dv) = (u1 - u0, v1 - v0);
+    let length = du.hypot(dv);
+    if du < 0.0 { (dv / length, -du / length) } else { (-dv / length, du / length) }
+}
+
+fn xy_to_uv(x: f64, y: f64) -> (f64, f64) {
+    let d = -2.0 * x + 12.0 * y + 3.0;
+    (4.0 * x / d, 6.0 * y / d)
+}
+
+fn uv_to_xy(u: f64, v: f64) -> (f64, f64) {
+    let d = 2.0 * u - 8.0 * v + 4.0;
+    (3.0 * u / d, 2.0 * v / d)
+}
+
+/// A white balance correction, which adapts colors seen under a source
+/// white to how they would appear under a target white. Colors are adapted
+/// in linear light, converting to XYZ through the matrices of an RGB space.
+///
+/// ```rust
+/// use simplecolor::{Rgb, WhiteBalance};
+///
+/// // Correct a photo shot under tungsten light with a daylight setting.
+/// let balance = WhiteBalance::new(3200.0, 0.0, 6504.0, 0.0032);
+/// let corrected = balance.apply(&Rgb::with_components(0.8f32, 0.6, 0.4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WhiteBalance {
+    /// xy chromaticity of the source white
+    source: (f64, f64),
+
+    /// xy chromaticity of the target white
+    target: (f64, f64),
+
+    /// Adaptation method
+    method: AdaptationMethod,
+
+    /// Space of the colors being balanced
+    space: RgbSpace,
+
+    /// Linear RGB to linear RGB matrix
+    matrix: Matrix3
+}
+
+impl WhiteBalance {
+    /// Create a correction from one correlated color temperature and tint
+    /// to another, for sRGB colors. See `cct_to_chromaticity` for the
+    /// meaning of the tint.
+    pub fn new(source_cct: f64, source_tint: f64, target_cct: f64, target_tint: f64) -> WhiteBalance {
+        WhiteBalance::from_chromaticity(cct_to_chromaticity(source_cct, source_tint),
+                                        cct_to_chromaticity(target_cct, target_tint))
+    }
+
+    /// Create a correction between two xy chromaticities, for sRGB colors.
+    pub fn from_chromaticity(source: (f64, f64), target: (f64, f64)) -> WhiteBalance {
+        let mut balance = WhiteBalance {
+            source: source,
+            target: target,
+            method: AdaptationMethod::Bradford,
+            space: RgbSpace::srgb(),
+            matrix: [[0.0; 3]; 3]
+        };
+        balance.update();
+        balance
+    }
+
+    /// Create a correction which makes the given color, encoded in an RGB
+    /// space, neutral. This is the eyedropper tool of photo editors.
+    pub fn neutralize<F: Channel + Float>(white: &Rgb<F>, space: &RgbSpace) -> WhiteBalance {
+        let linear = space.to_linear(white);
+        let (r, g, b) = linear.components();
+        WhiteBalance::from_linear_white([r.to_f64().unwrap(), g.to_f64().unwrap(), b.to_f64().unwrap()],
+                                        space)
+    }
+
+    /// Create a correction by estimating the illuminant with the gray
+    /// world assumption: that the average of a scene, in linear light, is
+    /// neutral.
+    pub fn gray_world<F: Channel + Float>(pixels: &[Rgb<F>], space: &RgbSpace) -> WhiteBalance {
+        let mut sum = [0.0; 3];
+        for pixel in pixels {
+            let linear = linear_vector(pixel, space);
+            for i in 0..3 {
+                sum[i] += linear[i];
+            }
+        }
+        WhiteBalance::from_linear_white(sum, space)
+    }
+
+    /// Create a correction by estimating the illuminant with the white
+    /// patch assumption: that the brightest values of each channel belong
+    /// to a white surface. Each channel's value at the given percentile,
+    /// between zero and one, is taken as its white, so that a percentile
+    /// slightly below one ignores specular highlights and noise.
+    pub fn white_patch<F: Channel + Float>(pixels: &[Rgb<F>], space: &RgbSpace,
+                                           percentile: f64) -> WhiteBalance {
+        let mut white = [0.0; 3];
+        if !pixels.is_empty() {
+            let linear: Vec<[f64; 3]> = pixels.iter().map(|pixel| linear_vector(pixel, space)).collect();
+            let index = (clamp(percentile, 0.0, 1.0) * (pixels.len() - 1) as f64).round() as usize;
+            for (i, w) in white.iter_mut().enumerate() {
+                let mut channel: Vec<f64> = linear.iter().map(|c| c[i]).collect();
+                channel.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                *w = channel[index];
+            }
+        }
+        WhiteBalance::from_linear_white(white, space)
+    }
+
+    /// Create a correction from an estimated white in linear light to the
+    /// white point of the space. A white without positive luminance gives
+    /// no correction.
+    fn from_linear_white(white: [f64; 3], space: &RgbSpace) -> WhiteBalance {
+        let xyz = mat3_apply(&space.to_xyz_matrix(), white);
+        let target = space.white();
+        let source = if xyz[1] > 0.0 {
+            let sum = xyz[0] + xyz[1] + xyz[2];
+            (xyz[0] / sum, xyz[1] / sum)
+        } else {
+            target
+        };
+        let mut balance = WhiteBalance::from_chromaticity(source, target);
+        balance.set_space(*space);
+        balance
+    }
+
+    /// Returns the xy chromaticity of the source white.
+    #[inline]
+    pub fn source(&self) -> (f64, f64) { self.source }
+
+    /// Returns the xy chromaticity of the target white.
+    #[inline]
+    pub fn target(&self) -> (f64, f64) { self.target }
+
+    /// Returns the correlated color temperature and tint of the source
+    /// white, such as one found by an estimator.
+    pub fn source_temperature(&self) -> (f64, f64) {
+        chromaticity_to_cct(self.source.0, self.source.1)
+    }
+
+    /// Returns the chromatic adaptation method.
+    #[inline]
+    pub fn method(&self) -> AdaptationMethod { self.method }
+
+    /// Returns the RGB space of the colors being balanced.
+    #[inline]
+    pub fn space(&self) -> RgbSpace { self.space }
+
+    /// Set the chromatic adaptation method. The default is Bradford.
+    pub fn set_method(&mut self, method: AdaptationMethod) {
+        self.method = method;
+        self.update();
+    }
+
+    /// Set the RGB space of the colors being balanced. The default is sRGB.
+    pub fn set_space(&mut self, space: RgbSpace) {
+        self.space = space;
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let source = Xyz::from_chromaticity(self.source.0, self.source.1);
+        let target = Xyz::from_chromaticity(self.target.0, self.target.1);
+        let adaptation = ChromaticAdaptation::new(self.method, &source, &target);
+        self.matrix = mat3_mul(&self.space.from_xyz_matrix(),
+                               &mat3_mul(&adaptation.matrix(), &self.space.to_xyz_matrix()));
+    }
+
+    /// Correct a color encoded in the RGB space. Colors pushed outside of
+    /// the gamut of the space will have channels outside of [0, 1].
+    pub fn apply<F: Channel + Float>(&self, rgb: &Rgb<F>) -> Rgb<F> {
+        let c = mat3_apply(&self.matrix, linear_vector(rgb, &self.space));
+        self.space.from_linear(&Rgb::with_components(float(c[0]), float(c[1]), float(c[2])))
+    }
+
+    /// Correct a buffer of colors in place.
+    pub fn apply_in_place<F: Channel + Float>(&self, pixels: &mut [Rgb<F>]) {
+        for pixel in pixels.iter_mut() {
+            *pixel = self.apply(pixel);
+        }
+    }
+}
+
+/// The linear light components of an encoded color.
+fn linear_vector<F: Channel + Float>(rgb: &Rgb<F>, space: &RgbSpace) -> [f64; 3] {
+    let (r, g, b) = space.to_linear(rgb).components();
+    [r.to_f64().unwrap(), g.to_f64().unwrap(), b.to_f64().unwrap()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::{Rgb, RgbSpace};
+
+    fn assert_neutral(rgb: Rgb<f64>, tolerance: f64) {
+        let (r, g, b) = rgb.components();
+        assert!((r - g).abs() < tolerance && (g - b).abs() < tolerance, "{:?} is not neutral", rgb);
+    }
+
+    #[test]
+    fn planckian_chromaticities() {
+        // CIE illuminant A lies on the Planckian locus.
+        let (x, y) = cct_to_chromaticity(2856.0, 0.0);
+        assert!((x - 0.4476).abs() < 1e-3);
+        assert!((y - 0.4074).abs() < 1e-3);
+
+        let (x, y) = cct_to_chromaticity(6504.0, 0.0032);
+        assert!((x - 0.3127).abs() < 1e-3);
+        assert!((y - 0.3290).abs() < 1e-3);
+    }
+
+    #[test]
+    fn d65_temperature() {
+        let (cct, tint) = chromaticity_to_cct(0.3127, 0.3290);
+        assert!((cct - 6504.0).abs() < 30.0);
+        assert!((tint - 0.0032).abs() < 3e-4);
+    }
+
+    #[test]
+    fn temperature_round_trips() {
+        for &cct in [2000.0, 3200.0, 5000.0, 6500.0, 10000.0].iter() {
+            for &tint in [-0.01, 0.0, 0.01].iter() {
+                let (x, y) = cct_to_chromaticity(cct, tint);
+                let (c, t) = chromaticity_to_cct(x, y);
+                assert!((c - cct).abs() / cct < 1e-4, "{} became {}", cct, c);
+                assert!((t - tint).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn positive_tint_is_greener() {
+        assert!(cct_to_chromaticity(5000.0, 0.01).1 > cct_to_chromaticity(5000.0, -0.01).1);
+    }
+
+    #[test]
+    fn same_temperature_is_identity() {
+        let balance = WhiteBalance::new(4000.0, 0.0, 4000.0, 0.0);
+        let (r, g, b) = balance.apply(&Rgb::with_components(0.2f64, 0.5, 0.8)).components();
+        assert!((r - 0.2).abs() < 1e-9 && (g - 0.5).abs() < 1e-9 && (b - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn source_white_becomes_neutral() {
+        let (x, y) = cct_to_chromaticity(3000.0, 0.0);
+        let space = RgbSpace::srgb();
+        let linear = mat3_apply(&space.from_xyz_matrix(), [x / y, 1.0, (1.0 - x - y) / y]);
+        let max = linear[0].max(linear[1]).max(linear[2]);
+        let warm = space.from_linear(&Rgb::with_components(linear[0] / max, linear[1] / max, linear[2] / max));
+        let balance = WhiteBalance::new(3000.0, 0.0, 6504.0, 0.0032);
+        assert_neutral(balance.apply(&warm), 2e-3);
+        assert_neutral(WhiteBalance::neutralize(&warm, &space).apply(&warm), 1e-9);
+    }
+
+    #[test]
+    fn gray_world_neutralizes_the_average() {
+        let space = RgbSpace::srgb();
+        let scene = [(0.8, 0.2, 0.1), (0.1, 0.6, 0.3), (0.2, 0.3, 0.9), (0.5, 0.5, 0.4)];
+        let cast: Vec<Rgb<f64>> = scene.iter()
+            .map(|&(r, g, b)| space.from_linear(&Rgb::with_components(r * 1.0, g * 0.8, b * 0.5)))
+            .collect();
+        let balance = WhiteBalance::gray_world(&cast, &space);
+        assert!(balance.source_temperature().0 < 5000.0);
+
+        let mut sum = [0.0; 3];
+        for pixel in &cast {
+            let c = linear_vector(&balance.apply(pixel), &space);
+            for i in 0..3 {
+                sum[i] += c[i];
+            }
+        }
+        assert_neutral(Rgb::with_components(sum[0], sum[1], sum[2]), 1e-9);
+    }
+
+    #[test]
+    fn white_patch_neutralizes_the_brightest_values() {
+        let space = RgbSpace::display_p3();
+        let mut pixels: Vec<Rgb<f32>> = (0..99).map(|i| {
+            let v = i as f32 / 200.0;
+            Rgb::with_components(v, v * 0.9, v * 0.7)
+        }).collect();
+        let white = Rgb::with_components(0.9f32, 0.8, 0.6);
+        pixels.push(white);
+        let balance = WhiteBalance::white_patch(&pixels, &space, 1.0);
+        assert_eq!(balance.space(), space);
+        let (r, g, b) = balance.apply(&white).components();
+        assert!((r - g).abs() < 1e-5 && (g - b).abs() < 1e-5);
+
+        let unchanged = WhiteBalance::white_patch::<f32>(&[], &space, 0.99);
+        assert_eq!(unchanged.source(), unchanged.target());
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::Float;
+
+use super::{float, Channel, Rgb};
+
+/// A color in the CIE 1931 XYZ color space. The Y component is the
+/// luminance of the color, with a reference white having a Y of one.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Xyz<F: Float> {
+    /// X component
+    x: F,
+
+    /// Y component
+    y: F,
+
+    /// Z component
+    z: F
+}
+
+impl<F: Float> Xyz<F> {
+    /// Construct an Xyz color piecewise from individual components.
+    pub fn with_components(x: F, y: F, z: F) -> Xyz<F> {
+        Xyz {
+            x: x,
+            y: y,
+            z: z
+        }
+    }
+
+    /// Construct the Xyz color with the given xy chromaticity and a Y of one,
+    /// such as a white point.
+    pub fn from_chromaticity(x: F, y: F) -> Xyz<F> {
+        Xyz::with_components(x / y, F::one(), (F::one() - x - y) / y)
+    }
+
+    /// The CIE standard illuminant D65, used as the white point of sRGB.
+    pub fn d65() -> Xyz<F> {
+        Xyz::with_components(float(0.95047), F::one(), float(1.08883))
+    }
+
+    /// The CIE standard illuminant D50, used as the white point of print
+    /// and ICC workflows.
+    pub fn d50() -> Xyz<F> {
+        Xyz::with_components(float(0.96422), F::one(), float(0.82521))
+    }
+
+    /// Returns the X component.
+    #[inline]
+    pub fn x(&self) -> F { self.x }
+
+    /// Returns the Y (luminance) component.
+    #[inline]
+    pub fn y(&self) -> F { self.y }
+
+    /// Returns the Z component.
+    #[inline]
+    pub fn z(&self) -> F { self.z }
+
+    /// Return each component in a 3-element tuple. Useful for destructuring.
+    pub fn components(&self) -> (F, F, F) {
+        (self.x, self.y, self.z)
+    }
+
+    /// Returns the xy chromaticity coordinates of the color.
+    pub fn chromaticity(&self) -> (F, F) {
+        let sum = self.x + self.y + self.z;
+        (self.x / sum, self.y / sum)
+    }
+}
+
+impl<F: Channel + Float> Xyz<F> {
+    /// Convert an sRGB encoded color to Xyz, relative to the D65 white point.
+    pub fn from_rgb(rgb: &Rgb<F>) -> Xyz<F> {
+        Xyz::from_linear_rgb(&rgb.to_linear())
+    }
+
+    /// Convert a linear light sRGB color to Xyz, relative to the D65 white point.
+    pub fn from_linear_rgb(rgb: &Rgb<F>) -> Xyz<F> {
+        let (r, g, b) = rgb.components();
+        Xyz::with_components(
+            r * float(0.4124564) + g * float(0.3575761) + b * float(0.1804375),
+            r * float(0.2126729) + g * float(0.7151522) + b * float(0.0721750),
+            r * float(0.0193339) + g * float(0.1191920) + b * float(0.9503041))
+    }
+
+    /// Convert this color to an sRGB encoded color. Colors outside of the
+    /// sRGB gamut will have channels outside of [0, 1].
+    pub fn to_rgb(&self) -> Rgb<F> {
+        self.to_linear_rgb().to_srgb()
+    }
+
+    /// Convert this color to a linear light sRGB color.
+    pub fn to_linear_rgb(&self) -> Rgb<F> {
+        let (x, y, z) = self.components();
+        Rgb::with_components(
+            x * float(3.2404542) - y * float(1.5371385) - z * float(0.4985314),
+            y * float(1.8760108) - x * float(0.9692660) + z * float(0.0415560),
+            x * float(0.0556434) - y * float(0.2040259) + z * float(1.0572252))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    #[test]
+    fn white_converts_to_d65() {
+        let white = Xyz::from_rgb(&Rgb::with_components(1.0f64, 1.0, 1.0));
+        let d65 = Xyz::<f64>::d65();
+        assert!((white.x() - d65.x()).abs() < 1e-4);
+        assert!((white.y() - d65.y()).abs() < 1e-4);
+        assert!((white.z() - d65.z()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn round_trip_through_rgb() {
+        let col = Rgb::with_components(0.2f64, 0.5, 0.8);
+        let (r, g, b) = Xyz::from_rgb(&col).to_rgb().components();
+        assert!((r - 0.2).abs() < 1e-6);
+        assert!((g - 0.5).abs() < 1e-6);
+        assert!((b - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn chromaticity_round_trip() {
+        let (x, y) = Xyz::<f64>::d50().chromaticity();
+        assert!((x - 0.3457).abs() < 1e-4 && (y - 0.3585).abs() < 1e-4);
+        let (x, y) = Xyz::from_chromaticity(0.3127f64, 0.329).chromaticity();
+        assert!((x - 0.3127).abs() < 1e-12 && (y - 0.329).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright (c) 2015 George Burton
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation the
+// rights to use, copy, modify, merge, publish, distribute, sublicense, and/or
+// sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+// FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+// COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN
+// AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use num::NumCast;
+use num::traits::cast;
+
+use super::{clamp, Channel, Color, Rgb};
+
+/// The luma coefficients used to derive Y'CbCr from R'G'B'.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum YCbCrMatrix {
+    /// ITU-R BT.601, used by standard definition video and JPEG.
+    Bt601,
+
+    /// ITU-R BT.709, used by high definition video.
+    Bt709,
+
+    /// ITU-R BT.2020 non-constant luminance, used by UHD and HDR video.
+    Bt2020
+}
+
+impl YCbCrMatrix {
+    /// Returns the red and blue luma coefficients, Kr and Kb. The green
+    /// coefficient is 1 - Kr - Kb.
+    pub fn coefficients(self) -> (f64, f64) {
+        match self {
+            YCbCrMatrix::Bt601 => (0.299, 0.114),
+            YCbCrMatrix::Bt709 => (0.2126, 0.0722),
+            YCbCrMatrix::Bt2020 => (0.2627, 0.0593)
+        }
+    }
+}
+
+/// The range of code values used by integral Y'CbCr components.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum YCbCrRange {
+    /// Every code value is used, as in JPEG. Chroma is centred on half of
+    /// the range.
+    Full,
+
+    /// The studio swing range of broadcast video: at 8 bits, luma lies
+    /// between 16 and 235 and chroma between 16 and 240.
+    Limited
+}
+
+/// The matrix, range and bit depth which together describe how R'G'B'
+/// colors are stored as Y'CbCr.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct YCbCrEncoding {
+    /// Luma coefficients
+    matrix: YCbCrMatrix,
+
+    /// Code value range
+    range: YCbCrRange,
+
+    /// Bits per component
+    bit_depth: u32
+}
+
+impl YCbCrEncoding {
+    /// Create an encoding. Panics unless the bit depth is between 8 and 16.
+    pub fn new(matrix: YCbCrMatrix, range: YCbCrRange, bit_depth: u32) -> YCbCrEncoding {
+        assert!(bit_depth >= 8);
+        assert!(bit_depth <= 16);
+        YCbCrEncoding {
+            matrix: matrix,
+            range: range,
+            bit_depth: bit_depth
+        }
+    }
+
+    /// Returns the luma coefficients.
+    #[inline]
+    pub fn matrix(&self) -> YCbCrMatrix { self.matrix }
+
+    /// Returns the code value range.
+    #[inline]
+    pub fn range(&self) -> YCbCrRange { self.range }
+
+    /// Returns the number of bits per component.
+    #[inline]
+    pub fn bit_depth(&self) -> u32 { self.bit_depth }
+
+    /// Returns the scale and offset which map floating point luma and chroma
+    /// to code values, as `((luma_scale, luma_offset), (chroma_scale,
+    /// chroma_offset))`.
+    pub fn quantization(&self) -> ((f64, f64), (f64, f64)) {
+        let n = self.bit_depth as i32;
+        match self.range {
+            YCbCrRange::Full => {
+                let max = 2.0f64.powi(n) - 1.0;
+                ((max, 0.0), (max, 2.0f64.powi(n - 1)))
+            }
+            YCbCrRange::Limited => {
+                let scale = 2.0f64.powi(n - 8);
+                ((219.0 * scale, 16.0 * scale), (224.0 * scale, 128.0 * scale))
+            }
+        }
+    }
+}
+
+/// A color stored as luma (Y') and blue and red difference chroma (Cb and
+/// Cr) components, as used by video and JPEG.
+///
+/// For unsigned integral channels the components are code values at the bit
+/// depth of a `YCbCrEncoding`, so a 10 bit `YCbCr<u16>` holds values up to
+/// 1023. For floating point channels luma lies between 0 and 1 and chroma
+/// between -0.5 and 0.5, and the range and bit depth of the encoding are
+/// not used.
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub struct YCbCr<T: Channel> {
+    /// Luma component
+    y: T,
+
+    /// Blue difference component
+    cb: T,
+
+    /// Red difference component
+    cr: T
+}
+
+impl<T: Channel> YCbCr<T> {
+    /// Construct a YCbCr color piecewise from individual components.
+    pub const fn with_components(y: T, cb: T, cr: T) -> YCbCr<T> {
+        YCbCr {
+            y: y,
+            cb: cb,
+            cr: cr
+        }
+    }
+
+    /// Returns the luma component.
+    #[inline]
+    pub const fn y(&self) -> T { self.y }
+
+    /// Returns the blue difference component.
+    #[inline]
+    pub const fn cb(&self) -> T { self.cb }
+
+    /// Returns the red difference component.
+    #[inline]
+    pub const fn cr(&self) -> T { self.cr }
+
+    /// Return each component in a 3-element tuple. Useful for destructuring.
+    pub const fn components(&self) -> (T, T, T) {
+        (self.y, self.cb, self.cr)
+    }
+}
+
+impl<T: Channel + NumCast> YCbCr<T> {
+    /// Convert an R'G'B' color to Y'CbCr with the given encoding. Panics if
+    /// `T` is integral and too narrow for the encoding's bit depth.
+    ///
+    /// ```rust
+    /// use simplecolor::{Rgb, YCbCr, YCbCrEncoding, YCbCrMatrix, YCbCrRange};
+    ///
+    /// let encoding = YCbCrEncoding::new(YCbCrMatrix::Bt709, YCbCrRange::Limited, 8);
+    /// let red: YCbCr<u8> = YCbCr::from_rgb(&Rgb::with_components(255u8, 0, 0), &encoding);
+    /// assert_eq!(red.components(), (63, 102, 240));
+    /// ```
+    pub fn from_rgb<U: Channel>(rgb: &Rgb<U>, encoding: &YCbCrEncoding) -> YCbCr<T> {
+        let (kr, kb) = encoding.matrix.coefficients();
+        let (r, g, b) = (rgb.r().to_float::<f64>(), rgb.g().to_float::<f64>(), rgb.b().to_float::<f64>());
+        let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+        let cb = (b - y) / (2.0 * (1.0 - kb));
+        let cr = (r - y) / (2.0 * (1.0 - kr));
+
+        if !is_integral::<T>() {
+            return YCbCr::with_components(cast(y).unwrap(), cast(cb).unwrap(), cast(cr).unwrap());
+        }
+        assert_fits::<T>(encoding);
+        let ((y_scale, y_offset), (c_scale, c_offset)) = encoding.quantization();
+        let max = 2.0f64.powi(encoding.bit_depth as i32) - 1.0;
+        let code = |x: f64| -> T { cast(clamp(x.round(), 0.0, max)).unwrap() };
+        YCbCr::with_components(
+            code(y * y_scale + y_offset),
+            code(cb * c_scale + c_offset),
+            code(cr * c_scale + c_offset))
+    }
+
+    /// Convert this color to R'G'B' with the given encoding. Colors outside
+    /// of the R'G'B' cube, such as super-whites, are clamped. Panics if `T`
+    /// is integral and too narrow for the encoding's bit depth.
+    pub fn to_rgb<U: Channel>(&self, encoding: &YCbCrEncoding) -> Rgb<U> {
+        let (y, cb, cr): (f64, f64, f64) =
+            (cast(self.y).unwrap(), cast(self.cb).unwrap(), cast(self.cr).unwrap());
+        let (y, cb, cr) = if is_integral::<T>() {
+            assert_fits::<T>(encoding);
+            let ((y_scale, y_offset), (c_scale, c_offset)) = encoding.quantization();
+            ((y - y_offset) / y_scale, (cb - c_offset) / c_scale, (cr - c_offset) / c_scale)
+        } else {
+            (y, cb, cr)
+        };
+
+        let (kr, kb) = encoding.matrix.coefficients();
+        let r = y + 2.0 * (1.0 - kr) * cr;
+        let b = y + 2.0 * (1.0 - kb) * cb;
+        let g = (y - kr * r - kb * b) / (1.0 - kr - kb);
+        let unit = |x: f64| U::from_float(clamp(x, 0.0, 1.0));
+        Rgb::with_components(unit(r), unit(g), unit(b)).normalise()
+    }
+}
+
+/// Returns true if the channel type is integral, rather than having a full
+/// scale of one.
+#[inline]
+fn is_integral<T: Channel>() -> bool {
+    T::from_float(1.0f64) != T::one()
+}
+
+/// Panic unless an integral channel type can hold the code values of an
+/// encoding.
+fn assert_fits<T: Channel + NumCast>(encoding: &YCbCrEncoding) {
+    let largest: f64 = cast(T::zero().inverted()).unwrap();
+    assert!(largest >= 2.0f64.powi(encoding.bit_depth as i32) - 1.0,
+            "channel type is too narrow for a bit depth of {}", encoding.bit_depth);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::Rgb;
+
+    fn encoding(matrix: YCbCrMatrix, range: YCbCrRange, bit_depth: u32) -> YCbCrEncoding {
+        YCbCrEncoding::new(matrix, range, bit_depth)
+    }
+
+    #[test]
+    fn limited_range_8_bit_reference_values() {
+        let bt709 = encoding(YCbCrMatrix::Bt709, YCbCrRange::Limited, 8);
+        let bt601 = encoding(YCbCrMatrix::Bt601, YCbCrRange::Limited, 8);
+        let cases = [(bt709, (255u8, 255u8, 255u8), (235u8, 128u8, 128u8)),
+                     (bt709, (0, 0, 0), (16, 128, 128)),
+                     (bt709, (255, 0, 0), (63, 102, 240)),
+                     (bt709, (0, 255, 0), (173, 42, 26)),
+                     (bt709, (0, 0, 255), (32, 240, 118)),
+                     (bt601, (255, 0, 0), (81, 90, 240)),
+                     (bt601, (0, 255, 0), (145, 54, 34)),
+                     (bt601, (0, 0, 255), (41, 240, 110))];
+        for &(ref encoding, (r, g, b), expected) in cases.iter() {
+            let ycbcr: YCbCr<u8> = YCbCr::from_rgb(&Rgb::with_components(r, g, b), encoding);
+            assert_eq!(ycbcr.components(), expected);
+        }
+    }
+
+    #[test]
+    fn high_bit_depth_ranges() {
+        let white = Rgb::with_components(1.0f64, 1.0, 1.0);
+        let limited10 = encoding(YCbCrMatrix::Bt2020, YCbCrRange::Limited, 10);
+        let full12 = encoding(YCbCrMatrix::Bt2020, YCbCrRange::Full, 12);
+        assert_eq!(YCbCr::<u16>::from_rgb(&white, &limited10).components(), (940, 512, 512));
+        assert_eq!(YCbCr::<u16>::from_rgb(&Rgb::with_components(0.0f64, 0.0, 0.0), &limited10).components(),
+                   (64, 512, 512));
+        assert_eq!(YCbCr::<u16>::from_rgb(&white, &full12).components(), (4095, 2048, 2048));
+    }
+
+    #[test]
+    fn full_range_jpeg_values() {
+        let jpeg = encoding(YCbCrMatrix::Bt601, YCbCrRange::Full, 8);
+        let red: YCbCr<u8> = YCbCr::from_rgb(&Rgb::with_components(255u8, 0, 0), &jpeg);
+        assert_eq!(red.components(), (76, 85, 255));
+    }
+
+    #[test]
+    fn round_trips_through_integral_and_float_ycbcr() {
+        for &matrix in [YCbCrMatrix::Bt601, YCbCrMatrix::Bt709, YCbCrMatrix::Bt2020].iter() {
+            let encoding = encoding(matrix, YCbCrRange::Limited, 16);
+            let col = Rgb::with_components(1000u16, 40000, 65535);
+            let ycbcr: YCbCr<u16> = YCbCr::from_rgb(&col, &encoding);
+            let back: Rgb<u16> = ycbcr.to_rgb(&encoding);
+            assert!((back.r() as i32 - 1000).abs() <= 2);
+            assert!((back.g() as i32 - 40000).abs() <= 2);
+            assert!((back.b() as i32 - 65535).abs() <= 2);
+
+            let col = Rgb::with_components(0.1f64, 0.5, 0.9);
+            let ycbcr: YCbCr<f64> = YCbCr::from_rgb(&col, &encoding);
+            let (r, g, b) = ycbcr.to_rgb::<f64>(&encoding).components();
+            assert!((r - 0.1).abs() < 1e-12 && (g - 0.5).abs() < 1e-12 && (b - 0.9).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn float_chroma_is_centred_on_zero() {
+        let bt709 = encoding(YCbCrMatrix::Bt709, YCbCrRange::Full, 8);
+        let (y, cb, cr) = YCbCr::<f32>::from_rgb(&Rgb::with_components(0.5f32, 0.5, 0.5), &bt709).components();
+        assert!((y - 0.5).abs() < 1e-6 && cb.abs() < 1e-6 && cr.abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "too narrow")]
+    fn rejects_channels_narrower_than_the_bit_depth() {
+        let limited10 = encoding(YCbCrMatrix::Bt709, YCbCrRange::Limited, 10);
+        let _: YCbCr<u8> = YCbCr::from_rgb(&Rgb::with_components(0u8, 0, 0), &limited10);
+    }
+}